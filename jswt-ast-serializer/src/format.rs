@@ -0,0 +1,236 @@
+use crate::AstSerializer;
+use jswt_parser::{ParseError, Parser};
+use jswt_tokenizer::{Tokenizer, TokenizerError};
+
+/// Layout settings for formatted source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormatOptions {
+    /// Number of spaces per level of indentation
+    pub indent_width: usize,
+    /// Lists that would run past this column are broken up
+    /// with one item per line
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            line_width: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FormatError {
+    Tokenizer(TokenizerError),
+    Parse(ParseError),
+}
+
+/// Source text that isn't part of the tree but has to
+/// survive formatting, comments and imports
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Trivia {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) text: String,
+}
+
+/// Formats a source file, keeping its comments and imports.
+/// Sources that don't parse are left for the caller to report.
+pub fn format_source(
+    path: &str,
+    source: &str,
+    options: FormatOptions,
+) -> Result<String, Vec<FormatError>> {
+    let (trivia, imports) = scan(source);
+
+    // Imports are resolved by the tokenizer so they're blanked out to
+    // keep it from loading them. Offsets into the source stay the same.
+    let mut blanked = source.as_bytes().to_vec();
+    for import in imports.iter() {
+        for byte in blanked[import.start..import.end].iter_mut() {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    }
+    let blanked = String::from_utf8(blanked).unwrap();
+
+    let mut tokenizer = Tokenizer::default();
+    tokenizer.enqueue_source_str(path, blanked);
+    let mut parser = Parser::new(&mut tokenizer);
+    let ast = parser.parse();
+
+    let errors: Vec<_> = parser
+        .tokenizer_errors()
+        .into_iter()
+        .map(FormatError::Tokenizer)
+        .chain(parser.parse_errors().into_iter().map(FormatError::Parse))
+        .collect();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut trivia: Vec<_> = trivia.into_iter().chain(imports).collect();
+    trivia.sort_by_key(|trivia| trivia.start);
+
+    let mut serializer = AstSerializer::formatter(source, trivia, options);
+    Ok(serializer.format(&ast.program.files[0]))
+}
+
+/// Finds the comments and imports in the source, skipping over
+/// anything that looks like one inside a string
+fn scan(source: &str) -> (Vec<Trivia>, Vec<Trivia>) {
+    let mut comments = vec![];
+    let mut imports = vec![];
+    let mut offset = 0;
+    while offset < source.len() {
+        let rest = &source[offset..];
+        if rest.starts_with("//") {
+            let end = offset + rest.find('\n').unwrap_or(rest.len());
+            comments.push(Trivia {
+                start: offset,
+                end,
+                text: source[offset..end].trim_end().to_owned(),
+            });
+            offset = end;
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let end = offset + comment.find("*/").map_or(rest.len(), |end| end + 4);
+            comments.push(Trivia {
+                start: offset,
+                end,
+                text: source[offset..end].to_owned(),
+            });
+            offset = end;
+        } else if let Some(string) = rest.strip_prefix('"') {
+            // Strings don't have escapes so the next quote closes it
+            offset += string.find('"').map_or(rest.len(), |end| end + 2);
+        } else if let Some(import) = import(source, offset) {
+            offset = import.end;
+            imports.push(import);
+        } else {
+            offset += rest.chars().next().unwrap().len_utf8();
+        }
+    }
+    (comments, imports)
+}
+
+/// Matches an `import "path"` directive and the semicolon after it
+fn import(source: &str, offset: usize) -> Option<Trivia> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let rest = source[offset..].strip_prefix("import")?;
+    if source[..offset].ends_with(is_word) || !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let quoted = rest.trim_start().strip_prefix('"')?;
+    let path = &quoted[..quoted.find('"')?];
+    let mut end = source.len() - quoted.len() + path.len() + 1;
+    let after = &source[end..];
+    if after.trim_start().starts_with(';') {
+        end += after.find(';').unwrap() + 1;
+    }
+
+    Some(Trivia {
+        start: offset,
+        end,
+        text: format!("import \"{}\";", path),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jswt_assert::{assert_debug_snapshot, assert_snapshot, assert_str_eq};
+    use std::path::Path;
+
+    fn format(name: &str, source: &str) -> String {
+        format_source(name, source, FormatOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_scan_skips_strings() {
+        let source = r#"let a = "// not a comment"; // comment
+            import "x.jswt" ;
+            /* block */ let b = "import \"y\"";"#;
+        assert_debug_snapshot!(scan(source));
+    }
+
+    #[test]
+    fn test_format_keeps_comments() {
+        let source = r#"// @ts-nocheck
+
+import "a.jswt";
+// leading
+let a = 1; // trailing
+
+
+/**
+ * Doc comment
+ */
+@inline
+function f(x: i32): i32 {
+        // inside
+    return x; /* after */
+}
+function g() { /* empty */ }
+class A {
+    // field
+    x: i32;
+
+    readonly y: i32;
+    /* method */ get(): i32 { return this.x; }
+    // end of class
+}
+// end of file
+"#;
+        assert_snapshot!(format("test_format_keeps_comments", source));
+    }
+
+    #[test]
+    fn test_format_breaks_long_lists() {
+        let source = r#"
+            function f(first: i32, second: i32, third: i32) { g(first + second, [first, second, third], third); }
+        "#;
+        let options = FormatOptions {
+            indent_width: 2,
+            line_width: 40,
+        };
+        assert_snapshot!(format_source("test_format_breaks_long_lists", source, options).unwrap());
+    }
+
+    #[test]
+    fn test_format_rejects_parse_errors() {
+        let result = format_source(
+            "test_format_rejects_parse_errors",
+            "let = 1;",
+            FormatOptions::default(),
+        );
+        assert!(matches!(result.unwrap_err()[0], FormatError::Parse(_)));
+    }
+
+    #[test]
+    fn test_format_sources() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        for dir in ["example", "runtime", "stdlib"] {
+            let mut paths: Vec<_> = std::fs::read_dir(root.join(dir))
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| path.extension().map_or(false, |ext| ext == "jswt"))
+                // Skip the .hir.jswt dumps the compiler writes next to its input
+                .filter(|path| !path.file_stem().unwrap().to_str().unwrap().contains('.'))
+                .collect();
+            paths.sort();
+
+            for path in paths {
+                let name = format!("{}/{}", dir, path.file_name().unwrap().to_str().unwrap());
+                let source = std::fs::read_to_string(&path).unwrap();
+                let formatted = format(&format!("format/{}", name), &source);
+                let again = format(&format!("format/{}.formatted", name), &formatted);
+                assert_str_eq!(&again, &formatted);
+                assert_snapshot!(name.replace('/', "__"), formatted);
+            }
+        }
+    }
+}
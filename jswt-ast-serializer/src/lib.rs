@@ -1,121 +1,528 @@
+mod format;
+
 use jswt_ast::{
     visit::{self, Visitor},
     *,
 };
-use jswt_common::Typeable;
+use jswt_common::{Identifiable, Span, Spannable};
+use jswt_parser::Precedence;
+use jswt_types::TypeTable;
+
+use format::Trivia;
+pub use format::{format_source, FormatError, FormatOptions};
 
+/// Prints the AST back out as source. Parentheses are only added where
+/// the precedence of an operand requires them so the output parses
+/// back into the same tree.
 #[derive(Default)]
-pub struct AstSerializer {
+pub struct AstSerializer<'a> {
     content: String,
     indent: usize,
+    /// Types printed alongside variables and identifiers
+    types: Option<&'a TypeTable>,
+    options: FormatOptions,
+    /// Source being formatted, if any. Comments and blank
+    /// lines are carried over from it into the output.
+    source: Option<&'a str>,
+    trivia: Vec<Trivia>,
+    /// Index of the next trivia that hasn't been printed
+    next_trivia: usize,
 }
 
-impl AstSerializer {
-    fn indent(&mut self) {
-        self.content += &" ".repeat(self.indent * 4);
+impl<'a> AstSerializer<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Annotates variables and identifiers with the types the checker
+    /// inferred for them. Used for debug dumps of the lowered tree.
+    pub fn with_types(types: &'a TypeTable) -> Self {
+        Self {
+            types: Some(types),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn formatter(source: &'a str, trivia: Vec<Trivia>, options: FormatOptions) -> Self {
+        Self {
+            options,
+            source: Some(source),
+            trivia,
+            ..Self::default()
+        }
     }
-}
 
-impl AstSerializer {
     pub fn serialze(&mut self, ast: &Ast) -> &String {
         self.content += "// @ts-nocheck\n";
 
         self.visit_program(&ast.program);
         &self.content
     }
-}
 
-impl Visitor for AstSerializer {
-    fn visit_file(&mut self, node: &File) {
-        self.content += "\n";
-        self.content += "// ";
-        self.content += &node.span.file;
-        self.content += "\n\n";
-        visit::walk_file(self, node);
+    /// Prints the file along with the trivia of the source it was parsed from
+    pub(crate) fn format(&mut self, file: &File) -> String {
+        self.visit_file(file);
+        self.flush_trivia(usize::MAX);
+
+        let content = self.content.trim_end();
+        if content.is_empty() {
+            return String::new();
+        }
+        format!("{}\n", content)
     }
 
-    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
-        for annotation in node.decorators.annotations.iter() {
-            self.content += "@";
-            self.content += &annotation.name.value;
+    fn indent(&mut self) {
+        self.content += &" ".repeat(self.indent * self.options.indent_width);
+    }
+
+    /// Width of the line being printed
+    fn column(&self) -> usize {
+        let line = match self.content.rfind('\n') {
+            Some(newline) => &self.content[newline + 1..],
+            None => &self.content,
+        };
+        line.chars().count()
+    }
+
+    /// Sets the next line apart unless it's the first in its block
+    fn blank_line(&mut self) {
+        if !self.content.is_empty()
+            && !self.content.ends_with("\n\n")
+            && !self.content.ends_with("{\n")
+        {
+            self.content += "\n";
+        }
+    }
+
+    /// Starts a line for the declaration or statement at `start`,
+    /// printing the trivia before it and keeping a blank line above
+    /// it if the source had one
+    fn line(&mut self, start: usize) {
+        self.flush_trivia(start);
+        if self.newlines_before(start) > 1 {
+            self.blank_line();
+        }
+    }
+
+    /// Number of line breaks between `offset` and the code before it.
+    /// The start of the file counts as a single line break.
+    fn newlines_before(&self, offset: usize) -> usize {
+        let source = match self.source {
+            Some(source) => &source[..offset.min(source.len())],
+            None => return 0,
+        };
+        let code = source.trim_end();
+        match source[code.len()..].matches('\n').count() {
+            0 if code.is_empty() => 1,
+            newlines => newlines,
+        }
+    }
+
+    fn has_trivia_before(&self, offset: usize) -> bool {
+        matches!(self.trivia.get(self.next_trivia), Some(trivia) if trivia.start < offset)
+    }
+
+    /// Prints the trivia that comes before `offset` in the source
+    fn flush_trivia(&mut self, offset: usize) {
+        while self.has_trivia_before(offset) {
+            let trivia = self.trivia[self.next_trivia].clone();
+            self.next_trivia += 1;
+
+            match self.newlines_before(trivia.start) {
+                // Trivia following code stays on the line of whatever
+                // was printed last
+                0 if !self.content.trim_end().is_empty() => {
+                    let newlines = self.content.len() - self.content.trim_end_matches('\n').len();
+                    self.content.truncate(self.content.len() - newlines);
+                    self.content += " ";
+                    self.content += &trivia.text;
+                    self.content += &"\n".repeat(newlines);
+                }
+                newlines => {
+                    if newlines > 1 {
+                        self.blank_line();
+                    }
+                    self.trivia_lines(&trivia);
+                }
+            }
+        }
+    }
+
+    /// Prints trivia on lines of its own. Lines after the first are
+    /// moved along with the first so block comments stay aligned.
+    fn trivia_lines(&mut self, trivia: &Trivia) {
+        let source = self.source.unwrap_or_default();
+        let column = trivia.start - source[..trivia.start].rfind('\n').map_or(0, |i| i + 1);
+        for (i, line) in trivia.text.lines().enumerate() {
+            let line = match i {
+                0 => line,
+                _ => {
+                    let indent = line.len() - line.trim_start().len();
+                    &line[indent.min(column)..]
+                }
+            };
+            if !line.trim().is_empty() {
+                self.indent();
+                self.content += line.trim_end();
+            }
+            self.content += "\n";
+        }
+    }
+
+    /// Prints an operand, wrapping it in parentheses if it binds
+    /// looser than the position it appears in allows
+    fn expression(&mut self, node: &SingleExpression, min: Precedence) {
+        let parenthesize = precedence(node) < min;
+        if parenthesize {
             self.content += "(";
-            self.content += &format!("{:?}", annotation.expr);
+        }
+        self.visit_single_expression(node);
+        if parenthesize {
             self.content += ")";
+        }
+    }
+
+    /// Prints a comma separated list between `open` and `close`. Lists
+    /// that run past the line width get one item per line instead.
+    fn list<T>(&mut self, open: &str, close: &str, items: &[T], item: fn(&mut Self, &T)) {
+        let start = self.content.len();
+        self.content += open;
+        for (i, node) in items.iter().enumerate() {
+            if i > 0 {
+                self.content += ", ";
+            }
+            item(self, node);
+        }
+        self.content += close;
+
+        // A list is also broken up when one of its items had to be
+        let fits =
+            self.column() <= self.options.line_width && !self.content[start..].contains('\n');
+        if items.is_empty() || fits {
+            return;
+        }
+
+        self.content.truncate(start);
+        self.content += open;
+        self.content += "\n";
+        self.indent += 1;
+        for (i, node) in items.iter().enumerate() {
+            self.indent();
+            item(self, node);
+            if i + 1 < items.len() {
+                self.content += ",";
+            }
             self.content += "\n";
-            self.indent()
+        }
+        self.indent -= 1;
+        self.indent();
+        self.content += close;
+    }
+
+    fn expressions(&mut self, open: &str, close: &str, nodes: &[SingleExpression]) {
+        self.list(open, close, nodes, |this, node| {
+            this.expression(node, Precedence::Lowest)
+        });
+    }
+
+    fn annotation(&mut self, node: &Annotation) {
+        self.content += "@";
+        self.content += &node.name.value;
+        if node.arguments.is_empty() {
+            return;
         }
 
-        self.content += "function ";
-        self.content += &node.ident.value;
         self.content += "(";
+        for (i, arg) in node.arguments.iter().enumerate() {
+            if i > 0 {
+                self.content += ", ";
+            }
+            match &arg.name {
+                Some(name) => {
+                    self.content += &name.value;
+                    self.content += " = ";
+                    self.expression(&arg.value, Precedence::Assignment);
+                }
+                None => self.expression(&arg.value, Precedence::Lowest),
+            }
+        }
+        self.content += ")";
+    }
+
+    /// Prints annotations of a declaration, each on its own line
+    fn annotation_lines(&mut self, annotations: &[Annotation]) {
+        for annotation in annotations {
+            self.annotation(annotation);
+            self.content += "\n";
+            self.indent();
+        }
+    }
+
+    fn params(&mut self, node: &FormalParameterList) {
+        self.list("(", ")", &node.parameters, Self::visit_formal_parameter);
+    }
 
-        for (i, param) in node.params.parameters.iter().enumerate() {
-            self.content += &param.ident.value;
+    fn returns(&mut self, returns: &Option<TypeAnnotation>) {
+        if let Some(returns) = returns {
             self.content += ": ";
-            self.content += &param.type_annotation.ty.to_string();
-            if i + 1 != node.params.parameters.len() {
-                self.content += ", "
+            self.content += &returns.ty.to_string();
+        }
+    }
+}
+
+/// How tightly an expression binds, mirroring the levels the parser
+/// builds it with
+fn precedence(node: &SingleExpression) -> Precedence {
+    match node {
+        SingleExpression::Assignment(exp)
+        | SingleExpression::Multiplicative(exp)
+        | SingleExpression::Bitwise(exp)
+        | SingleExpression::Additive(exp)
+        | SingleExpression::Equality(exp)
+        | SingleExpression::Relational(exp) => binary_precedence(&exp.op),
+        SingleExpression::Cast(_) => Precedence::Cast,
+        SingleExpression::Unary(exp) if is_postfix(&exp.op) => Precedence::Postfix,
+        SingleExpression::Unary(_) => Precedence::Prefix,
+        SingleExpression::New(_)
+        | SingleExpression::MemberIndex(_)
+        | SingleExpression::Arguments(_)
+        | SingleExpression::MemberDot(_)
+        | SingleExpression::Identifier(_)
+        | SingleExpression::This(_)
+        | SingleExpression::Literal(_)
+        | SingleExpression::Error(_) => Precedence::Member,
+    }
+}
+
+/// Where a declaration starts in the source, including its annotations
+fn start(annotations: &[Annotation], span: &Span) -> usize {
+    annotations
+        .iter()
+        .map(|annotation| annotation.span.start)
+        .chain([span.start])
+        .min()
+        .unwrap()
+}
+
+fn binary_precedence(op: &BinaryOperator) -> Precedence {
+    match op {
+        BinaryOperator::Assign(_) => Precedence::Assignment,
+        BinaryOperator::Or(_) => Precedence::BitwiseOr,
+        BinaryOperator::And(_) => Precedence::BitwiseAnd,
+        BinaryOperator::Equal(_) | BinaryOperator::NotEqual(_) => Precedence::Equality,
+        BinaryOperator::Greater(_)
+        | BinaryOperator::GreaterEqual(_)
+        | BinaryOperator::Less(_)
+        | BinaryOperator::LessEqual(_) => Precedence::Relational,
+        BinaryOperator::Plus(_) | BinaryOperator::Minus(_) => Precedence::Additive,
+        BinaryOperator::Mult(_) | BinaryOperator::Div(_) => Precedence::Multiplicative,
+    }
+}
+
+fn is_postfix(op: &UnaryOperator) -> bool {
+    matches!(
+        op,
+        UnaryOperator::PostIncrement(_) | UnaryOperator::PostDecrement(_)
+    )
+}
+
+impl<'a> Visitor for AstSerializer<'a> {
+    fn visit_file(&mut self, node: &File) {
+        // Formatted sources keep their own header comments
+        if self.source.is_none() {
+            self.content += "\n";
+            self.content += "// ";
+            self.content += &node.span.file;
+            self.content += "\n\n";
+        }
+        visit::walk_file(self, node);
+    }
+
+    fn visit_source_elements(&mut self, node: &SourceElements) {
+        let mut previous_declaration = false;
+        for (i, element) in node.source_elements.iter().enumerate() {
+            // Declarations are set apart from whatever surrounds them
+            let declaration = !matches!(element, SourceElement::Statement(_));
+            if i > 0 && (declaration || previous_declaration) {
+                self.blank_line();
             }
+            self.visit_source_element(element);
+            previous_declaration = declaration;
+        }
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        self.line(start(&node.decorators.annotations, &node.span));
+        self.indent();
+        self.annotation_lines(&node.decorators.annotations);
+        if node.decorators.export {
+            self.content += "export ";
+        }
+        self.content += "function ";
+        self.content += &node.ident.value;
+        self.params(&node.params);
+        self.returns(&node.returns);
+        self.content += " ";
+        self.visit_block_statement(&node.body);
+        self.content += "\n";
+    }
+
+    fn visit_formal_parameter(&mut self, node: &FormalParameterArg) {
+        for annotation in node.annotations.iter() {
+            self.annotation(annotation);
+            self.content += " ";
         }
-        self.content += "): ";
-        if let Some(ret) = &node.returns {
-            self.content += &ret.ty.to_string();
-        } else {
-            self.content += "void";
+        self.content += &node.ident.value;
+        self.content += ": ";
+        self.content += &node.type_annotation.ty.to_string();
+    }
+
+    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
+        self.line(start(&node.annotations, &node.span));
+        self.indent();
+        self.annotation_lines(&node.annotations);
+        self.content += "class ";
+        self.content += &node.ident.value;
+        self.content += " ";
+        self.visit_class_body(&node.body);
+        self.content += "\n";
+    }
+
+    fn visit_class_body(&mut self, node: &ClassBody) {
+        if node.class_elements.is_empty() && !self.has_trivia_before(node.span.end) {
+            self.content += "{}";
+            return;
+        }
+
+        self.content += "{\n";
+        self.indent += 1;
+        let mut previous_field = false;
+        for (i, element) in node.class_elements.iter().enumerate() {
+            // Fields are grouped together, everything else is set apart
+            let field = matches!(element, ClassElement::Field(_));
+            if i > 0 && !(field && previous_field) {
+                self.blank_line();
+            }
+            match element {
+                ClassElement::Constructor(elem) => self.visit_class_constructor_declaration(elem),
+                ClassElement::Method(elem) => self.visit_class_method_declaration(elem),
+                ClassElement::Field(elem) => self.visit_class_field_declaration(elem),
+            }
+            previous_field = field;
         }
+        self.flush_trivia(node.span.end);
+        self.indent -= 1;
+        self.indent();
+        self.content += "}";
+    }
+
+    fn visit_class_constructor_declaration(&mut self, node: &ClassConstructorElement) {
+        self.line(start(&node.annotations, &node.span));
+        self.indent();
+        self.annotation_lines(&node.annotations);
+        self.content += "constructor";
+        self.params(&node.params);
+        self.content += " ";
+        self.visit_block_statement(&node.body);
+        self.content += "\n";
+    }
 
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        self.line(start(&node.annotations, &node.span));
+        self.indent();
+        self.annotation_lines(&node.annotations);
+        self.content += &node.ident.value;
+        self.params(&node.params);
+        self.returns(&node.returns);
         self.content += " ";
-        visit::walk_function_declaration(self, node);
+        self.visit_block_statement(&node.body);
+        self.content += "\n";
+    }
+
+    fn visit_class_field_declaration(&mut self, node: &ClassFieldElement) {
+        self.line(start(&node.annotations, &node.span));
+        self.indent();
+        self.annotation_lines(&node.annotations);
+        if node.readonly {
+            self.content += "readonly ";
+        }
+        self.content += &node.ident.value;
+        self.content += ": ";
+        self.content += &node.type_annotation.ty.to_string();
+        self.content += ";\n";
     }
 
     fn visit_statement_element(&mut self, node: &StatementElement) {
+        self.line(node.span().start);
         self.indent();
         visit::walk_statement_element(self, node);
-        self.content += ";\n";
+        self.content += "\n";
     }
 
     fn visit_block_statement(&mut self, node: &BlockStatement) {
-        self.content += "{ \n";
+        if node.statements.statements.is_empty() && !self.has_trivia_before(node.span.end) {
+            self.content += "{}";
+            return;
+        }
+
+        self.content += "{\n";
         self.indent += 1;
         visit::walk_block_statement(self, node);
+        self.flush_trivia(node.span.end);
         self.indent -= 1;
-        self.content += "}\n";
+        self.indent();
+        self.content += "}";
     }
 
-    fn visit_return_statement(&mut self, node: &ReturnStatement) {
-        self.content += "return ";
-        visit::walk_return_statement(self, node);
+    fn visit_empty_statement(&mut self, _: &EmptyStatement) {
+        self.content += ";";
     }
 
-    fn visit_unary_expression(&mut self, node: &UnaryExpression) {
-        match node.op {
-            UnaryOperator::Plus(_) => {
-                self.content += "+";
-                self.visit_single_expression(&node.expr);
-            }
-            UnaryOperator::Minus(_) => {
-                self.content += "-";
-                self.visit_single_expression(&node.expr);
-            }
-            UnaryOperator::Not(_) => {
-                self.content += "!";
-                self.visit_single_expression(&node.expr);
-            }
-            UnaryOperator::PostIncrement(_) => {
-                self.visit_single_expression(&node.expr);
-                self.content += "++";
-            }
-            UnaryOperator::PostDecrement(_) => {
-                self.visit_single_expression(&node.expr);
-                self.content += "--";
+    fn visit_error_statement(&mut self, _: &ErrorStatement) {
+        self.content += "/* error */";
+    }
+
+    fn visit_if_statement(&mut self, node: &IfStatement) {
+        self.content += "if (";
+        self.expression(&node.condition, Precedence::Lowest);
+        self.content += ") ";
+        // Branches are printed in place rather than on a line of their own
+        visit::walk_statement_element(self, &node.consequence);
+
+        if let Some(alternative) = &node.alternative {
+            if matches!(*node.consequence, StatementElement::Block(_)) {
+                self.content += " ";
+            } else {
+                self.content += "\n";
+                self.indent();
             }
-        };
+            self.content += "else ";
+            visit::walk_statement_element(self, alternative);
+        }
     }
 
-    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
-        self.visit_single_expression(&node.target);
-        self.content += ".";
-        self.visit_single_expression(&node.expression);
+    fn visit_while_iteration_element(&mut self, node: &WhileIterationElement) {
+        self.content += "while (";
+        self.expression(&node.expression, Precedence::Lowest);
+        self.content += ") ";
+        self.visit_block_statement(&node.block);
+    }
+
+    fn visit_return_statement(&mut self, node: &ReturnStatement) {
+        self.content += "return";
+        if let Some(expression) = &node.expression {
+            self.content += " ";
+            self.expression(expression, Precedence::Lowest);
+        }
+        self.content += ";";
+    }
+
+    fn visit_break_statement(&mut self, _: &BreakStatement) {
+        self.content += "break;";
+    }
+
+    fn visit_continue_statement(&mut self, _: &ContinueStatement) {
+        self.content += "continue;";
     }
 
     fn visit_variable_statement(&mut self, node: &VariableStatement) {
@@ -127,10 +534,27 @@ impl Visitor for AstSerializer {
         self.content += modifier;
         self.content += " ";
         self.visit_assignable_element(&node.target);
-        self.content += ": ";
-        self.content += &node.expression.ty().to_string();
-        self.content += " = ";
-        self.visit_single_expression(&node.expression);
+        match (&node.type_annotation, &node.expression, self.types) {
+            (Some(annotation), _, _) => {
+                self.content += ": ";
+                self.content += &annotation.ty.to_string();
+            }
+            (None, Some(expression), Some(types)) => {
+                self.content += ": ";
+                self.content += &types.ty(expression.id()).to_string();
+            }
+            _ => {}
+        }
+        if let Some(expression) = &node.expression {
+            self.content += " = ";
+            self.expression(expression, Precedence::Lowest);
+        }
+        self.content += ";";
+    }
+
+    fn visit_expression_statement(&mut self, node: &ExpressionStatement) {
+        self.expression(&node.expression, Precedence::Lowest);
+        self.content += ";";
     }
 
     fn visit_assignable_element(&mut self, node: &AssignableElement) {
@@ -139,16 +563,58 @@ impl Visitor for AstSerializer {
         }
     }
 
-    fn visit_argument_expression(&mut self, node: &ArgumentsExpression) {
-        self.visit_single_expression(&*node.ident);
-        self.content += "(";
-        for (i, arg) in node.arguments.arguments.iter().enumerate() {
-            self.visit_single_expression(arg);
-            if i + 1 != node.arguments.arguments.len() {
-                self.content += ", ";
+    fn visit_unary_expression(&mut self, node: &UnaryExpression) {
+        let op = match node.op {
+            UnaryOperator::Plus(_) => "+",
+            UnaryOperator::Minus(_) => "-",
+            UnaryOperator::Not(_) => "~",
+            UnaryOperator::PostIncrement(_) => "++",
+            UnaryOperator::PostDecrement(_) => "--",
+        };
+
+        if is_postfix(&node.op) {
+            self.expression(&node.expr, Precedence::Postfix);
+            self.content += op;
+            return;
+        }
+
+        self.content += op;
+        // Keep `- -a` from running together into a decrement
+        if let SingleExpression::Unary(inner) = &*node.expr {
+            if matches!(inner.op, UnaryOperator::Plus(_) | UnaryOperator::Minus(_)) {
+                self.content += " ";
             }
         }
-        self.content += ")";
+        self.expression(&node.expr, Precedence::Prefix);
+    }
+
+    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
+        self.expression(&node.target, Precedence::Member);
+        self.content += ".";
+        self.expression(&node.expression, Precedence::Member);
+    }
+
+    fn visit_member_index(&mut self, node: &MemberIndexExpression) {
+        self.expression(&node.target, Precedence::Member);
+        self.content += "[";
+        self.expression(&node.index, Precedence::Lowest);
+        self.content += "]";
+    }
+
+    fn visit_new(&mut self, node: &NewExpression) {
+        self.content += "new ";
+        self.expression(&node.expression, Precedence::Member);
+    }
+
+    fn visit_cast_expression(&mut self, node: &CastExpression) {
+        self.expression(&node.expression, Precedence::Cast);
+        self.content += " as ";
+        self.content += &node.target.ty.to_string();
+    }
+
+    fn visit_argument_expression(&mut self, node: &ArgumentsExpression) {
+        self.expression(&node.ident, Precedence::Member);
+        self.expressions("(", ")", &node.arguments.arguments);
     }
 
     fn visit_assignment_expression(&mut self, node: &BinaryExpression) {
@@ -171,31 +637,379 @@ impl Visitor for AstSerializer {
             BinaryOperator::Or(_) => "|",
             BinaryOperator::Assign(_) => "=",
         };
-        self.visit_single_expression(&node.left);
+
+        let precedence = binary_precedence(&node.op);
+        // Assignment is the only right associative operator
+        let (left, right) = match node.op {
+            BinaryOperator::Assign(_) => (precedence.tighter(), precedence),
+            _ => (precedence, precedence.tighter()),
+        };
+        self.expression(&node.left, left);
         self.content += " ";
         self.content += op;
         self.content += " ";
-        self.visit_single_expression(&node.right);
+        self.expression(&node.right, right);
     }
 
     fn visit_this_expression(&mut self, _: &ThisExpression) {
         self.content += "this";
     }
 
+    fn visit_error_expression(&mut self, _: &ErrorExpression) {
+        self.content += "/* error */";
+    }
+
     fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
         self.content += &node.ident.value;
-        self.content += "/* ";
-        self.content += &node.ty().to_string();
-        self.content += " */"
+        if let Some(types) = self.types {
+            self.content += "/* ";
+            self.content += &types.ty(node.id).to_string();
+            self.content += " */"
+        }
     }
 
     fn visit_literal(&mut self, node: &Literal) {
         match node {
-            Literal::Array(_) => todo!(),
-            Literal::String(s) => self.content += s.value,
+            Literal::Array(array) => self.expressions("[", "]", &array.elements),
+            Literal::String(s) => {
+                self.content += "\"";
+                self.content += &s.value;
+                self.content += "\"";
+            }
+            // Negative values only come from hex literals that overflowed
+            Literal::Integer(i) if i.value < 0 => self.content += &format!("{:#x}", i.value),
             Literal::Integer(i) => self.content += &i.value.to_string(),
-            Literal::Float(f) => self.content += &f.value.to_string(),
+            Literal::Float(f) => {
+                let value = f.value.to_string();
+                self.content += &value;
+                // Whole numbers still need a fraction to be read as a float
+                if f.value.is_finite() && !value.contains('.') {
+                    self.content += ".0";
+                }
+            }
             Literal::Boolean(b) => self.content += &b.value.to_string(),
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jswt_assert::{assert_snapshot, assert_str_eq};
+    use jswt_common::{NodeId, Span};
+    use jswt_parser::Parser;
+    use jswt_tokenizer::Tokenizer;
+    use jswt_types::Type;
+    use std::fmt::Debug;
+
+    fn parse(name: &str, source: &str) -> Ast {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(name, source);
+        let mut parser = Parser::new(&mut tokenizer);
+        let ast = parser.parse();
+        let errors = parser.parse_errors();
+        assert!(errors.is_empty(), "{:?}\n{}", errors, source);
+        ast
+    }
+
+    fn print(ast: &Ast) -> String {
+        AstSerializer::new().serialze(ast).to_owned()
+    }
+
+    /// Debug output of the tree without spans and ids, which
+    /// change every time the source is printed and parsed again.
+    /// Doc comments are left out too since comments are only
+    /// printed when formatting a source.
+    fn shape<T: Debug>(node: &T) -> String {
+        let mut shape = format!("{:?}", node);
+        for (open, close) in [("Span {", '}'), ("NodeId(", ')')] {
+            while let Some(start) = shape.find(open) {
+                let end = start + shape[start..].find(close).unwrap();
+                shape.replace_range(start..=end, "_");
+            }
+        }
+        shape.replace("doc: Some(_)", "doc: None")
+    }
+
+    /// Prints the program, parses it again and checks
+    /// that nothing but the positions changed
+    fn assert_round_trips(name: &str, ast: &Ast) {
+        let printed = print(ast);
+        let reparsed = parse(&format!("{}.printed", name), &printed);
+        assert_str_eq!(
+            &shape(&reparsed.program.files[0].source_elements),
+            &shape(&ast.program.files[0].source_elements)
+        );
+    }
+
+    /// Xorshift generator so the property test is reproducible
+    /// without pulling in a dependency
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+
+        fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+            items[self.below(items.len())].clone()
+        }
+    }
+
+    fn span() -> Span {
+        Span::new("generated", "generated", 0, 0)
+    }
+
+    fn ident(rng: &mut Rng) -> SingleExpression {
+        let name = rng.pick(&["a", "b", "value"]);
+        SingleExpression::Identifier(IdentifierExpression {
+            id: NodeId::DUMMY,
+            span: span(),
+            ident: Identifier::new(name, span()),
+        })
+    }
+
+    fn primary(rng: &mut Rng) -> SingleExpression {
+        let literal: Literal = match rng.below(6) {
+            0 => return ident(rng),
+            1 => {
+                return SingleExpression::This(ThisExpression {
+                    id: NodeId::DUMMY,
+                    span: span(),
+                })
+            }
+            2 => IntegerLiteral {
+                id: NodeId::DUMMY,
+                span: span(),
+                value: rng.pick(&[0, 7, 4096, -1]),
+            }
+            .into(),
+            3 => FloatingPointLiteral {
+                id: NodeId::DUMMY,
+                span: span(),
+                value: rng.pick(&[0.5, 1.0, 2.25, 1e21]),
+            }
+            .into(),
+            4 => StringLiteral {
+                id: NodeId::DUMMY,
+                span: span(),
+                value: rng.pick(&["", "hello", "a b"]).into(),
+            }
+            .into(),
+            _ => BooleanLiteral {
+                id: NodeId::DUMMY,
+                span: span(),
+                value: rng.below(2) == 0,
+            }
+            .into(),
+        };
+        SingleExpression::Literal(literal)
+    }
+
+    fn binary(
+        rng: &mut Rng,
+        depth: usize,
+        exp: fn(BinaryExpression) -> SingleExpression,
+        ops: &[fn(Span) -> BinaryOperator],
+    ) -> SingleExpression {
+        let op = rng.pick(ops);
+        exp(BinaryExpression {
+            id: NodeId::DUMMY,
+            span: span(),
+            left: Box::new(expression(rng, depth - 1)),
+            op: op(span()),
+            right: Box::new(expression(rng, depth - 1)),
+        })
+    }
+
+    /// Builds a random expression tree out of every kind of expression
+    fn expression(rng: &mut Rng, depth: usize) -> SingleExpression {
+        if depth == 0 {
+            return primary(rng);
+        }
+
+        let operand = |rng: &mut Rng| Box::new(expression(rng, depth - 1));
+        match rng.below(14) {
+            0 => binary(
+                rng,
+                depth,
+                SingleExpression::Assignment,
+                &[BinaryOperator::Assign],
+            ),
+            1 => binary(
+                rng,
+                depth,
+                SingleExpression::Bitwise,
+                &[BinaryOperator::And, BinaryOperator::Or],
+            ),
+            2 => binary(
+                rng,
+                depth,
+                SingleExpression::Equality,
+                &[BinaryOperator::Equal, BinaryOperator::NotEqual],
+            ),
+            3 => binary(
+                rng,
+                depth,
+                SingleExpression::Relational,
+                &[
+                    BinaryOperator::Greater,
+                    BinaryOperator::GreaterEqual,
+                    BinaryOperator::Less,
+                    BinaryOperator::LessEqual,
+                ],
+            ),
+            4 => binary(
+                rng,
+                depth,
+                SingleExpression::Additive,
+                &[BinaryOperator::Plus, BinaryOperator::Minus],
+            ),
+            5 => binary(
+                rng,
+                depth,
+                SingleExpression::Multiplicative,
+                &[BinaryOperator::Mult, BinaryOperator::Div],
+            ),
+            6 => {
+                let op: fn(Span) -> UnaryOperator = rng.pick(&[
+                    UnaryOperator::Plus,
+                    UnaryOperator::Minus,
+                    UnaryOperator::Not,
+                    UnaryOperator::PostIncrement,
+                    UnaryOperator::PostDecrement,
+                ]);
+                SingleExpression::Unary(UnaryExpression {
+                    id: NodeId::DUMMY,
+                    span: span(),
+                    op: op(span()),
+                    expr: operand(rng),
+                })
+            }
+            7 => SingleExpression::Cast(CastExpression {
+                id: NodeId::DUMMY,
+                span: span(),
+                expression: operand(rng),
+                target: TypeAnnotation {
+                    span: span(),
+                    ty: rng.pick(&[Type::I32, Type::array(Type::F64), Type::from_name("Point")]),
+                },
+            }),
+            8 => SingleExpression::MemberDot(MemberDotExpression {
+                id: NodeId::DUMMY,
+                span: span(),
+                target: operand(rng),
+                expression: Box::new(ident(rng)),
+            }),
+            9 => SingleExpression::MemberIndex(MemberIndexExpression {
+                id: NodeId::DUMMY,
+                span: span(),
+                target: operand(rng),
+                index: operand(rng),
+            }),
+            10 => SingleExpression::Arguments(ArgumentsExpression {
+                id: NodeId::DUMMY,
+                span: span(),
+                ident: operand(rng),
+                arguments: ArgumentsList {
+                    span: span(),
+                    arguments: (0..rng.below(3))
+                        .map(|_| expression(rng, depth - 1))
+                        .collect(),
+                },
+            }),
+            11 => SingleExpression::New(NewExpression {
+                id: NodeId::DUMMY,
+                span: span(),
+                expression: Box::new(SingleExpression::Arguments(ArgumentsExpression {
+                    id: NodeId::DUMMY,
+                    span: span(),
+                    ident: Box::new(ident(rng)),
+                    arguments: ArgumentsList {
+                        span: span(),
+                        arguments: (0..rng.below(3))
+                            .map(|_| expression(rng, depth - 1))
+                            .collect(),
+                    },
+                })),
+            }),
+            12 => SingleExpression::Literal(Literal::Array(ArrayLiteral {
+                id: NodeId::DUMMY,
+                span: span(),
+                elements: (0..rng.below(3))
+                    .map(|_| expression(rng, depth - 1))
+                    .collect(),
+            })),
+            _ => primary(rng),
+        }
+    }
+
+    #[test]
+    fn test_random_expressions_round_trip() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for i in 0..500 {
+            let statement = ExpressionStatement {
+                id: NodeId::DUMMY,
+                span: span(),
+                expression: expression(&mut rng, 1 + i % 5),
+            };
+            let ast = Ast::new(Program {
+                id: NodeId::DUMMY,
+                files: vec![File {
+                    id: NodeId::DUMMY,
+                    span: span(),
+                    source_elements: SourceElements {
+                        span: span(),
+                        source_elements: vec![StatementElement::from(statement).into()],
+                    },
+                }],
+            });
+            assert_round_trips(&format!("test_random_expressions_round_trip_{}", i), &ast);
+        }
+    }
+
+    #[test]
+    fn test_sources_round_trip() {
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        for dir in ["example", "runtime", "stdlib"] {
+            for entry in std::fs::read_dir(root.join(dir)).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().map_or(true, |ext| ext != "jswt") {
+                    continue;
+                }
+                // Skip the .hir.jswt dumps the compiler writes next to its input
+                if path.file_stem().unwrap().to_str().unwrap().contains('.') {
+                    continue;
+                }
+                // Imports are resolved by the tokenizer and aren't part of the tree
+                let source: String = std::fs::read_to_string(&path)
+                    .unwrap()
+                    .lines()
+                    .filter(|line| !line.trim_start().starts_with("import"))
+                    .map(|line| format!("{}\n", line))
+                    .collect();
+                let name = path.to_str().unwrap();
+                assert_round_trips(name, &parse(name, &source));
+            }
+        }
+    }
+
+    #[test]
+    fn test_print_declarations() {
+        let ast = parse(
+            "test_print_declarations",
+            r#"
+            @native(module = "env") export function log(@allow("unused") value: i32) {}
+            class Point { readonly x: i32; y: i32; constructor(x: i32) { this.x = x; }
+                @inline length(): i32 { if (this.x > 0) { return this.x * (this.y + 1); } else return -this.x as i32; } }
+            let total = 0;
+            let i: i32;
+            function main(): void { while (total < 10) { total = total + 1; if (total == 5) break; } let p = new Point(1); }
+            "#,
+        );
+        assert_snapshot!(print(&ast));
+    }
+}
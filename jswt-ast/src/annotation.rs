@@ -0,0 +1,185 @@
+use std::fmt::Display;
+
+use jswt_common::Span;
+use jswt_derive::Spannable;
+use serde::Serialize;
+
+use crate::{Identifier, Literal, SingleExpression};
+
+#[derive(Debug, PartialEq, Spannable, Clone, Serialize)]
+pub struct Annotation {
+    pub span: Span,
+    pub name: Identifier,
+    pub arguments: Vec<AnnotationArgument>,
+}
+
+/// An argument passed to an annotation. Arguments are either positional
+/// `@wast("...")` or named `@wast(code = "...")`.
+#[derive(Debug, PartialEq, Spannable, Clone, Serialize)]
+pub struct AnnotationArgument {
+    pub span: Span,
+    pub name: Option<Identifier>,
+    pub value: SingleExpression,
+}
+
+impl Annotation {
+    /// Looks up the schema of this annotation in the registry
+    pub fn schema(&self) -> Option<&'static AnnotationSchema> {
+        AnnotationSchema::lookup(&self.name.value)
+    }
+
+    /// Resolves the argument bound to the parameter `name`. Named arguments
+    /// are matched by name and positional arguments by their position
+    /// in the annotation's schema.
+    pub fn argument(&self, name: &str) -> Option<&AnnotationArgument> {
+        let position = self
+            .schema()
+            .and_then(|schema| schema.arguments.iter().position(|arg| arg.name == name));
+
+        self.arguments
+            .iter()
+            .enumerate()
+            .find(|(i, arg)| match &arg.name {
+                Some(ident) => ident.value == name,
+                None => Some(*i) == position,
+            })
+            .map(|(_, arg)| arg)
+    }
+
+    /// Resolves the value of a string argument bound to the parameter `name`
+    pub fn string_argument(&self, name: &str) -> Option<&str> {
+        match self.argument(name).map(|arg| &arg.value) {
+            Some(SingleExpression::Literal(Literal::String(lit))) => Some(&lit.value),
+            _ => None,
+        }
+    }
+}
+
+/// Declarations that annotations can be attached to
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AnnotationTarget {
+    Function,
+    Class,
+    Method,
+    Constructor,
+    Field,
+    Parameter,
+}
+
+impl Display for AnnotationTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnnotationTarget::Function => write!(f, "function"),
+            AnnotationTarget::Class => write!(f, "class"),
+            AnnotationTarget::Method => write!(f, "method"),
+            AnnotationTarget::Constructor => write!(f, "constructor"),
+            AnnotationTarget::Field => write!(f, "field"),
+            AnnotationTarget::Parameter => write!(f, "parameter"),
+        }
+    }
+}
+
+/// The kind of literal an annotation argument must be
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArgumentKind {
+    String,
+    Integer,
+    Boolean,
+}
+
+impl ArgumentKind {
+    pub fn matches(&self, expression: &SingleExpression) -> bool {
+        matches!(
+            (self, expression),
+            (
+                ArgumentKind::String,
+                SingleExpression::Literal(Literal::String(_))
+            ) | (
+                ArgumentKind::Integer,
+                SingleExpression::Literal(Literal::Integer(_))
+            ) | (
+                ArgumentKind::Boolean,
+                SingleExpression::Literal(Literal::Boolean(_))
+            )
+        )
+    }
+}
+
+impl Display for ArgumentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgumentKind::String => write!(f, "string"),
+            ArgumentKind::Integer => write!(f, "integer"),
+            ArgumentKind::Boolean => write!(f, "boolean"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ArgumentSchema {
+    pub name: &'static str,
+    pub kind: ArgumentKind,
+    pub required: bool,
+}
+
+/// Describes an annotation known to the compiler, where it may be
+/// used and the arguments it accepts.
+#[derive(Debug, PartialEq)]
+pub struct AnnotationSchema {
+    pub name: &'static str,
+    pub targets: &'static [AnnotationTarget],
+    pub arguments: &'static [ArgumentSchema],
+}
+
+impl AnnotationSchema {
+    pub fn lookup(name: &str) -> Option<&'static AnnotationSchema> {
+        ANNOTATIONS.iter().find(|schema| schema.name == name)
+    }
+}
+
+/// Registry of annotations known to the compiler
+pub const ANNOTATIONS: &[AnnotationSchema] = &[
+    // Emits the given WAST instructions as the body of the function
+    AnnotationSchema {
+        name: "wast",
+        targets: &[AnnotationTarget::Function, AnnotationTarget::Method],
+        arguments: &[ArgumentSchema {
+            name: "code",
+            kind: ArgumentKind::String,
+            required: true,
+        }],
+    },
+    // Imports the function from the given host module
+    AnnotationSchema {
+        name: "native",
+        targets: &[AnnotationTarget::Function],
+        arguments: &[ArgumentSchema {
+            name: "module",
+            kind: ArgumentKind::String,
+            required: true,
+        }],
+    },
+    // Inlines the body of the function at its call sites
+    AnnotationSchema {
+        name: "inline",
+        targets: &[AnnotationTarget::Function, AnnotationTarget::Method],
+        arguments: &[],
+    },
+    // Silences the named lint within the declaration
+    AnnotationSchema {
+        name: "allow",
+        targets: &[
+            AnnotationTarget::Function,
+            AnnotationTarget::Class,
+            AnnotationTarget::Method,
+            AnnotationTarget::Constructor,
+            AnnotationTarget::Field,
+            AnnotationTarget::Parameter,
+        ],
+        arguments: &[ArgumentSchema {
+            name: "lint",
+            kind: ArgumentKind::String,
+            required: true,
+        }],
+    },
+];
@@ -1,14 +1,18 @@
-use crate::{ident::Identifier, Literal};
+use crate::{ident::Identifier, Literal, TypeAnnotation};
 
-use jswt_common::{Span, Type};
-use jswt_derive::{Spannable, Typeable};
+use jswt_common::{NodeId, Span, Spannable};
+use jswt_derive::{Identifiable, Spannable, Walk};
+use serde::Serialize;
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_single_expression, group = "expression")]
 pub enum SingleExpression {
     Unary(UnaryExpression),
     MemberIndex(MemberIndexExpression),
     New(NewExpression),
+    Cast(CastExpression),
     Arguments(ArgumentsExpression),
+    #[walk(visit_assignment_expression)]
     Assignment(BinaryExpression),
     Multiplicative(BinaryExpression),
     Bitwise(BinaryExpression),
@@ -19,6 +23,7 @@ pub enum SingleExpression {
     MemberDot(MemberDotExpression),
     This(ThisExpression),
     Literal(Literal),
+    Error(ErrorExpression),
 }
 
 impl SingleExpression {
@@ -38,6 +43,44 @@ impl SingleExpression {
         }
     }
 
+    /// Gives the expression the id of the node it replaces so anything
+    /// recorded about that node still applies to the expression
+    pub fn with_id(mut self, id: NodeId) -> Self {
+        match &mut self {
+            Self::Unary(exp) => exp.id = id,
+            Self::MemberIndex(exp) => exp.id = id,
+            Self::New(exp) => exp.id = id,
+            Self::Cast(exp) => exp.id = id,
+            Self::Arguments(exp) => exp.id = id,
+            Self::Assignment(exp)
+            | Self::Multiplicative(exp)
+            | Self::Bitwise(exp)
+            | Self::Additive(exp)
+            | Self::Equality(exp)
+            | Self::Relational(exp) => exp.id = id,
+            Self::Identifier(exp) => exp.id = id,
+            Self::MemberDot(exp) => exp.id = id,
+            Self::This(exp) => exp.id = id,
+            Self::Literal(Literal::Array(lit)) => lit.id = id,
+            Self::Literal(Literal::String(lit)) => lit.id = id,
+            Self::Literal(Literal::Integer(lit)) => lit.id = id,
+            Self::Literal(Literal::Float(lit)) => lit.id = id,
+            Self::Literal(Literal::Boolean(lit)) => lit.id = id,
+            Self::Error(exp) => exp.id = id,
+        }
+        self
+    }
+
+    /// Moves the expression out of the tree so a lowering pass can rebuild
+    /// it without cloning. An error expression is left in its place.
+    pub fn take(&mut self) -> Self {
+        let placeholder = Self::Error(ErrorExpression {
+            id: NodeId::DUMMY,
+            span: self.span(),
+        });
+        std::mem::replace(self, placeholder)
+    }
+
     pub fn as_identifier_mut(&mut self) -> Option<&mut IdentifierExpression> {
         if let Self::Identifier(v) = self {
             Some(v)
@@ -47,74 +90,112 @@ impl SingleExpression {
     }
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_new, group = "expression")]
 pub struct NewExpression {
+    pub id: NodeId,
+    pub span: Span,
+    #[walk]
+    pub expression: Box<SingleExpression>,
+}
+
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_cast_expression, group = "expression")]
+pub struct CastExpression {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub expression: Box<SingleExpression>,
-    pub ty: Type,
+    pub target: TypeAnnotation,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_member_dot, group = "expression")]
 pub struct MemberDotExpression {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub target: Box<SingleExpression>,
+    #[walk]
     pub expression: Box<SingleExpression>,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_argument_expression, group = "expression")]
 pub struct ArgumentsExpression {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub ident: Box<SingleExpression>,
+    #[walk]
     pub arguments: ArgumentsList,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Walk, Clone, Serialize)]
 pub struct ArgumentsList {
     pub span: Span,
+    #[walk]
     pub arguments: Vec<SingleExpression>,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+/// Placeholder for an expression that failed to parse so that
+/// the rest of the tree can still be analyzed
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_error_expression, group = "expression")]
+pub struct ErrorExpression {
+    pub id: NodeId,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_this_expression, group = "expression")]
 pub struct ThisExpression {
+    pub id: NodeId,
     pub span: Span,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_member_index, group = "expression")]
 pub struct MemberIndexExpression {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub target: Box<SingleExpression>,
+    #[walk]
     pub index: Box<SingleExpression>,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_unary_expression, group = "expression")]
 pub struct UnaryExpression {
+    pub id: NodeId,
     pub span: Span,
     pub op: UnaryOperator,
+    #[walk]
     pub expr: Box<SingleExpression>,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_binary_expression, group = "expression")]
 pub struct BinaryExpression {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub left: Box<SingleExpression>,
     pub op: BinaryOperator,
+    #[walk]
     pub right: Box<SingleExpression>,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_identifier_expression, group = "expression")]
 pub struct IdentifierExpression {
+    pub id: NodeId,
     pub span: Span,
     pub ident: Identifier,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum UnaryOperator {
     Plus(Span),
     Minus(Span),
@@ -123,7 +204,7 @@ pub enum UnaryOperator {
     PostDecrement(Span),
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum BinaryOperator {
     Plus(Span),
     Minus(Span),
@@ -139,3 +220,23 @@ pub enum BinaryOperator {
     Or(Span),
     Assign(Span),
 }
+
+impl BinaryOperator {
+    /// Name of the method a class implements to overload the operator.
+    /// Logical operators and assignment can't be overloaded.
+    pub fn method_name(&self) -> Option<&'static str> {
+        match self {
+            BinaryOperator::Plus(_) => Some("add"),
+            BinaryOperator::Minus(_) => Some("sub"),
+            BinaryOperator::Mult(_) => Some("mul"),
+            BinaryOperator::Div(_) => Some("div"),
+            BinaryOperator::Equal(_) => Some("eq"),
+            BinaryOperator::NotEqual(_) => Some("ne"),
+            BinaryOperator::Greater(_) => Some("gt"),
+            BinaryOperator::GreaterEqual(_) => Some("ge"),
+            BinaryOperator::Less(_) => Some("lt"),
+            BinaryOperator::LessEqual(_) => Some("le"),
+            BinaryOperator::And(_) | BinaryOperator::Or(_) | BinaryOperator::Assign(_) => None,
+        }
+    }
+}
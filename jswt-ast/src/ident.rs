@@ -1,16 +1,15 @@
-use std::borrow::Cow;
+use jswt_common::{Atom, Span};
+use jswt_derive::{Spannable, Walk};
+use serde::Serialize;
 
-use jswt_common::Span;
-use jswt_derive::Spannable;
-
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Walk, Clone, Serialize)]
 pub struct Identifier {
     pub span: Span,
-    pub value: Cow<'static, str>,
+    pub value: Atom,
 }
 
 impl Identifier {
-    pub fn new<T: Into<Cow<'static, str>>>(value: T, span: Span) -> Self {
+    pub fn new<T: Into<Atom>>(value: T, span: Span) -> Self {
         Self {
             span,
             value: value.into(),
@@ -0,0 +1,184 @@
+use jswt_common::NodeId;
+
+use crate::mut_visit::*;
+use crate::*;
+
+/// Numbers every node in the program that doesn't have an id yet,
+/// starting at `first`, and returns the id the next new node gets.
+/// Nodes that already have one keep it so lowering passes can carry
+/// the id of the node they replace, and with it everything recorded
+/// about that node in side tables.
+pub fn assign_node_ids(program: &mut Program, first: u32) -> u32 {
+    let mut assigner = NodeIdAssigner { next: first };
+    assigner.visit_program(program);
+    assigner.next
+}
+
+struct NodeIdAssigner {
+    next: u32,
+}
+
+impl NodeIdAssigner {
+    fn id(&mut self, id: &mut NodeId) {
+        if !id.is_dummy() {
+            self.next = self.next.max(id.0 + 1);
+        } else {
+            *id = NodeId(self.next);
+            self.next += 1;
+        }
+    }
+}
+
+impl MutVisitor for NodeIdAssigner {
+    fn visit_program(&mut self, node: &mut Program) {
+        self.id(&mut node.id);
+        walk_program(self, node);
+    }
+
+    fn visit_file(&mut self, node: &mut File) {
+        self.id(&mut node.id);
+        walk_file(self, node);
+    }
+
+    fn visit_block_statement(&mut self, node: &mut BlockStatement) {
+        self.id(&mut node.id);
+        walk_block_statement(self, node);
+    }
+
+    fn visit_empty_statement(&mut self, node: &mut EmptyStatement) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_error_statement(&mut self, node: &mut ErrorStatement) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_if_statement(&mut self, node: &mut IfStatement) {
+        self.id(&mut node.id);
+        walk_if_statement(self, node);
+    }
+
+    fn visit_while_iteration_element(&mut self, node: &mut WhileIterationElement) {
+        self.id(&mut node.id);
+        walk_while_iteration_element(self, node);
+    }
+
+    fn visit_return_statement(&mut self, node: &mut ReturnStatement) {
+        self.id(&mut node.id);
+        walk_return_statement(self, node);
+    }
+
+    fn visit_break_statement(&mut self, node: &mut BreakStatement) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_continue_statement(&mut self, node: &mut ContinueStatement) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_variable_statement(&mut self, node: &mut VariableStatement) {
+        self.id(&mut node.id);
+        walk_variable_statement(self, node);
+    }
+
+    fn visit_expression_statement(&mut self, node: &mut ExpressionStatement) {
+        self.id(&mut node.id);
+        walk_expression_statement(self, node);
+    }
+
+    fn visit_function_declaration(&mut self, node: &mut FunctionDeclarationElement) {
+        self.id(&mut node.id);
+        walk_function_declaration(self, node);
+    }
+
+    fn visit_formal_parameter(&mut self, node: &mut FormalParameterArg) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_class_declaration(&mut self, node: &mut ClassDeclarationElement) {
+        self.id(&mut node.id);
+        walk_class_declaration(self, node);
+    }
+
+    fn visit_class_body(&mut self, node: &mut ClassBody) {
+        self.id(&mut node.id);
+        walk_class_body(self, node);
+    }
+
+    fn visit_class_constructor_declaration(&mut self, node: &mut ClassConstructorElement) {
+        self.id(&mut node.id);
+        walk_class_constructor_declaration(self, node);
+    }
+
+    fn visit_class_method_declaration(&mut self, node: &mut ClassMethodElement) {
+        self.id(&mut node.id);
+        walk_class_method_declaration(self, node);
+    }
+
+    fn visit_class_field_declaration(&mut self, node: &mut ClassFieldElement) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_member_dot(&mut self, node: &mut MemberDotExpression) {
+        self.id(&mut node.id);
+        walk_member_dot(self, node);
+    }
+
+    fn visit_member_index(&mut self, node: &mut MemberIndexExpression) {
+        self.id(&mut node.id);
+        walk_member_index(self, node);
+    }
+
+    fn visit_new(&mut self, node: &mut NewExpression) {
+        self.id(&mut node.id);
+        walk_new(self, node);
+    }
+
+    fn visit_cast_expression(&mut self, node: &mut CastExpression) {
+        self.id(&mut node.id);
+        walk_cast_expression(self, node);
+    }
+
+    fn visit_identifier_expression(&mut self, node: &mut IdentifierExpression) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_argument_expression(&mut self, node: &mut ArgumentsExpression) {
+        self.id(&mut node.id);
+        walk_argument_expression(self, node);
+    }
+
+    fn visit_unary_expression(&mut self, node: &mut UnaryExpression) {
+        self.id(&mut node.id);
+        walk_unary_expression(self, node);
+    }
+
+    fn visit_assignment_expression(&mut self, node: &mut BinaryExpression) {
+        self.id(&mut node.id);
+        walk_assignment_expression(self, node);
+    }
+
+    fn visit_binary_expression(&mut self, node: &mut BinaryExpression) {
+        self.id(&mut node.id);
+        walk_binary_expression(self, node);
+    }
+
+    fn visit_this_expression(&mut self, node: &mut ThisExpression) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_error_expression(&mut self, node: &mut ErrorExpression) {
+        self.id(&mut node.id);
+    }
+
+    fn visit_literal(&mut self, node: &mut Literal) {
+        match node {
+            Literal::Array(lit) => self.id(&mut lit.id),
+            Literal::String(lit) => self.id(&mut lit.id),
+            Literal::Integer(lit) => self.id(&mut lit.id),
+            Literal::Float(lit) => self.id(&mut lit.id),
+            Literal::Boolean(lit) => self.id(&mut lit.id),
+        }
+        walk_literal(self, node);
+    }
+}
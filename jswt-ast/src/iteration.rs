@@ -1,16 +1,22 @@
-use jswt_common::Span;
-use jswt_derive::{FromEnumVariant, Spannable};
+use jswt_common::{NodeId, Span};
+use jswt_derive::{FromEnumVariant, Identifiable, Spannable, Walk};
+use serde::Serialize;
 
 use crate::{SingleExpression, BlockStatement};
 
-#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Clone)]
+#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_iteration_statement, group = "statement")]
 pub enum IterationStatement {
     While(WhileIterationElement),
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_while_iteration_element, group = "statement")]
 pub struct WhileIterationElement {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub expression: SingleExpression,
+    #[walk]
     pub block: BlockStatement,
 }
@@ -1,5 +1,9 @@
+mod walk;
+
+mod annotation;
 mod expression;
 mod ident;
+mod ids;
 mod iteration;
 mod literal;
 mod statement;
@@ -8,149 +12,223 @@ mod variable;
 mod visitor;
 
 pub mod mut_visit;
-pub mod transform;
 pub mod visit;
 
+pub use annotation::*;
 pub use expression::*;
 pub use ident::*;
+pub use ids::assign_node_ids;
 pub use iteration::*;
 pub use literal::*;
 pub use statement::*;
 pub use types::*;
 pub use variable::*;
 pub use visitor::*;
+pub use walk::Walk;
 
-use jswt_common::{Span, Spannable, Typeable};
-use jswt_derive::{FromEnumVariant, Spannable};
+use jswt_common::{NodeId, Span, Spannable};
+use jswt_derive::{FromEnumVariant, Identifiable, Spannable, Walk};
+use serde::Serialize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Ast {
     pub program: Program,
+    // Id the next node added to the tree receives
+    #[serde(skip)]
+    next_id: u32,
 }
 
 impl Ast {
-    pub fn new(program: Program) -> Self {
-        Self { program }
+    /// Every node in the program is given an id when the tree is built
+    pub fn new(mut program: Program) -> Self {
+        let next_id = assign_node_ids(&mut program, 0);
+        Self { program, next_id }
+    }
+
+    /// Numbers the nodes a lowering pass added to the tree. They come
+    /// after every id the tree has used so they can't pick up side table
+    /// entries of a node that was lowered away.
+    pub fn assign_ids(&mut self) {
+        self.next_id = assign_node_ids(&mut self.program, self.next_id);
+    }
+
+    /// Adds the files of another program to the tree. Their nodes are
+    /// numbered after the ones already in it.
+    pub fn append(&mut self, program: Program) {
+        self.program.files.extend(program.files);
+        self.assign_ids();
+    }
+}
+
+impl Default for Ast {
+    fn default() -> Self {
+        Self::new(Program {
+            id: NodeId::DUMMY,
+            files: vec![],
+        })
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_program, group = "program")]
 pub struct Program {
+    pub id: NodeId,
+    #[walk]
     pub files: Vec<File>,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_file, group = "program")]
 pub struct File {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub source_elements: SourceElements,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Walk, Clone, Serialize)]
+#[walk(visit_source_elements, group = "program")]
 pub struct SourceElements {
     pub span: Span,
+    #[walk]
     pub source_elements: Vec<SourceElement>,
 }
 
-#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Clone)]
+#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_source_element, group = "program")]
 pub enum SourceElement {
     FunctionDeclaration(FunctionDeclarationElement),
     ClassDeclaration(ClassDeclarationElement),
     Statement(StatementElement),
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_class_declaration, group = "statement")]
 pub struct ClassDeclarationElement {
+    pub id: NodeId,
     pub span: Span,
+    /// Span of the `/** */` doc comment before the class and its annotations
+    pub doc: Option<Span>,
+    pub annotations: Vec<Annotation>,
     pub ident: Identifier,
+    #[walk]
     pub body: ClassBody,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_class_body, group = "statement")]
 pub struct ClassBody {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub class_elements: Vec<ClassElement>,
 }
 
-#[derive(Debug, PartialEq, Spannable, FromEnumVariant, Clone)]
+#[derive(Debug, PartialEq, Spannable, FromEnumVariant, Identifiable, Walk, Clone, Serialize)]
 pub enum ClassElement {
     Constructor(ClassConstructorElement),
     Field(ClassFieldElement),
     Method(ClassMethodElement),
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_class_constructor_declaration, group = "statement")]
 pub struct ClassConstructorElement {
+    pub id: NodeId,
     pub span: Span,
+    /// Doc comment before the constructor and its annotations
+    pub doc: Option<Span>,
+    pub annotations: Vec<Annotation>,
+    #[walk]
     pub params: FormalParameterList,
+    #[walk]
     pub body: BlockStatement,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_class_method_declaration, group = "statement")]
 pub struct ClassMethodElement {
+    pub id: NodeId,
     pub span: Span,
+    /// Doc comment before the method
+    pub doc: Option<Span>,
     pub annotations: Vec<Annotation>,
     pub ident: Identifier,
+    #[walk]
     pub params: FormalParameterList,
     pub returns: Option<TypeAnnotation>,
+    #[walk]
     pub body: BlockStatement,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_class_field_declaration, group = "statement")]
 pub struct ClassFieldElement {
+    pub id: NodeId,
     pub span: Span,
+    /// Doc comment before the field
+    pub doc: Option<Span>,
     pub annotations: Vec<Annotation>,
+    /// Readonly fields can only be assigned in the constructor
+    pub readonly: bool,
     pub ident: Identifier,
     pub type_annotation: TypeAnnotation,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_function_declaration, group = "statement")]
 pub struct FunctionDeclarationElement {
+    pub id: NodeId,
     pub span: Span,
+    /// Doc comment before the function and its annotations
+    pub doc: Option<Span>,
     pub decorators: FunctionDecorators,
     pub ident: Identifier,
+    #[walk]
     pub params: FormalParameterList,
     pub returns: Option<TypeAnnotation>,
+    #[walk]
     pub body: BlockStatement,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct FunctionDecorators {
     pub annotations: Vec<Annotation>,
     pub export: bool,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
-pub struct Annotation {
-    pub span: Span,
-    pub name: Identifier,
-    pub expr: Option<SingleExpression>,
-}
-
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Walk, Clone, Serialize)]
 pub struct FormalParameterList {
     pub span: Span,
+    #[walk]
     pub parameters: Vec<FormalParameterArg>,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_formal_parameter)]
 pub struct FormalParameterArg {
+    pub id: NodeId,
     pub span: Span,
+    pub annotations: Vec<Annotation>,
     pub ident: Identifier,
     pub type_annotation: TypeAnnotation,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Clone, Serialize)]
 pub struct FunctionBody {
     pub span: Span,
     pub source_elements: SourceElements,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Walk, Clone, Serialize)]
+#[walk(visit_statement_list, group = "statement")]
 pub struct StatementList {
+    #[walk]
     pub statements: Vec<StatementElement>,
 }
 
-#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Clone)]
+#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Walk, Clone, Serialize)]
+#[walk(visit_assignable_element, group = "expression")]
 pub enum AssignableElement {
     Identifier(Identifier),
 }
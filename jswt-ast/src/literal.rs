@@ -1,9 +1,11 @@
-use jswt_common::{Span, Type};
-use jswt_derive::{FromEnumVariant, Spannable, Typeable};
+use jswt_common::{Atom, NodeId, Span};
+use jswt_derive::{FromEnumVariant, Identifiable, Spannable, Walk};
+use serde::Serialize;
 
 use crate::SingleExpression;
 
-#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_literal, group = "expression")]
 pub enum Literal {
     Array(ArrayLiteral),
     String(StringLiteral),
@@ -12,37 +14,38 @@ pub enum Literal {
     Boolean(BooleanLiteral),
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
 pub struct BooleanLiteral {
+    pub id: NodeId,
     pub span: Span,
     pub value: bool,
-    pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
 pub struct IntegerLiteral {
+    pub id: NodeId,
     pub span: Span,
-    pub value: i32,
-    pub ty: Type,
+    pub value: i64,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
 pub struct FloatingPointLiteral {
+    pub id: NodeId,
     pub span: Span,
-    pub value: f32,
-    pub ty: Type,
+    pub value: f64,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
 pub struct StringLiteral {
+    pub id: NodeId,
     pub span: Span,
-    pub value: &'static str,
-    pub ty: Type,
+    pub value: Atom,
 }
 
-#[derive(Debug, PartialEq, Spannable, Typeable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
 pub struct ArrayLiteral {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub elements: Vec<SingleExpression>,
-    pub ty: Type,
 }
@@ -1,57 +1,108 @@
-use jswt_derive::FromEnumVariant;
+use jswt_common::NodeId;
+use jswt_derive::{FromEnumVariant, Identifiable, Walk};
+use serde::Serialize;
 
 use crate::{
     iteration::IterationStatement, variable::VariableModifier, AssignableElement, SingleExpression,
     Span, Spannable, StatementList, TypeAnnotation,
 };
 
-#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Clone)]
+#[derive(Debug, PartialEq, FromEnumVariant, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_statement_element, group = "statement")]
 pub enum StatementElement {
     Block(BlockStatement),
     Empty(EmptyStatement),
     If(IfStatement),
     Iteration(IterationStatement),
     Return(ReturnStatement),
+    Break(BreakStatement),
+    Continue(ContinueStatement),
     Variable(VariableStatement),
     Expression(ExpressionStatement),
+    Error(ErrorStatement),
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_block_statement, group = "statement")]
 pub struct BlockStatement {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub statements: StatementList,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_empty_statement, group = "statement")]
 pub struct EmptyStatement {
+    pub id: NodeId,
     pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+/// Placeholder for the tokens skipped while recovering
+/// from a statement that failed to parse
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_error_statement, group = "statement")]
+pub struct ErrorStatement {
+    pub id: NodeId,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_if_statement, group = "statement")]
 pub struct IfStatement {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub condition: SingleExpression,
+    #[walk]
     pub consequence: Box<StatementElement>,
+    #[walk]
     pub alternative: Option<Box<StatementElement>>,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_return_statement, group = "statement")]
 pub struct ReturnStatement {
+    pub id: NodeId,
     pub span: Span,
-    pub expression: SingleExpression,
+    #[walk]
+    pub expression: Option<SingleExpression>,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_break_statement, group = "statement")]
+pub struct BreakStatement {
+    pub id: NodeId,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_continue_statement, group = "statement")]
+pub struct ContinueStatement {
+    pub id: NodeId,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_variable_statement, group = "statement")]
 pub struct VariableStatement {
+    pub id: NodeId,
     pub span: Span,
     pub modifier: VariableModifier,
+    #[walk]
     pub target: AssignableElement,
-    pub expression: SingleExpression,
+    /// Variables declared without an initializer must be
+    /// assigned before they are read
+    #[walk]
+    pub expression: Option<SingleExpression>,
     pub type_annotation: Option<TypeAnnotation>,
 }
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Identifiable, Walk, Clone, Serialize)]
+#[walk(visit_expression_statement, group = "statement")]
 pub struct ExpressionStatement {
+    pub id: NodeId,
     pub span: Span,
+    #[walk]
     pub expression: SingleExpression,
 }
@@ -1,20 +1,22 @@
 use std::borrow::Cow;
 
-use jswt_common::{Span, Type};
+use jswt_common::Span;
 use jswt_derive::Spannable;
+use jswt_types::Type;
+use serde::Serialize;
 
-#[derive(Debug, PartialEq, Spannable, Clone)]
+#[derive(Debug, PartialEq, Spannable, Clone, Serialize)]
 pub struct TypeAnnotation {
     pub span: Span,
     pub ty: Type,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct IdentifierType {
     pub name: Cow<'static, str>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct ArrayType {
     pub ident: Box<Type>,
 }
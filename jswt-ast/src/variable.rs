@@ -1,6 +1,7 @@
 use jswt_common::{Span, Spannable};
+use serde::Serialize;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub enum VariableModifier {
     Let(Span),
     Const(Span),
@@ -0,0 +1,85 @@
+use crate::mut_visit::MutVisitor;
+use crate::visit::Visitor;
+
+/// Implemented for every node with `#[derive(Walk)]`
+pub trait Walk {
+    /// Hands the node to the visitor method for its kind
+    fn accept<V: Visitor>(&self, visitor: &mut V);
+
+    fn accept_mut<V: MutVisitor>(&mut self, visitor: &mut V);
+
+    /// Hands each child of the node to the visitor
+    fn walk<V: Visitor>(&self, visitor: &mut V);
+
+    fn walk_mut<V: MutVisitor>(&mut self, visitor: &mut V);
+}
+
+impl<T: Walk> Walk for Box<T> {
+    fn accept<V: Visitor>(&self, visitor: &mut V) {
+        (**self).accept(visitor);
+    }
+
+    fn accept_mut<V: MutVisitor>(&mut self, visitor: &mut V) {
+        (**self).accept_mut(visitor);
+    }
+
+    fn walk<V: Visitor>(&self, visitor: &mut V) {
+        (**self).walk(visitor);
+    }
+
+    fn walk_mut<V: MutVisitor>(&mut self, visitor: &mut V) {
+        (**self).walk_mut(visitor);
+    }
+}
+
+impl<T: Walk> Walk for Option<T> {
+    fn accept<V: Visitor>(&self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.accept(visitor);
+        }
+    }
+
+    fn accept_mut<V: MutVisitor>(&mut self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.accept_mut(visitor);
+        }
+    }
+
+    fn walk<V: Visitor>(&self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.walk(visitor);
+        }
+    }
+
+    fn walk_mut<V: MutVisitor>(&mut self, visitor: &mut V) {
+        if let Some(node) = self {
+            node.walk_mut(visitor);
+        }
+    }
+}
+
+impl<T: Walk> Walk for Vec<T> {
+    fn accept<V: Visitor>(&self, visitor: &mut V) {
+        for node in self {
+            node.accept(visitor);
+        }
+    }
+
+    fn accept_mut<V: MutVisitor>(&mut self, visitor: &mut V) {
+        for node in self {
+            node.accept_mut(visitor);
+        }
+    }
+
+    fn walk<V: Visitor>(&self, visitor: &mut V) {
+        for node in self {
+            node.walk(visitor);
+        }
+    }
+
+    fn walk_mut<V: MutVisitor>(&mut self, visitor: &mut V) {
+        for node in self {
+            node.walk_mut(visitor);
+        }
+    }
+}
@@ -1,9 +1,15 @@
 mod symbols;
+mod types;
 
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
+use std::collections::{BTreeSet, HashMap};
 use symbols::{WastSymbol, WastSymbolTable};
+pub use types::value_type;
 
 use jswt_ast::*;
+use jswt_common::Identifiable;
+use jswt_symbols::{DefId, ResolutionTable};
+use jswt_types::{Type, TypeTable};
 use jswt_wast::*;
 
 #[derive(Debug)]
@@ -16,6 +22,18 @@ pub struct CodeGenerator {
     scopes: Vec<InstructionScope>,
     symbols: WastSymbolTable,
     label_counter: usize,
+    /// Labels of the loops enclosing the current statement. Each loop is
+    /// wrapped in a block with the same label that `break` branches out of
+    loops: Vec<usize>,
+    /// Functions annotated with both `@inline` and `@wast` whose bodies
+    /// are expanded at their call sites
+    inlines: HashMap<Cow<'static, str>, InlineFunction>,
+    /// Types inferred by the type checker for the nodes of the tree
+    types: TypeTable,
+    /// Declarations that identifiers resolve to
+    resolutions: ResolutionTable,
+    /// Declarations of the module's global variables
+    globals: BTreeSet<DefId>,
 }
 
 #[derive(Debug)]
@@ -23,21 +41,43 @@ struct InstructionScope {
     instructions: Vec<Instruction>,
 }
 
+#[derive(Debug)]
+struct InlineFunction {
+    params: Vec<(Cow<'static, str>, ValueType)>,
+    returns: Option<ValueType>,
+    code: Cow<'static, str>,
+}
+
 impl Default for CodeGenerator {
     fn default() -> Self {
         Self {
             module: Default::default(),
             scopes: Default::default(),
             symbols: WastSymbolTable::new(),
-            label_counter: 0,
+            // Label 0 is reserved for the function body block
+            label_counter: 1,
+            loops: vec![],
+            inlines: HashMap::new(),
+            types: TypeTable::default(),
+            resolutions: ResolutionTable::default(),
+            globals: BTreeSet::new(),
         }
     }
 }
 
 impl CodeGenerator {
+    pub fn new(types: TypeTable, resolutions: ResolutionTable) -> Self {
+        Self {
+            types,
+            resolutions,
+            ..Default::default()
+        }
+    }
+
     pub fn generate_module(&mut self, ast: &Ast) -> &Module {
         // TODO - we should be accepting builtins externally from the env
         // This is a stop gap so tests don't break
+        self.collect_inline_functions(&ast.program);
         self.visit_program(&ast.program);
         &self.module
     }
@@ -47,6 +87,16 @@ impl CodeGenerator {
         self.module.imports.len() - 1
     }
 
+    /// Whether an identifier refers to a global variable. Identifiers
+    /// synthesized during lowering aren't resolved so they fall back to
+    /// being looked up by name.
+    fn is_global(&self, node: &IdentifierExpression) -> bool {
+        match self.resolutions.get(node.id) {
+            Some(def) => self.globals.contains(&def),
+            None => self.symbols.lookup_global(&node.ident.value).is_some(),
+        }
+    }
+
     fn push_global(&mut self, global: GlobalType) -> usize {
         self.module.globals.push(global);
         self.module.globals.len() - 1
@@ -88,10 +138,107 @@ impl CodeGenerator {
     fn pop_instruction_scope(&mut self) -> Option<InstructionScope> {
         self.scopes.pop()
     }
+
+    /// Collects the functions that can be inlined before any call site
+    /// is generated since calls may precede the function declaration
+    fn collect_inline_functions(&mut self, program: &Program) {
+        let functions = program
+            .files
+            .iter()
+            .flat_map(|file| file.source_elements.source_elements.iter())
+            .filter_map(|element| match element {
+                SourceElement::FunctionDeclaration(function) => Some(function),
+                _ => None,
+            });
+
+        for function in functions {
+            let annotations = &function.decorators.annotations;
+            if !annotations.iter().any(|a| a.name.value == "inline") {
+                continue;
+            }
+            let code = annotations
+                .iter()
+                .filter(|a| a.name.value == "wast")
+                .find_map(|a| a.string_argument("code"));
+
+            if let Some(code) = code {
+                let params = function
+                    .params
+                    .parameters
+                    .iter()
+                    .map(|param| {
+                        let value_type = types::value_type(&param.type_annotation.ty);
+                        (param.ident.value.clone().into(), value_type)
+                    })
+                    .collect();
+                self.inlines.insert(
+                    function.ident.value.clone().into(),
+                    InlineFunction {
+                        params,
+                        returns: function
+                            .returns
+                            .as_ref()
+                            .map(|returns| types::value_type(&returns.ty)),
+                        code: code.to_owned().into(),
+                    },
+                );
+            }
+        }
+    }
+
+    /// Expands the body of an inline function in place of a call. When
+    /// every parameter is read exactly once and in order the arguments
+    /// are substituted in place of the reads. Otherwise the arguments are
+    /// stored in synthetic locals so each one is evaluated exactly once.
+    fn inline_call(&mut self, function: &str, arguments: Vec<Instruction>) -> Instruction {
+        let inline = &self.inlines[function];
+        let (params, returns) = (inline.params.clone(), inline.returns);
+        let mut code = inline.code.to_string();
+
+        let reads: Option<Vec<usize>> = params
+            .iter()
+            .map(|(name, _)| single_read(&code, name))
+            .collect();
+        if let Some(reads) = reads.filter(|reads| reads.windows(2).all(|w| w[0] < w[1])) {
+            // Substitute from the back so earlier offsets stay valid
+            for ((index, (name, _)), argument) in reads.iter().zip(&params).zip(arguments).rev() {
+                let read = format!("(local.get ${})", name);
+                code.replace_range(*index..index + read.len(), &argument.to_string());
+            }
+            return Instruction::RawWast(code.into());
+        }
+
+        let mut block = match returns {
+            Some(ty) => format!("(block (result {})", ty),
+            None => "(block".to_string(),
+        };
+        for ((name, ty), argument) in params.into_iter().zip(arguments) {
+            let local = self.symbols.define_synthetic_local(ty);
+            code = substitute_local(&code, &name, &local);
+            block += &format!(" {}", Instruction::LocalSet(local, Box::new(argument)));
+        }
+        block += &format!(" {})", code);
+        Instruction::RawWast(block.into())
+    }
+
+    /// Type the checker inferred for the expression. This is used to
+    /// select conversion instructions
+    fn expression_type(&self, node: &SingleExpression) -> Type {
+        self.types.ty(node.id())
+    }
 }
 
 impl ProgramVisitor<()> for CodeGenerator {
     fn visit_program(&mut self, node: &Program) {
+        // Globals can be used by functions declared before them
+        for file in &node.files {
+            for element in &file.source_elements.source_elements {
+                if let SourceElement::Statement(StatementElement::Variable(variable)) = element {
+                    self.globals.insert(DefId(variable.id));
+                }
+            }
+        }
+
         // Push global scope
         self.symbols.push_scope();
         for file in &node.files {
@@ -127,10 +274,13 @@ impl StatementVisitor<()> for CodeGenerator {
             StatementElement::Block(stmt) => self.visit_block_statement(stmt),
             StatementElement::Empty(stmt) => self.visit_empty_statement(stmt),
             StatementElement::Return(stmt) => self.visit_return_statement(stmt),
+            StatementElement::Break(stmt) => self.visit_break_statement(stmt),
+            StatementElement::Continue(stmt) => self.visit_continue_statement(stmt),
             StatementElement::Variable(stmt) => self.visit_variable_statement(stmt),
             StatementElement::Expression(stmt) => self.visit_expression_statement(stmt),
             StatementElement::If(stmt) => self.visit_if_statement(stmt),
             StatementElement::Iteration(stmt) => self.visit_iteration_statement(stmt),
+            StatementElement::Error(stmt) => self.visit_error_statement(stmt),
         }
     }
 
@@ -142,6 +292,10 @@ impl StatementVisitor<()> for CodeGenerator {
         // No-op
     }
 
+    fn visit_error_statement(&mut self, _: &ErrorStatement) {
+        unreachable!("code generation should not run on a tree with parse errors")
+    }
+
     fn visit_if_statement(&mut self, node: &IfStatement) {
         let cond = self.visit_single_expression(&node.condition);
 
@@ -173,6 +327,7 @@ impl StatementVisitor<()> for CodeGenerator {
     fn visit_while_iteration_element(&mut self, node: &WhileIterationElement) {
         let loop_label = self.label_counter;
         self.label_counter += 1;
+        self.loops.push(loop_label);
 
         self.push_instruction_scope();
 
@@ -195,32 +350,72 @@ impl StatementVisitor<()> for CodeGenerator {
         ));
 
         let loop_scope = self.pop_instruction_scope().unwrap();
-        self.push_instruction(Instruction::Loop(loop_label, loop_scope.instructions));
+        self.loops.pop();
+        self.push_instruction(Instruction::Block(
+            loop_label,
+            vec![Instruction::Loop(loop_label, loop_scope.instructions)],
+        ));
     }
 
     fn visit_return_statement(&mut self, node: &ReturnStatement) {
-        let exp = self.visit_single_expression(&node.expression);
-        self.push_instruction(Instruction::Return(Box::new(exp)));
+        match &node.expression {
+            Some(expression) => {
+                let exp = self.visit_single_expression(expression);
+                self.push_instruction(Instruction::Return(Box::new(exp)));
+            }
+            // Nothing to return, break straight out of the function block
+            None => self.push_instruction(Instruction::BrBlock(0)),
+        }
+    }
+
+    fn visit_break_statement(&mut self, _: &BreakStatement) {
+        let label = *self.loops.last().expect("break outside of a loop");
+        self.push_instruction(Instruction::BrBlock(label));
+    }
+
+    fn visit_continue_statement(&mut self, _: &ContinueStatement) {
+        let label = *self.loops.last().expect("continue outside of a loop");
+        self.push_instruction(Instruction::BrLoop(label));
     }
 
     fn visit_variable_statement(&mut self, node: &VariableStatement) {
         // This should be assignment local.set, global.set
         let target = self.visit_assignable_element(&node.target);
-        let exp = self.visit_single_expression(&node.expression);
+        let exp = node
+            .expression
+            .as_ref()
+            .map(|expression| self.visit_single_expression(expression));
+        // Variables without a type annotation always have an initializer
+        let ty = match (&node.type_annotation, &node.expression) {
+            (Some(annotation), _) => annotation.ty.clone(),
+            (None, Some(expression)) => self.expression_type(expression),
+            (None, None) => unreachable!("variable without a type or initializer"),
+        };
+        let value_type = types::value_type(&ty);
         match target {
             Instruction::GlobalSet(name, _) => {
+                self.symbols
+                    .define(name.clone(), WastSymbol::Global(value_type));
                 self.push_global(GlobalType {
                     name,
-                    ty: ValueType::I32,
+                    ty: value_type,
                     mutable: true, // TODO - check mutability
-                    initializer: exp,
+                    initializer: exp.unwrap_or_else(|| types::zero_value(value_type)),
                 });
             }
             Instruction::LocalSet(name, _) => {
-                let exp = Instruction::LocalSet(name, Box::new(exp));
-                self.push_instruction(exp)
+                self.symbols
+                    .define(name.clone(), WastSymbol::Local(value_type));
+                // Locals are zero initialized by the runtime
+                if let Some(exp) = exp {
+                    self.push_instruction(Instruction::LocalSet(name, Box::new(exp)))
+                }
+            }
+            _ => {
+                if let Some(exp) = exp {
+                    self.push_instruction(exp)
+                }
             }
-            _ => self.push_instruction(exp),
         }
     }
 
@@ -236,7 +431,7 @@ impl StatementVisitor<()> for CodeGenerator {
     }
 
     fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
-        let function_name = node.ident.value.clone();
+        let function_name = &node.ident.value;
 
         // Push the scope for the function body
         self.symbols.push_scope();
@@ -244,19 +439,21 @@ impl StatementVisitor<()> for CodeGenerator {
         // Push Symbols for Params. We need this in case the scope
         // needs to declare synthetic local variables
         for (index, arg) in node.params.parameters.iter().enumerate() {
+            let value_type = types::value_type(&arg.type_annotation.ty);
             // Add to symbol table
-            self.symbols.define(
-                arg.ident.value.clone(),
-                WastSymbol::Param(index, ValueType::I32),
-            );
-            // Todo, convert to ValueType
-            type_params.push((arg.ident.value.clone(), ValueType::I32))
+            self.symbols
+                .define(&arg.ident.value, WastSymbol::Param(index, value_type));
+            type_params.push((arg.ident.value.clone().into(), value_type))
         }
 
         // Resolve return Value
+        let ret = node
+            .returns
+            .as_ref()
+            .map(|returns| types::value_type(&returns.ty));
         let ty = FunctionType {
             params: type_params,
-            ret: node.returns.as_ref().map(|_| ValueType::I32),
+            ret,
         };
         // Add type definition to type index
         let type_idx = self.push_type(ty);
@@ -264,7 +461,8 @@ impl StatementVisitor<()> for CodeGenerator {
         // Push a new Instruction scope to hold emitted instructions
         self.push_instruction_scope();
 
-        // Resolve annotations
+        // Resolve annotations. Their arguments have already been
+        // validated against the annotation registry during semantic analysis.
         let mut has_inlined_body = false;
         let mut is_predefined_function = false;
         for annotation in &node.decorators.annotations {
@@ -272,26 +470,23 @@ impl StatementVisitor<()> for CodeGenerator {
                 // The "wast" annotation allows the developer to emit
                 // WAST instructions directily into the instruction scope
                 // of the function.
-                "wast" => match &annotation.expr {
-                    Some(SingleExpression::Literal(Literal::String(string_lit))) => {
+                "wast" => {
+                    if let Some(code) = annotation.string_argument("code") {
                         has_inlined_body = true;
-                        self.push_instruction(Instruction::RawWast(string_lit.value.into()));
+                        self.push_instruction(Instruction::RawWast(code.to_owned().into()));
                     }
-                    _ => todo!(),
-                },
-                "native" => match &annotation.expr {
-                    Some(SingleExpression::Literal(Literal::String(lit))) => {
+                }
+                "native" => {
+                    if let Some(module) = annotation.string_argument("module") {
                         has_inlined_body = true;
                         is_predefined_function = true;
                         self.push_import(Import::Function(FunctionImport {
-                            name: function_name.clone(),
+                            name: function_name.into(),
                             type_idx,
-                            module: lit.value.into(),
+                            module: module.to_owned().into(),
                         }));
                     }
-                    _ => todo!(),
-                },
-                "inline" => {}
+                }
                 _ => {}
             }
         }
@@ -309,9 +504,8 @@ impl StatementVisitor<()> for CodeGenerator {
             // Add synthetic return value
             // This is to make dealing with branching returns easier to manage
             // We're using a keyword here to prevent users from accidentally shadowing the value
-            if node.returns.is_some() {
-                self.symbols
-                    .define("return", WastSymbol::Local(ValueType::I32));
+            if let Some(ret) = ret {
+                self.symbols.define("return", WastSymbol::Local(ret));
 
                 // We're pushing the synthetic return to the end of the function
                 instructions.push(Instruction::SynthReturn);
@@ -331,7 +525,7 @@ impl StatementVisitor<()> for CodeGenerator {
 
         if !is_predefined_function {
             let function = Function {
-                name: function_name.clone(),
+                name: function_name.into(),
                 type_idx,
                 instructions,
             };
@@ -341,7 +535,7 @@ impl StatementVisitor<()> for CodeGenerator {
             if node.decorators.export {
                 let desc = FunctionExport {
                     function_idx,
-                    name: function_name.clone(),
+                    name: function_name.into(),
                 };
                 self.push_export(Export::Function(desc));
             }
@@ -399,6 +593,8 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
             SingleExpression::This(exp) => self.visit_this_expression(exp),
             SingleExpression::MemberDot(exp) => self.visit_member_dot(exp),
             SingleExpression::New(exp) => self.visit_new(exp),
+            SingleExpression::Cast(exp) => self.visit_cast_expression(exp),
+            SingleExpression::Error(exp) => self.visit_error_expression(exp),
         }
     }
 
@@ -410,16 +606,15 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
             AssignableElement::Identifier(ident) => {
                 let name = &ident.value;
                 // Check if this element has been defined
-                if self.symbols.lookup(name.clone()).is_none() {
+                if self.symbols.lookup(name).is_none() {
                     if self.symbols.depth() == 1 {
                         self.symbols
-                            .define(name.clone(), WastSymbol::Global(ValueType::I32));
-                        return Instruction::GlobalSet(name.clone(), Box::new(Instruction::Noop));
+                            .define(name, WastSymbol::Global(ValueType::I32));
+                        return Instruction::GlobalSet(name.into(), Box::new(Instruction::Noop));
                     } else {
-                        self.symbols
-                            .define(name.clone(), WastSymbol::Local(ValueType::I32));
+                        self.symbols.define(name, WastSymbol::Local(ValueType::I32));
 
-                        return Instruction::LocalSet(name.clone(), Box::new(Instruction::Noop));
+                        return Instruction::LocalSet(name.into(), Box::new(Instruction::Noop));
                     }
                 }
                 unreachable!()
@@ -428,7 +623,7 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
     }
 
     fn visit_member_dot(&mut self, _: &MemberDotExpression) -> Instruction {
-        todo!()
+        unreachable!("member access should be lowered into loads and calls")
     }
 
     fn visit_member_index(&mut self, node: &MemberIndexExpression) -> Instruction {
@@ -441,12 +636,18 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
         todo!()
     }
 
+    fn visit_cast_expression(&mut self, node: &CastExpression) -> Instruction {
+        let value = self.visit_single_expression(&node.expression);
+        let from = self.expression_type(&node.expression);
+        types::cast(value, &from, &node.target.ty)
+    }
+
     fn visit_identifier_expression(&mut self, node: &IdentifierExpression) -> Instruction {
         let target = &node.ident.value;
-        if self.symbols.lookup_global(target.clone()).is_some() {
-            Instruction::GlobalGet(target.clone())
+        if self.is_global(node) {
+            Instruction::GlobalGet(target.into())
         } else {
-            Instruction::LocalGet(target.clone())
+            Instruction::LocalGet(target.into())
         }
     }
 
@@ -460,7 +661,12 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
                 .map(|exp| self.visit_single_expression(exp))
                 .collect();
 
-            return Instruction::Call(ident_exp.ident.value.clone(), instructions);
+            // Synthetic locals can only be declared within a function
+            let function = &ident_exp.ident.value;
+            if self.inlines.contains_key(function.as_str()) && self.symbols.depth() > 1 {
+                return self.inline_call(function, instructions);
+            }
+            return Instruction::Call(function.into(), instructions);
         }
 
         // Other targets for function calls.
@@ -471,8 +677,19 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
         let exp = self.visit_single_expression(&node.expr);
         match node.op {
             UnaryOperator::Plus(_) => todo!(),
-            UnaryOperator::Minus(_) => {
-                Instruction::I32Sub(Box::new(Instruction::I32Const(0)), Box::new(exp))
+            UnaryOperator::Minus(_) => match types::value_type(&self.expression_type(&node.expr)) {
+                ValueType::I32 => {
+                    Instruction::I32Sub(Box::new(Instruction::I32Const(0)), Box::new(exp))
+                }
+                ValueType::I64 => {
+                    Instruction::I64Sub(Box::new(Instruction::I64Const(0)), Box::new(exp))
+                }
+                ValueType::F32 => Instruction::F32Neg(Box::new(exp)),
+                ValueType::F64 => Instruction::F64Neg(Box::new(exp)),
+            },
+            // Booleans are 0 or 1 so they can't be inverted bit by bit
+            UnaryOperator::Not(_) if self.expression_type(&node.expr) == Type::BOOLEAN => {
+                Instruction::I32Eqz(Box::new(exp))
             }
             UnaryOperator::Not(_) => {
                 Instruction::I32Xor(Box::new(exp), Box::new(Instruction::I32Const(-1)))
@@ -499,12 +716,12 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
             SingleExpression::Identifier(ident_exp) => {
                 let name = &ident_exp.ident.value;
                 // figure out the scope of the variable
-                let isr = if self.symbols.lookup_global(name.clone()).is_some() {
+                let isr = if self.is_global(ident_exp) {
                     Instruction::GlobalSet
                 } else {
                     Instruction::LocalSet
                 };
-                isr(name.clone(), Box::new(rhs))
+                isr(name.into(), Box::new(rhs))
             }
             SingleExpression::MemberIndex(exp) => {
                 let index_ptr = self.visit_member_index(exp);
@@ -513,6 +730,9 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
             SingleExpression::Unary(_) => todo!(),
             SingleExpression::Assignment(_) => todo!(),
             SingleExpression::New(_) => todo!(),
+            SingleExpression::Cast(_) => {
+                unreachable!("casts are rejected as assignment targets by the type checker")
+            }
             SingleExpression::Arguments(_) => todo!(),
             SingleExpression::Multiplicative(_) => todo!(),
             SingleExpression::Bitwise(_) => todo!(),
@@ -522,6 +742,9 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
             SingleExpression::MemberDot(_) => todo!(),
             SingleExpression::This(_) => todo!(),
             SingleExpression::Literal(_) => todo!(),
+            SingleExpression::Error(_) => {
+                unreachable!("code generation should not run on a tree with parse errors")
+            }
         }
     }
 
@@ -551,11 +774,22 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
         todo!()
     }
 
+    fn visit_error_expression(&mut self, _: &ErrorExpression) -> Instruction {
+        unreachable!("code generation should not run on a tree with parse errors")
+    }
+
     fn visit_literal(&mut self, node: &Literal) -> Instruction {
         match node {
             Literal::String(_) => todo!(),
-            Literal::Integer(lit) => Instruction::I32Const(lit.value),
-            Literal::Float(_) => todo!(),
+            // Numeric literals take the width of the type they were checked against
+            Literal::Integer(lit) => match types::value_type(&self.types.ty(lit.id)) {
+                ValueType::I64 => Instruction::I64Const(lit.value),
+                _ => Instruction::I32Const(lit.value as i32),
+            },
+            Literal::Float(lit) => match types::value_type(&self.types.ty(lit.id)) {
+                ValueType::F64 => Instruction::F64Const(lit.value),
+                _ => Instruction::F32Const(lit.value as f32),
+            },
             Literal::Boolean(lit) => match lit.value {
                 // Boolean values in WebAssembly are represented as values of type i32. In a boolean context,
                 // such as a br_if condition, any non-zero value is interpreted as true and 0 is interpreted as false.
@@ -593,6 +827,40 @@ impl ExpressionVisitor<Instruction> for CodeGenerator {
     }
 }
 
+/// Returns the offset of the only `local.get` of the local `name` in raw
+/// WAST, if that is the only reference to the local
+fn single_read(code: &str, name: &str) -> Option<usize> {
+    let read = format!("(local.get ${})", name);
+    match local_references(code, name).len() {
+        1 => code.find(&read),
+        _ => None,
+    }
+}
+
+/// Finds the offsets of references to the local `$name` in raw WAST
+/// without matching locals that share the same prefix
+fn local_references(code: &str, name: &str) -> Vec<usize> {
+    let pattern = format!("${}", name);
+    code.match_indices(&pattern)
+        .map(|(index, _)| index)
+        .filter(|index| {
+            !code[index + pattern.len()..]
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_alphanumeric() || c == '_')
+        })
+        .collect()
+}
+
+/// Replaces references to the local `$from` in raw WAST with `$to`
+fn substitute_local(code: &str, from: &str, to: &str) -> String {
+    let mut code = code.to_string();
+    for index in local_references(&code, from).into_iter().rev() {
+        code.replace_range(index + 1..index + 1 + from.len(), to);
+    }
+    code
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,64 @@
+use jswt_types::{PrimitiveType, Type};
+use jswt_wast::{Instruction, ValueType};
+
+/// Maps a source level type to the wasm value type used to represent it.
+/// Anything that isn't a 64 bit or floating point number is an i32 at
+/// runtime; class references and `ptr` are addresses into linear memory.
+pub fn value_type(ty: &Type) -> ValueType {
+    match ty {
+        Type::Primitive(PrimitiveType::I64) => ValueType::I64,
+        Type::Primitive(PrimitiveType::F32) => ValueType::F32,
+        Type::Primitive(PrimitiveType::F64) => ValueType::F64,
+        _ => ValueType::I32,
+    }
+}
+
+/// The value a variable holds before it is assigned
+pub fn zero_value(ty: ValueType) -> Instruction {
+    match ty {
+        ValueType::I32 => Instruction::I32Const(0),
+        ValueType::I64 => Instruction::I64Const(0),
+        ValueType::F32 => Instruction::F32Const(0.0),
+        ValueType::F64 => Instruction::F64Const(0.0),
+    }
+}
+
+/// Generates the conversion required to cast a value between two types.
+/// Semantic analysis has already rejected invalid casts so anything that
+/// doesn't change the underlying value type is a no-op. Conversions from
+/// and to `u32` treat the value as unsigned.
+pub fn cast(value: Instruction, from: &Type, to: &Type) -> Instruction {
+    let is_ptr = |ty: &Type| *ty == Type::PTR;
+    let is_unsigned = |ty: &Type| *ty == Type::U32;
+    let value = Box::new(value);
+    // Integers are truthy when they aren't zero
+    if *to == Type::BOOLEAN && *from != Type::BOOLEAN {
+        return match value_type(from) {
+            ValueType::I64 => Instruction::I64Ne(value, Box::new(Instruction::I64Const(0))),
+            _ => Instruction::I32Neq(value, Box::new(Instruction::I32Const(0))),
+        };
+    }
+    match (value_type(from), value_type(to)) {
+        // Floats and pointers are reinterpreted bit for bit
+        (ValueType::F32, ValueType::I32) if is_ptr(to) => Instruction::I32ReinterpretF32(value),
+        (ValueType::I32, ValueType::F32) if is_ptr(from) => Instruction::F32ReinterpretI32(value),
+        (ValueType::F32, ValueType::I32) if is_unsigned(to) => Instruction::I32TruncF32U(value),
+        (ValueType::F32, ValueType::I32) => Instruction::I32TruncF32S(value),
+        (ValueType::F64, ValueType::I32) if is_unsigned(to) => Instruction::I32TruncF64U(value),
+        (ValueType::F64, ValueType::I32) => Instruction::I32TruncF64S(value),
+        (ValueType::I64, ValueType::I32) => Instruction::I32WrapI64(value),
+        (ValueType::I32, ValueType::I64) if is_unsigned(from) => Instruction::I64ExtendI32U(value),
+        (ValueType::I32, ValueType::I64) => Instruction::I64ExtendI32S(value),
+        (ValueType::F32, ValueType::I64) => Instruction::I64TruncF32S(value),
+        (ValueType::F64, ValueType::I64) => Instruction::I64TruncF64S(value),
+        (ValueType::I32, ValueType::F32) if is_unsigned(from) => Instruction::F32ConvertI32U(value),
+        (ValueType::I32, ValueType::F32) => Instruction::F32ConvertI32S(value),
+        (ValueType::I64, ValueType::F32) => Instruction::F32ConvertI64S(value),
+        (ValueType::F64, ValueType::F32) => Instruction::F32DemoteF64(value),
+        (ValueType::I32, ValueType::F64) if is_unsigned(from) => Instruction::F64ConvertI32U(value),
+        (ValueType::I32, ValueType::F64) => Instruction::F64ConvertI32S(value),
+        (ValueType::I64, ValueType::F64) => Instruction::F64ConvertI64S(value),
+        (ValueType::F32, ValueType::F64) => Instruction::F64PromoteF32(value),
+        _ => *value,
+    }
+}
@@ -0,0 +1,221 @@
+use lazy_static::lazy_static;
+use serde::{Serialize, Serializer};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::HashSet,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    sync::{Arc, Mutex},
+};
+
+lazy_static! {
+    static ref INTERNER: Mutex<Interner> = Mutex::new(Interner::default());
+}
+
+/// Strings that have been interned. The table holds a reference to each
+/// string so strings that no atom refers to anymore are dropped the next
+/// time the table is purged.
+#[derive(Default)]
+struct Interner {
+    strings: HashSet<Arc<str>>,
+    // Number of strings the table can hold before it's purged
+    capacity: usize,
+}
+
+impl Interner {
+    /// Strings the table starts purging at
+    const MIN_CAPACITY: usize = 1024;
+
+    fn intern(&mut self, value: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(value) {
+            return interned.clone();
+        }
+
+        if self.strings.len() >= self.capacity {
+            self.purge();
+        }
+        let interned: Arc<str> = Arc::from(value);
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    /// Drops strings that are only referenced by the table. The capacity
+    /// grows with the strings still in use so purging stays amortized.
+    fn purge(&mut self) {
+        self.strings.retain(|string| Arc::strong_count(string) > 1);
+        self.capacity = (self.strings.len() * 2).max(Self::MIN_CAPACITY);
+    }
+}
+
+/// Interned string. Each distinct string is stored once while atoms of
+/// it are alive so atoms are cheap to clone and compare.
+#[derive(Clone)]
+pub struct Atom(Arc<str>);
+
+impl Atom {
+    pub fn new(value: &str) -> Self {
+        Atom(INTERNER.lock().unwrap().intern(value))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Self) -> bool {
+        // Interned strings are only equal if they are the same string
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Atom {}
+
+impl PartialEq<str> for Atom {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Atom {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<Atom> for Cow<'_, str> {
+    fn eq(&self, other: &Atom) -> bool {
+        self.as_ref() == other.as_str()
+    }
+}
+
+impl PartialEq<Atom> for &str {
+    fn eq(&self, other: &Atom) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialOrd for Atom {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Atom {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl Serialize for Atom {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(value: &str) -> Self {
+        Atom::new(value)
+    }
+}
+
+impl From<String> for Atom {
+    fn from(value: String) -> Self {
+        Atom::new(&value)
+    }
+}
+
+impl From<&String> for Atom {
+    fn from(value: &String) -> Self {
+        Atom::new(value)
+    }
+}
+
+impl From<Cow<'static, str>> for Atom {
+    fn from(value: Cow<'static, str>) -> Self {
+        Atom::new(&value)
+    }
+}
+
+impl From<Atom> for Cow<'static, str> {
+    fn from(atom: Atom) -> Self {
+        Cow::Owned(atom.0.to_string())
+    }
+}
+
+impl From<&Atom> for Cow<'static, str> {
+    fn from(atom: &Atom) -> Self {
+        Cow::Owned(atom.0.to_string())
+    }
+}
+
+impl From<Atom> for String {
+    fn from(atom: Atom) -> Self {
+        atom.0.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_atoms_of_the_same_string_are_equal() {
+        let a = Atom::new("value");
+        let b = Atom::from(String::from("value"));
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+    }
+
+    #[test]
+    fn test_strings_are_dropped_once_no_atom_refers_to_them() {
+        let mut interner = Interner::default();
+        let kept = interner.intern("kept");
+        interner.intern("dropped");
+        interner.purge();
+
+        assert!(interner.strings.contains("kept"));
+        assert!(!interner.strings.contains("dropped"));
+        assert!(Arc::ptr_eq(&kept, &interner.intern("kept")));
+    }
+
+    #[test]
+    fn test_atoms_of_different_strings_are_not_equal() {
+        assert_ne!(Atom::new("left"), Atom::new("right"));
+        assert!(Atom::new("left") < Atom::new("right"));
+    }
+}
@@ -1,32 +1,31 @@
 use crate::Span;
 use lazy_static::lazy_static;
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 // Re-export canonicalize as part of our public API
 pub use std::fs::canonicalize;
 
 lazy_static! {
-    /// Cache for file reader to avoid reopening files on disk
-    /// We're making use of this as a makeshift Arena
+    /// Cache for file reader to avoid reopening files on disk.
+    /// Contents are reference counted so replacing an entry frees
+    /// the previous content once nothing borrows it anymore.
     /// Note: watch for concurrent access during tests as those may be run in parallel
-    static ref FILE_CACHE: Mutex<HashMap<String, &'static str>> =
-        Mutex::new(HashMap::<String, &'static str>::new());
+    static ref FILE_CACHE: Mutex<HashMap<String, Arc<str>>> =
+        Mutex::new(HashMap::<String, Arc<str>>::new());
 }
 
-/// Replacement for fs that returns a reference to a cached
+/// Replacement for fs that returns a shared handle to a cached
 /// version of a file which has been read from disk
-pub fn read_to_string<T: AsRef<str>>(path: &T) -> &'static str {
+pub fn read_to_string<T: AsRef<str>>(path: &T) -> Arc<str> {
     FILE_CACHE
         .lock()
         .unwrap()
         .entry(path.as_ref().to_string())
-        .or_insert_with(|| {
-            Box::leak(
-                std::fs::read_to_string(&path.as_ref())
-                    .unwrap()
-                    .into_boxed_str(),
-            )
-        })
+        .or_insert_with(|| std::fs::read_to_string(path.as_ref()).unwrap().into())
+        .clone()
 }
 
 /// Checks if the file exists in the cache
@@ -34,20 +33,21 @@ pub fn is_cached(path: &str) -> bool {
     FILE_CACHE.lock().unwrap().contains_key(path)
 }
 
-/// Inserts a value into the file cache and returns a
-/// reference to the value inserted
-pub fn cache(key: &str, value: String) {
-    let _ = &FILE_CACHE
+/// Inserts a value into the file cache, replacing any previous
+/// content cached for the same key, and returns a handle to it
+pub fn cache(key: &str, value: String) -> Arc<str> {
+    let content: Arc<str> = value.into();
+    FILE_CACHE
         .lock()
         .unwrap()
-        .insert(key.to_string(), Box::leak(value.into_boxed_str()));
+        .insert(key.to_string(), content.clone());
+    content
 }
 
-/// Returns string slice for the given span
-pub fn from_span(span: &Span) -> &'static str {
-    let cache = &FILE_CACHE.lock().unwrap();
-    let source = cache.get(span.file.as_ref()).unwrap();
-    &source[span.start..span.end]
+/// Returns the text for the given span
+pub fn from_span(span: &Span) -> String {
+    let source = read_to_string(&span.file);
+    source[span.start..span.end].to_owned()
 }
 
 #[cfg(test)]
@@ -57,5 +57,11 @@ mod test {
     #[test]
     fn fs() {
         cache("key", "value".to_string());
+        assert_eq!(&*read_to_string(&"key"), "value");
+
+        let previous = read_to_string(&"key");
+        cache("key", "other".to_string());
+        assert_eq!(&*previous, "value");
+        assert_eq!(&*read_to_string(&"key"), "other");
     }
 }
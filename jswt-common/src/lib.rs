@@ -1,6 +1,8 @@
+mod atom;
 pub mod fs;
+mod node_id;
 mod span;
-mod ty;
 
+pub use atom::Atom;
+pub use node_id::{Identifiable, NodeId};
 pub use span::{Span, Spannable};
-pub use ty::{Type, Typeable};
@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+/// Generic descriptor of a resource that has a [NodeId]
+pub trait Identifiable {
+    fn id(&self) -> NodeId;
+}
+
+/// Stable identifier of a node in the AST. Passes record what they
+/// learn about a node in side tables keyed by its id rather than its
+/// span since synthetic nodes all share the same span.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize)]
+pub struct NodeId(pub u32);
+
+impl NodeId {
+    /// Placeholder for nodes that haven't been numbered yet
+    pub const DUMMY: NodeId = NodeId(u32::MAX);
+
+    pub fn is_dummy(&self) -> bool {
+        *self == Self::DUMMY
+    }
+}
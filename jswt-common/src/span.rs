@@ -1,10 +1,10 @@
 use std::{
-    borrow::Cow,
     fmt,
     ops::{Add, Sub},
 };
 
-use crate::fs;
+use crate::{fs, Atom};
+use serde::Serialize;
 
 /// Generic descriptor of a resource that has a [Span]
 pub trait Spannable {
@@ -13,24 +13,19 @@ pub trait Spannable {
 
 /// Descriptor for a span of text that can be located
 /// in a Source file.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Serialize)]
 pub struct Span {
-    pub file: Cow<'static, str>,
-    pub module: Cow<'static, str>,
+    pub file: Atom,
+    pub module: Atom,
     pub start: usize,
     pub end: usize,
 }
 
 impl Span {
-    pub fn new(
-        file: Cow<'static, str>,
-        module: Cow<'static, str>,
-        start: usize,
-        end: usize,
-    ) -> Self {
+    pub fn new<F: Into<Atom>, M: Into<Atom>>(file: F, module: M, start: usize, end: usize) -> Self {
         Span {
-            file,
-            module,
+            file: file.into(),
+            module: module.into(),
             start,
             end,
         }
@@ -45,8 +40,8 @@ impl Span {
         }
     }
 
-    pub fn lexme(&self) -> &'static str {
-        &fs::from_span(&self)
+    pub fn lexme(&self) -> String {
+        fs::from_span(self)
     }
 }
 
@@ -0,0 +1,116 @@
+use jswt_doc::Documentation;
+use jswt_query::Database;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    Html,
+    Markdown,
+}
+
+impl DocFormat {
+    pub const ALL: &'static [DocFormat] = &[DocFormat::Html, DocFormat::Markdown];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DocFormat::Html => "html",
+            DocFormat::Markdown => "markdown",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            DocFormat::Html => "html",
+            DocFormat::Markdown => "md",
+        }
+    }
+}
+
+impl Display for DocFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for DocFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DocFormat::ALL
+            .iter()
+            .find(|format| format.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown format '{}'", s))
+    }
+}
+
+/// Writes the documentation of the given files and the sources they import
+/// into the output directory. Pages mirror the layout of the sources under
+/// their common directory. Returns the exit code for the command.
+pub fn document(
+    paths: &[PathBuf],
+    output: &Path,
+    formats: &[DocFormat],
+    runtime: Option<PathBuf>,
+) -> i32 {
+    let paths: Vec<_> = paths
+        .iter()
+        .map(|path| fs::canonicalize(path).unwrap())
+        .collect();
+
+    // The runtime is only checked along with the sources, it isn't documented
+    // unless one of them imports it
+    let mut db = Database::new();
+    db.set_roots(paths.clone());
+    let files = db.files().to_vec();
+    let mut roots = paths;
+    roots.extend(runtime);
+    db.set_roots(roots);
+
+    let root = common_directory(&files);
+    let docs = Documentation::new(&mut db, &files, &root);
+    for warning in docs.unresolved_links() {
+        eprintln!("warning: {}", warning);
+    }
+
+    for format in formats {
+        let extension = format.extension();
+        for page in &docs.pages {
+            let text = match format {
+                DocFormat::Html => docs.html(page),
+                DocFormat::Markdown => docs.markdown(page),
+            };
+            write(&output.join(format!("{}.{}", page.name, extension)), text);
+        }
+        let index = match format {
+            DocFormat::Html => docs.html_index(),
+            DocFormat::Markdown => docs.markdown_index(),
+        };
+        write(&output.join(format!("index.{}", extension)), index);
+    }
+    0
+}
+
+fn write(path: &Path, text: String) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, text).unwrap();
+}
+
+/// Deepest directory that contains all of the files
+fn common_directory(files: &[PathBuf]) -> PathBuf {
+    let mut directory = match files.first().and_then(|file| file.parent()) {
+        Some(directory) => directory.to_path_buf(),
+        None => return PathBuf::new(),
+    };
+    for file in files {
+        while !file.starts_with(&directory) {
+            if !directory.pop() {
+                break;
+            }
+        }
+    }
+    directory
+}
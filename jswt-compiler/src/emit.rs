@@ -0,0 +1,100 @@
+use jswt_symbols::{BindingsTable, ScopedSymbolTable};
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use std::{fmt::Display, fs, path::Path, str::FromStr};
+
+/// Version of the emitted JSON. Bumped whenever the shape
+/// of the serialized tree or tables changes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Machine readable outputs that can be requested with `--emit`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// The tree as parsed
+    Ast,
+    /// The tree after HIR lowering
+    Hir,
+    /// Class bindings and scoped symbols after semantic analysis
+    Symbols,
+}
+
+impl Emit {
+    pub const ALL: &'static [Emit] = &[Emit::Ast, Emit::Hir, Emit::Symbols];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Emit::Ast => "ast-json",
+            Emit::Hir => "hir-json",
+            Emit::Symbols => "symbols-json",
+        }
+    }
+
+    /// Key the output is stored under in the emitted document
+    fn key(&self) -> &'static str {
+        match self {
+            Emit::Ast => "ast",
+            Emit::Hir => "hir",
+            Emit::Symbols => "symbols",
+        }
+    }
+
+    /// Extension of the file the output is written to
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Emit::Ast => "ast.json",
+            Emit::Hir => "hir.json",
+            Emit::Symbols => "symbols.json",
+        }
+    }
+}
+
+impl Display for Emit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Emit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Emit::ALL
+            .iter()
+            .find(|emit| emit.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown output '{}'", s))
+    }
+}
+
+/// Tables built up by semantic analysis
+#[derive(Serialize)]
+pub struct Symbols<'a> {
+    pub bindings: &'a BindingsTable,
+    pub scopes: &'a ScopedSymbolTable,
+}
+
+/// Top level object of every emitted file
+struct Document<'a, T> {
+    kind: Emit,
+    content: &'a T,
+}
+
+impl<'a, T: Serialize> Serialize for Document<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("version", &SCHEMA_VERSION)?;
+        map.serialize_entry(self.kind.key(), self.content)?;
+        map.end()
+    }
+}
+
+/// Writes the content next to `output` if it was requested, along
+/// with the schema version it was written with
+pub fn write_json<T: Serialize>(emit: &[Emit], kind: Emit, output: &Path, content: &T) {
+    if !emit.contains(&kind) {
+        return;
+    }
+
+    let document = Document { kind, content };
+    let json = serde_json::to_string_pretty(&document).unwrap();
+    fs::write(output.with_extension(kind.extension()), json).unwrap();
+}
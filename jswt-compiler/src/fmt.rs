@@ -0,0 +1,68 @@
+use jswt_ast_serializer::{format_source, FormatError, FormatOptions};
+use jswt_errors::{print_parser_error, print_tokenizer_error};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Formats the given files and every source under the given directories.
+/// In check mode files are left alone and the ones that would change are
+/// listed instead. Returns the exit code for the command.
+pub fn format_files(paths: &[PathBuf], check: bool, options: FormatOptions) -> i32 {
+    let mut sources = vec![];
+    for path in paths {
+        collect_sources(path, &mut sources);
+    }
+
+    let mut failed = false;
+    for path in sources {
+        let path = fs::canonicalize(&path).unwrap();
+        let source = fs::read_to_string(&path).unwrap();
+        let formatted = match format_source(path.to_str().unwrap(), &source, options) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                failed = true;
+                for error in errors {
+                    match error {
+                        FormatError::Tokenizer(error) => print_tokenizer_error(&error),
+                        FormatError::Parse(error) => print_parser_error(&error),
+                    }
+                }
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            failed = true;
+            println!("{}", path.display());
+        } else {
+            fs::write(&path, formatted).unwrap();
+        }
+    }
+
+    if failed {
+        1
+    } else {
+        0
+    }
+}
+
+fn collect_sources(path: &Path, sources: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        sources.push(path.to_path_buf());
+        return;
+    }
+
+    let mut entries: Vec<_> = fs::read_dir(path)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() || entry.extension().map_or(false, |ext| ext == "jswt") {
+            collect_sources(&entry, sources);
+        }
+    }
+}
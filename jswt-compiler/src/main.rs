@@ -1,14 +1,28 @@
+mod doc;
+mod emit;
 mod env;
+mod fmt;
+mod refactor;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use doc::DocFormat;
+use emit::{write_json, Emit, Symbols};
 use jswt_ast::Ast;
-use jswt_ast_serializer::AstSerializer;
+use jswt_ast_serializer::{AstSerializer, FormatOptions};
 use jswt_hir_lowering::HirLoweringContext;
 use jswt_mir_lowering::MirLoweringContext;
 use jswt_semantics::GlobalSemanticResolver;
+use jswt_semantics::Lint;
+use jswt_semantics::LintConfig;
+use jswt_semantics::LintLevel;
+use jswt_semantics::Linter;
 use jswt_semantics::LocalSemanticResolver;
+use jswt_semantics::TypeChecker;
 use jswt_symbols::BindingsTable;
+use jswt_symbols::ResolutionTable;
 use jswt_symbols::ScopedSymbolTable;
+use jswt_types::TypeTable;
+use refactor::Refactoring;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
@@ -21,13 +35,22 @@ use jswt_parser::Parser as JswtParser;
 use jswt_tokenizer::Tokenizer;
 
 #[derive(Parser, Debug)]
-#[command(author, version, about)]
+#[command(
+    author,
+    version,
+    about,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(short, long, help = "Write output to file")]
     output: Option<PathBuf>,
 
-    #[arg(help = "Write output to file")]
-    file: PathBuf,
+    #[arg(required = true, help = "Write output to file")]
+    file: Option<PathBuf>,
 
     #[arg(
         short,
@@ -58,10 +81,123 @@ struct Args {
 
     #[arg(short, long, help = "Path to runtime sources")]
     runtime_path: Option<PathBuf>,
+
+    #[arg(long, value_name = "LINT", help = "Don't report the given lint")]
+    allow: Vec<Lint>,
+
+    #[arg(long, value_name = "LINT", help = "Report the given lint as an error")]
+    deny: Vec<Lint>,
+
+    #[arg(
+        long,
+        value_delimiter = ',',
+        help = "Write machine readable outputs, any of ast-json, hir-json or symbols-json"
+    )]
+    emit: Vec<Emit>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Format sources in place
+    Fmt {
+        #[arg(required = true, help = "Files or directories to format")]
+        paths: Vec<PathBuf>,
+
+        #[arg(
+            long,
+            help = "List files that aren't formatted instead of rewriting them",
+            default_value = "false"
+        )]
+        check: bool,
+
+        #[arg(
+            long,
+            help = "Number of spaces per indentation level",
+            default_value = "4"
+        )]
+        indent: usize,
+
+        #[arg(long, help = "Maximum width of a line", default_value = "100")]
+        line_width: usize,
+    },
+    /// Generate API documentation from doc comments
+    Doc {
+        #[arg(
+            required = true,
+            help = "Files to document along with the sources they import"
+        )]
+        files: Vec<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            help = "Directory to write the documentation to",
+            default_value = "docs"
+        )]
+        output: PathBuf,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Formats to write, any of html or markdown",
+            default_value = "html,markdown"
+        )]
+        format: Vec<DocFormat>,
+
+        #[arg(short, long, help = "Path to runtime sources")]
+        runtime_path: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            help = "Do not include the runtime and stdlib",
+            default_value = "false"
+        )]
+        no_std: bool,
+    },
+    /// Run a language server over stdio
+    Lsp {
+        #[arg(short, long, help = "Path to runtime sources")]
+        runtime_path: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            help = "Do not include the runtime and stdlib",
+            default_value = "false"
+        )]
+        no_std: bool,
+    },
+    /// Apply a refactoring to a source file
+    Refactor {
+        #[command(subcommand)]
+        refactoring: Refactoring,
+
+        #[arg(short, long, global = true, help = "Path to runtime sources")]
+        runtime_path: Option<PathBuf>,
+
+        #[arg(
+            short,
+            long,
+            global = true,
+            help = "Do not include the runtime and stdlib",
+            default_value = "false"
+        )]
+        no_std: bool,
+
+        #[arg(
+            long,
+            global = true,
+            help = "Print the changed files instead of rewriting them",
+            default_value = "false"
+        )]
+        dry_run: bool,
+    },
 }
 
 fn main() {
     let Args {
+        command,
         output,
         file,
         wast,
@@ -69,8 +205,60 @@ fn main() {
         minified,
         no_std,
         runtime_path,
+        allow,
+        deny,
+        emit,
     } = Args::parse();
 
+    if let Some(command) = command {
+        let code = match command {
+            Command::Fmt {
+                paths,
+                check,
+                indent,
+                line_width,
+            } => {
+                let options = FormatOptions {
+                    indent_width: indent,
+                    line_width,
+                };
+                fmt::format_files(&paths, check, options)
+            }
+            Command::Doc {
+                files,
+                output,
+                format,
+                runtime_path,
+                no_std,
+            } => doc::document(&files, &output, &format, runtime(runtime_path, no_std)),
+            Command::Lsp {
+                runtime_path,
+                no_std,
+            } => {
+                let runtime = runtime(runtime_path, no_std);
+                let stdin = std::io::stdin();
+                let stdout = std::io::stdout();
+                match jswt_lsp::run(stdin.lock(), stdout.lock(), runtime) {
+                    Ok(()) => 0,
+                    Err(error) => {
+                        eprintln!("{}", error);
+                        1
+                    }
+                }
+            }
+            Command::Refactor {
+                refactoring,
+                runtime_path,
+                no_std,
+                dry_run,
+            } => refactor::refactor(refactoring, runtime(runtime_path, no_std), dry_run),
+        };
+        exit(code);
+    }
+
+    // Required whenever there's no subcommand
+    let file = file.unwrap();
+
     let output = match output {
         Some(it) => PathBuf::from(it),
         None => file.clone(),
@@ -88,8 +276,16 @@ fn main() {
             .map(Result::unwrap)
     };
 
-    let ast = compile_module(&file, &output, runtime.as_ref());
-    let mut code_gen = CodeGenerator::default();
+    let mut lints = LintConfig::default();
+    for lint in allow {
+        lints.set(lint, LintLevel::Allow);
+    }
+    for lint in deny {
+        lints.set(lint, LintLevel::Deny);
+    }
+
+    let (ast, types, resolutions) = compile_module(&file, &output, runtime.as_ref(), &lints, &emit);
+    let mut code_gen = CodeGenerator::new(types, resolutions);
     let module = code_gen.generate_module(&ast);
 
     // Write generated wasm AST for debugging
@@ -167,7 +363,27 @@ fn main() {
     exit(1);
 }
 
-fn compile_module(input: &Path, output: &Path, runtime: Option<&PathBuf>) -> Ast {
+/// Runtime that programs are checked with. Sources are checked with the
+/// runtime in the working directory unless asked not to.
+fn runtime(runtime_path: Option<PathBuf>, no_std: bool) -> Option<PathBuf> {
+    let default = PathBuf::from("./runtime/rt.jswt");
+    match runtime_path {
+        _ if no_std => None,
+        Some(path) => Some(path),
+        None if default.exists() => Some(default),
+        None => None,
+    }
+    .map(fs::canonicalize)
+    .map(Result::unwrap)
+}
+
+fn compile_module(
+    input: &Path,
+    output: &Path,
+    runtime: Option<&PathBuf>,
+    lints: &LintConfig,
+    emit: &[Emit],
+) -> (Ast, TypeTable, ResolutionTable) {
     // Let binding to prevent the ref being dropped before getting passed to the tokenizer
     let mut tokenizer = Tokenizer::new();
     // Sources root for user defined sources is the current directory
@@ -187,10 +403,11 @@ fn compile_module(input: &Path, output: &Path, runtime: Option<&PathBuf>) -> Ast
     }
 
     let mut parser = JswtParser::new(&mut tokenizer);
-    let mut ast = parser.parse();
+    let ast = parser.parse();
 
     // Write AST for debugging
     fs::write(output.with_extension("ast"), format!("{:#?}", ast)).unwrap();
+    write_json(emit, Emit::Ast, output, &ast);
 
     let mut has_errors = false;
 
@@ -205,9 +422,20 @@ fn compile_module(input: &Path, output: &Path, runtime: Option<&PathBuf>) -> Ast
         print_parser_error(&error);
     }
 
+    // Users can't act on warnings in the runtime
+    let mut lints = lints.clone();
+    for file in ast.program.files.iter() {
+        let module = &file.span.module;
+        if module.split('/').next() == Some("runtime") {
+            lints.ignore_module(module.to_string());
+        }
+    }
+
     let mut symbol_table = ScopedSymbolTable::default();
     let mut bindings_table = BindingsTable::default();
 
+    // Parse errors leave placeholders in the tree so
+    // semantic analysis still runs on everything else
     // Global Semantic analytis pass
     let mut global = GlobalSemanticResolver::new(&mut bindings_table, &mut symbol_table);
     global.resolve(&ast);
@@ -217,10 +445,6 @@ fn compile_module(input: &Path, output: &Path, runtime: Option<&PathBuf>) -> Ast
         print_semantic_error(&error);
     }
 
-    if has_errors {
-        exit(1);
-    }
-
     // Local semantic analysis pass
     let mut local = LocalSemanticResolver::new(&mut bindings_table, &mut symbol_table);
     local.resolve(&ast);
@@ -229,30 +453,72 @@ fn compile_module(input: &Path, output: &Path, runtime: Option<&PathBuf>) -> Ast
         has_errors = true;
         print_semantic_error(&error);
     }
+    let resolutions = local.resolutions;
+
+    // Type checking pass
+    let mut checker = TypeChecker::new(&mut bindings_table, &mut symbol_table, &resolutions);
+    checker.resolve(&ast);
+
+    for error in checker.errors() {
+        has_errors = true;
+        print_semantic_error(error);
+    }
+
+    let types = checker.types;
+
+    // Lint pass
+    let mut linter = Linter::new(lints, &symbol_table, &resolutions);
+    linter.set_inserted_semicolons(parser.inserted_semicolons());
+    // Imported sources are named after their canonical path
+    let imports = tokenizer
+        .imports()
+        .iter()
+        .cloned()
+        .map(|mut import| {
+            if let Ok(path) = fs::canonicalize(&import.path) {
+                import.path = path;
+            }
+            import
+        })
+        .collect();
+    linter.set_imports(imports);
+    linter.lint(&ast);
+
+    for error in linter.errors() {
+        has_errors |= !error.is_warning();
+        print_semantic_error(error);
+    }
+
+    let symbols = Symbols {
+        bindings: &bindings_table,
+        scopes: &symbol_table,
+    };
+    write_json(emit, Emit::Symbols, output, &symbols);
 
     if has_errors {
         exit(1);
     }
 
     // Hir lowering pass
-    let mut lowering = HirLoweringContext::new(&mut bindings_table, &mut symbol_table);
-    let ast = lowering.lower(&mut ast);
+    let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table, &types);
+    let ast = lowering.lower(ast);
 
     fs::write(output.with_extension("hir.ast"), format!("{:#?}", ast)).unwrap();
+    write_json(emit, Emit::Hir, output, &ast);
 
-    let mut serializer = AstSerializer::default();
+    let mut serializer = AstSerializer::with_types(&types);
     let content = serializer.serialze(&ast);
     fs::write(output.with_extension("hir.jswt"), content).unwrap();
 
     // Mir lowering pass
-    let mut mir_lowering = MirLoweringContext::new(&mut bindings_table, &mut symbol_table);
-    let ast = mir_lowering.lower(&ast);
+    let mut mir_lowering = MirLoweringContext::new(&bindings_table, &symbol_table, &types);
+    let ast = mir_lowering.lower(ast);
 
     if has_errors {
         exit(1);
     }
 
-    ast
+    (ast, types, resolutions)
 }
 
 #[cfg(test)]
@@ -298,6 +564,131 @@ mod test {
         assert_str_eq!(stdout, "7\n1\n12\n1\n");
     }
 
+    #[test]
+    fn test_compile_and_execute_casts_sample() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("../example/casts.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_str_eq!(stdout, "7\n2\n1065353216\n-2\n8\n");
+    }
+
+    #[test]
+    fn test_compile_and_execute_asi_sample() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("../example/asi.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_str_eq!(stdout, "2\n4\n6\n3\n");
+    }
+
+    #[test]
+    fn test_compile_and_execute_objects_sample() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("../example/objects.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_str_eq!(stdout, "1\n6\n36\n7\n0\n1\n");
+    }
+
+    #[test]
+    fn test_compile_and_execute_numbers_sample() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("../example/numbers.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_str_eq!(stdout, "2432902\n-5\n2147483647\n1\n16777217\n7\n3\n");
+    }
+
+    #[test]
+    fn test_compile_and_execute_wide_fields_sample() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("../example/wide_fields.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_str_eq!(stdout, "7\n10000000\n5\n33554435\n9\n9\n1\n");
+    }
+
+    #[test]
+    fn test_compile_and_execute_booleans_sample() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("../example/booleans.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_str_eq!(stdout, "1\n2\n0\n1\n3\n");
+    }
+
+    #[test]
+    fn test_compile_and_execute_operators_sample() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("../example/operators.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_str_eq!(stdout, "2\n4\n25\n1\n7\n");
+    }
+
+    #[test]
+    fn test_denied_missing_semicolons_fail_compilation() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("--deny")
+            .arg("missing-semicolon")
+            .arg("../example/asi.jswt")
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn test_lint_warnings_do_not_fail_compilation() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("./test/unused-variable.jswt")
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_denied_lints_fail_compilation() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("--deny")
+            .arg("unused-variable")
+            .arg("./test/unused-variable.jswt")
+            .assert()
+            .failure();
+    }
+
     #[test]
     #[ignore]
     fn test_compile_and_execute_arrays_sample() {
@@ -325,11 +716,204 @@ mod test {
         assert_snapshot!(redact_paths(stdout));
     }
 
+    #[test]
+    fn test_missing_semicolon_keeps_the_statement() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("./test/missing-semicolon.jswt")
+            .assert()
+            .failure();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_eq!(stdout.matches("error:").count(), 1);
+        assert_snapshot!(redact_paths(stdout));
+    }
+
+    #[test]
+    fn test_fmt_check_lists_unformatted_files() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("fmt")
+            .arg("--check")
+            .arg("./test/unformatted.jswt")
+            .assert()
+            .failure();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert!(stdout.ends_with("unformatted.jswt\n"));
+    }
+
+    #[test]
+    fn test_fmt_rewrites_files() {
+        let dir = std::env::temp_dir().join("jswt-test-fmt-rewrites-files");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("unformatted.jswt");
+        std::fs::copy("./test/unformatted.jswt", &path).unwrap();
+
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("fmt")
+            .arg("--indent")
+            .arg("2")
+            .arg(&dir)
+            .assert()
+            .success();
+        assert_snapshot!(std::fs::read_to_string(&path).unwrap());
+
+        // Formatting is idempotent so the result passes the check
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("fmt")
+            .arg("--check")
+            .arg("--indent")
+            .arg("2")
+            .arg(&path)
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn test_emit_writes_json_outputs() {
+        let dir = std::env::temp_dir().join("jswt-test-emit-writes-json-outputs");
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("loops.jswt");
+
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("--emit")
+            .arg("ast-json,hir-json,symbols-json")
+            .arg("--output")
+            .arg(&output)
+            .arg("../example/loops.jswt")
+            .assert()
+            .success();
+
+        for (extension, key) in [
+            ("ast.json", "ast"),
+            ("hir.json", "hir"),
+            ("symbols.json", "symbols"),
+        ] {
+            let content = std::fs::read_to_string(output.with_extension(extension)).unwrap();
+            let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+            assert_eq!(json["version"], 2);
+            assert!(json[key].is_object(), "{} is missing '{}'", extension, key);
+        }
+    }
+
+    #[test]
+    fn test_refactor_rewrites_files() {
+        let dir = std::env::temp_dir().join("jswt-test-refactor-rewrites-files");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("refactor.jswt");
+        std::fs::copy("./test/refactor.jswt", &path).unwrap();
+
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("refactor")
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("rename")
+            .arg("--at")
+            .arg("1:10")
+            .arg("--to")
+            .arg("plus")
+            .arg(&path)
+            .assert()
+            .success();
+
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("refactor")
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("inline-variable")
+            .arg("--at")
+            .arg("7:13")
+            .arg(&path)
+            .assert()
+            .success();
+        assert_snapshot!(std::fs::read_to_string(&path).unwrap());
+    }
+
+    #[test]
+    fn test_refactor_dry_run_prints_changes() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("refactor")
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("--dry-run")
+            .arg("extract-function")
+            .arg("--start")
+            .arg("6:5")
+            .arg("--end")
+            .arg("7:18")
+            .arg("--name")
+            .arg("report")
+            .arg("./test/refactor.jswt")
+            .assert()
+            .success();
+        let stdout = std::str::from_utf8(&assert.get_output().stdout).unwrap();
+        assert_snapshot!(stdout);
+        // The file is left alone
+        assert!(std::fs::read_to_string("./test/refactor.jswt")
+            .unwrap()
+            .contains("let sum"));
+    }
+
+    #[test]
+    fn test_refactor_failures_are_reported() {
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        let assert = cmd
+            .arg("refactor")
+            .arg("--runtime-path")
+            .arg("../runtime/rt.jswt")
+            .arg("--dry-run")
+            .arg("rename")
+            .arg("--at")
+            .arg("1:10")
+            .arg("--to")
+            .arg("while")
+            .arg("./test/refactor.jswt")
+            .assert()
+            .failure();
+        let stderr = std::str::from_utf8(&assert.get_output().stderr).unwrap();
+        assert_eq!(stderr, "error: 'while' is not a valid identifier\n");
+    }
+
+    #[test]
+    fn test_doc_writes_html_and_markdown() {
+        let dir = std::env::temp_dir().join("jswt-test-doc-writes-html-and-markdown");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("doc")
+            .arg("--no-std")
+            .arg("--output")
+            .arg(&dir)
+            .arg("../runtime/rt.jswt")
+            .assert()
+            .success();
+
+        let html = std::fs::read_to_string(dir.join("runtime/env.html")).unwrap();
+        assert!(html.contains("<p>Logs a single i32 value to stdout</p>"));
+        let markdown = std::fs::read_to_string(dir.join("runtime/env.md")).unwrap();
+        assert!(markdown.contains("- `value`: [`i32`](../runtime/i32.md#i32) — value to log"));
+        let index = std::fs::read_to_string(dir.join("index.md")).unwrap();
+        assert!(index.contains("  - function [println](runtime/env.md#println)"));
+
+        let mut cmd = Command::cargo_bin("jswt").unwrap();
+        cmd.arg("doc")
+            .arg("--no-std")
+            .arg("--format")
+            .arg("pdf")
+            .arg("../runtime/rt.jswt")
+            .assert()
+            .failure();
+    }
+
     /// Simple redaction of paths that will be generated by errors
     /// in the crate to keeps tests that may run on different systems
     /// from failing.
     fn redact_paths(value: &str) -> Cow<str> {
-        let re = Regex::new(r"/[\w/]+/jswt/jswt-compiler/").unwrap();
+        let re = Regex::new(r"/[\w/]+/jswt-compiler/").unwrap();
         re.replace_all(value, "[redacted]/jswt/jswt-compiler/")
     }
 }
@@ -0,0 +1,142 @@
+use clap::Subcommand;
+use jswt_query::Database;
+use jswt_refactor::{edited_sources, extract_function, inline_variable, rename};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[derive(Subcommand, Debug)]
+pub enum Refactoring {
+    /// Rename a symbol and every reference to it
+    Rename {
+        #[arg(help = "File the symbol is in")]
+        file: PathBuf,
+
+        #[arg(long, value_name = "LINE:COLUMN", help = "Position of the symbol")]
+        at: LineColumn,
+
+        #[arg(long, value_name = "NAME", help = "New name of the symbol")]
+        to: String,
+    },
+    /// Move statements into a new function and call it in their place
+    ExtractFunction {
+        #[arg(help = "File the statements are in")]
+        file: PathBuf,
+
+        #[arg(long, value_name = "LINE:COLUMN", help = "Start of the statements")]
+        start: LineColumn,
+
+        #[arg(long, value_name = "LINE:COLUMN", help = "End of the statements")]
+        end: LineColumn,
+
+        #[arg(long, help = "Name of the new function")]
+        name: String,
+    },
+    /// Replace the references to a variable with its initializer
+    InlineVariable {
+        #[arg(help = "File the variable is in")]
+        file: PathBuf,
+
+        #[arg(long, value_name = "LINE:COLUMN", help = "Position of the variable")]
+        at: LineColumn,
+    },
+}
+
+/// One based line and column of a position in a source file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl LineColumn {
+    /// Byte offset of the position in the source if it's in the source
+    fn offset(&self, source: &str) -> Option<usize> {
+        let line = source.split_inclusive('\n').nth(self.line - 1)?;
+        let start = line.as_ptr() as usize - source.as_ptr() as usize;
+        let content = line.trim_end_matches('\n');
+        if self.column - 1 == content.chars().count() {
+            return Some(start + content.len());
+        }
+        content
+            .char_indices()
+            .nth(self.column - 1)
+            .map(|(index, _)| start + index)
+    }
+}
+
+impl FromStr for LineColumn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || format!("Expected a position like 12:5 but got '{}'", s);
+        let (line, column) = s.split_once(':').ok_or_else(error)?;
+        let line = line.parse().map_err(|_| error())?;
+        let column = column.parse().map_err(|_| error())?;
+        if line == 0 || column == 0 {
+            return Err(error());
+        }
+        Ok(LineColumn { line, column })
+    }
+}
+
+/// Applies the refactoring and writes the files it changes. With dry run
+/// the changed files are printed instead. Returns the exit code for the
+/// command.
+pub fn refactor(refactoring: Refactoring, runtime: Option<PathBuf>, dry_run: bool) -> i32 {
+    let file = match &refactoring {
+        Refactoring::Rename { file, .. }
+        | Refactoring::ExtractFunction { file, .. }
+        | Refactoring::InlineVariable { file, .. } => file,
+    };
+    let path = fs::canonicalize(file).unwrap();
+    let source = fs::read_to_string(&path).unwrap();
+
+    let mut db = Database::new();
+    let mut roots = vec![path.clone()];
+    roots.extend(runtime);
+    db.set_roots(roots);
+
+    let offset = |position: LineColumn| {
+        position.offset(&source).ok_or_else(|| {
+            format!(
+                "{}:{} is outside of {}",
+                position.line,
+                position.column,
+                path.display()
+            )
+        })
+    };
+    let result = match refactoring {
+        Refactoring::Rename { at, to, .. } => offset(at).and_then(|offset| {
+            rename(&mut db, &path, offset, &to).map_err(|error| error.to_string())
+        }),
+        Refactoring::ExtractFunction {
+            start, end, name, ..
+        } => offset(start).and_then(|start| {
+            let end = offset(end)?;
+            extract_function(&mut db, &path, start..end, &name).map_err(|error| error.to_string())
+        }),
+        Refactoring::InlineVariable { at, .. } => offset(at).and_then(|offset| {
+            inline_variable(&mut db, &path, offset).map_err(|error| error.to_string())
+        }),
+    };
+    let edits = match result {
+        Ok(edits) => edits,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return 1;
+        }
+    };
+
+    let cwd = std::env::current_dir().unwrap();
+    for (path, text) in edited_sources(&mut db, &edits) {
+        if dry_run {
+            let name = path.strip_prefix(&cwd).unwrap_or(&path);
+            println!("// {}\n{}", name.display(), text);
+        } else {
+            fs::write(&path, text).unwrap();
+        }
+    }
+    0
+}
@@ -1,6 +1,10 @@
 use proc_macro::{self, TokenStream};
-use quote::{__private::ext::RepToTokensExt, quote};
-use syn::{parse_macro_input, DataEnum, DeriveInput};
+use quote::{__private::ext::RepToTokensExt, format_ident, quote};
+use std::{env, fs, path::Path};
+use syn::{
+    parse_macro_input, Attribute, DataEnum, DataStruct, DeriveInput, Fields, Ident, Item, Lit,
+    Meta, NestedMeta, Type,
+};
 
 /// Derive for generating spannable impls for structs
 #[proc_macro_derive(Spannable)]
@@ -37,45 +41,30 @@ pub fn span(input: TokenStream) -> TokenStream {
     tokens.into()
 }
 
-/// Derive for generating spannable impls for structs
-#[proc_macro_derive(Typeable)]
-pub fn ty(input: TokenStream) -> TokenStream {
+/// Derive for generating identifiable impls for structs
+#[proc_macro_derive(Identifiable)]
+pub fn id(input: TokenStream) -> TokenStream {
     let DeriveInput { ident, data, .. } = parse_macro_input!(input);
     let tokens = match data {
         syn::Data::Struct(_) => {
             quote! {
-                impl jswt_common::Typeable for #ident {
-                    fn ty(&self) -> jswt_common::Type {
-                        self.ty.to_owned()
-                    }
-                    fn binding(&self) -> Option<std::borrow::Cow<'static, str>> {
-                        match &self.ty {
-                            jswt_common::Type::Binding(r) => Some(r.clone()),
-                            _ => None
-                        }                        
+                impl jswt_common::Identifiable for #ident {
+                    fn id(&self) -> jswt_common::NodeId {
+                        self.id
                     }
                 }
             }
         }
-        // This is currently a low effort macro for generating  Spannable implementation for enums
-        // It assumes that every variant of the enum also implements Spannable
+        // Like Spannable this assumes that every variant of the enum
+        // also implements Identifiable
         syn::Data::Enum(DataEnum { variants, .. }) => {
             let variant_ident = variants.into_iter().map(|v| v.ident);
-            let variant_ident_ = variant_ident.clone();
             quote! {
-                impl jswt_common::Typeable for #ident {
-                    fn ty(&self) -> jswt_common::Type {
+                impl jswt_common::Identifiable for #ident {
+                    fn id(&self) -> jswt_common::NodeId {
                         match self {
                             #(
-                                #ident::#variant_ident(variant) => variant.ty(),
-                            )*
-                        }
-                    }
-
-                    fn binding(&self) -> Option<std::borrow::Cow<'static, str>> {
-                        match self {
-                            #(
-                                #ident::#variant_ident_(variant) => variant.binding(),
+                                #ident::#variant_ident(variant) => variant.id(),
                             )*
                         }
                     }
@@ -116,3 +105,221 @@ pub fn enum_variant(input: TokenStream) -> TokenStream {
     let tokens = quote! { #(#tokens)* };
     tokens.into()
 }
+
+/// Generates the walker of an AST node. Fields marked with `#[walk]` are
+/// visited in the order they are declared and every variant of an enum is
+/// visited. `#[walk(visit_method)]` on the type names the visitor method
+/// the node is handed to, nodes without one are walked in place. On an
+/// enum variant it overrides the method used for that variant.
+/// `#[walk(visit_method, group = "...")]` also puts the method in the
+/// value returning visitor of the group, see `ast_nodes!`.
+#[proc_macro_derive(Walk, attributes(walk))]
+pub fn walk(input: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident, data, attrs, ..
+    } = parse_macro_input!(input);
+    let (walk, walk_mut) = match data {
+        syn::Data::Struct(DataStruct { fields, .. }) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .filter(|field| field.attrs.iter().any(|attr| attr.path.is_ident("walk")))
+                .map(|field| field.ident.clone().expect("tuple structs can't be walked"))
+                .collect();
+            (
+                quote! { #( crate::Walk::accept(&self.#fields, visitor); )* },
+                quote! { #( crate::Walk::accept_mut(&mut self.#fields, visitor); )* },
+            )
+        }
+        // Like Spannable this assumes that every variant of the enum
+        // holds a single node
+        syn::Data::Enum(DataEnum { variants, .. }) => {
+            let (arms, arms_mut): (Vec<_>, Vec<_>) = variants
+                .iter()
+                .map(|v| {
+                    let var = &v.ident;
+                    match visit_method(&v.attrs) {
+                        Some(method) => (
+                            quote! { #ident::#var(node) => visitor.#method(node), },
+                            quote! { #ident::#var(node) => visitor.#method(node), },
+                        ),
+                        None => (
+                            quote! { #ident::#var(node) => crate::Walk::accept(node, visitor), },
+                            quote! { #ident::#var(node) => crate::Walk::accept_mut(node, visitor), },
+                        ),
+                    }
+                })
+                .unzip();
+            (
+                quote! { match self { #(#arms)* } },
+                quote! { match self { #(#arms_mut)* } },
+            )
+        }
+        syn::Data::Union(_) => unimplemented!(),
+    };
+
+    let (accept, accept_mut) = match visit_method(&attrs) {
+        Some(method) => (
+            quote! { visitor.#method(self) },
+            quote! { visitor.#method(self) },
+        ),
+        None => (
+            quote! { crate::Walk::walk(self, visitor) },
+            quote! { crate::Walk::walk_mut(self, visitor) },
+        ),
+    };
+
+    let tokens = quote! {
+        impl crate::Walk for #ident {
+            fn accept<V: crate::visit::Visitor>(&self, visitor: &mut V) {
+                #accept
+            }
+
+            fn accept_mut<V: crate::mut_visit::MutVisitor>(&mut self, visitor: &mut V) {
+                #accept_mut
+            }
+
+            #[allow(unused_variables)]
+            fn walk<V: crate::visit::Visitor>(&self, visitor: &mut V) {
+                #walk
+            }
+
+            #[allow(unused_variables)]
+            fn walk_mut<V: crate::mut_visit::MutVisitor>(&mut self, visitor: &mut V) {
+                #walk_mut
+            }
+        }
+    };
+    tokens.into()
+}
+
+/// Reads the visitor method out of a `#[walk(visit_method)]` attribute
+fn visit_method(attrs: &[Attribute]) -> Option<Ident> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("walk"))
+        .find_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.into_iter().find_map(|nested| match nested {
+                NestedMeta::Meta(Meta::Path(path)) => path.get_ident().cloned(),
+                _ => None,
+            }),
+            _ => None,
+        })
+}
+
+/// Hands every visitor method of the `#[derive(Walk)]` nodes of the crate
+/// to the given macro, grouped by the value returning visitor they belong to:
+///
+/// ```ignore
+/// visitor! {
+///     program { visit_program, walk_program: Program, ... }
+///     statement { ... }
+///     expression { ... }
+///     node { ... }
+/// }
+/// ```
+///
+/// Nodes without a group are listed under `node`. The sources of the crate
+/// are read so adding a node kind only takes deriving `Walk` for it.
+#[proc_macro]
+pub fn ast_nodes(input: TokenStream) -> TokenStream {
+    let callback = parse_macro_input!(input as Ident);
+    let root = env::var("CARGO_MANIFEST_DIR").expect("ast_nodes! is expanded by cargo");
+    let mut files: Vec<_> = fs::read_dir(Path::new(&root).join("src"))
+        .expect("sources of the crate can be read")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "rs"))
+        .collect();
+    files.sort();
+
+    let mut groups: Vec<(String, Vec<_>)> = ["program", "statement", "expression", "node"]
+        .iter()
+        .map(|group| (group.to_string(), vec![]))
+        .collect();
+    for path in files {
+        let source = fs::read_to_string(&path).unwrap();
+        let file = syn::parse_file(&source).unwrap_or_else(|e| panic!("{:?}: {}", path, e));
+        for item in file.items {
+            for (method, group, node) in walked_nodes(&item) {
+                let walk = format_ident!("walk{}", &method.to_string()["visit".len()..]);
+                let group = group.unwrap_or_else(|| "node".to_string());
+                match groups.iter_mut().find(|(name, _)| *name == group) {
+                    Some((_, nodes)) => nodes.push(quote! { #method, #walk: #node }),
+                    None => panic!("{} is in the unknown group '{}'", method, group),
+                }
+            }
+        }
+    }
+
+    let groups = groups.into_iter().map(|(group, nodes)| {
+        let group = format_ident!("{}", group);
+        quote! { #group { #(#nodes),* } }
+    });
+    let tokens = quote! {
+        #callback! { #(#groups)* }
+    };
+    tokens.into()
+}
+
+/// Visitor methods of an item deriving `Walk` along with their group and the
+/// node they're given. Enum variants with a method of their own are in the
+/// group of the enum.
+fn walked_nodes(item: &Item) -> Vec<(Ident, Option<String>, Type)> {
+    let (attrs, ident) = match item {
+        Item::Struct(item) => (&item.attrs, &item.ident),
+        Item::Enum(item) => (&item.attrs, &item.ident),
+        _ => return vec![],
+    };
+    if !derives_walk(attrs) {
+        return vec![];
+    }
+
+    let mut nodes = vec![];
+    let group = visit_group(attrs);
+    if let Some(method) = visit_method(attrs) {
+        nodes.push((method, group.clone(), syn::parse_quote!(#ident)));
+    }
+    if let Item::Enum(item) = item {
+        for variant in item.variants.iter() {
+            let node = match &variant.fields {
+                Fields::Unnamed(fields) => fields.unnamed.first().map(|field| field.ty.clone()),
+                _ => None,
+            };
+            if let (Some(method), Some(node)) = (visit_method(&variant.attrs), node) {
+                nodes.push((method, group.clone(), node));
+            }
+        }
+    }
+    nodes
+}
+
+fn derives_walk(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("derive"))
+        .any(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.iter().any(|nested| match nested {
+                NestedMeta::Meta(Meta::Path(path)) => path.is_ident("Walk"),
+                _ => false,
+            }),
+            _ => false,
+        })
+}
+
+/// Reads the group out of a `#[walk(visit_method, group = "...")]` attribute
+fn visit_group(attrs: &[Attribute]) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("walk"))
+        .find_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => list.nested.into_iter().find_map(|nested| match nested {
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("group") => {
+                    match value.lit {
+                        Lit::Str(group) => Some(group.value()),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }),
+            _ => None,
+        })
+}
@@ -0,0 +1,248 @@
+/// A `/** */` comment split into its description and JSDoc tags
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocComment {
+    /// Text before the first tag. Paragraphs are separated by blank lines
+    pub description: String,
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    /// `@param name description`
+    Param { name: String, description: String },
+    /// `@returns description`
+    Returns(String),
+    /// `@deprecated reason`
+    Deprecated(String),
+    /// `@example` followed by code on the lines after it
+    Example(String),
+    /// `@see target`
+    See(String),
+    /// Any other tag, kept as it's written
+    Other { name: String, text: String },
+}
+
+/// Tags that end an example. Other lines starting with `@` in
+/// an example are annotations in the example's code.
+const TAGS: &[&str] = &["param", "returns", "return", "deprecated", "example", "see"];
+
+impl DocComment {
+    pub fn parse(comment: &str) -> Self {
+        let body = comment.strip_prefix("/**").unwrap_or(comment);
+        let body = body.strip_suffix("*/").unwrap_or(body);
+
+        let mut description = vec![];
+        let mut tags: Vec<(&str, Vec<&str>)> = vec![];
+        for line in body.lines() {
+            // Leading asterisks and the space after them aren't part of the text
+            let line = line.trim_start();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+
+            if let Some(rest) = line.strip_prefix('@') {
+                let (name, text) = rest.split_once(' ').unwrap_or((rest, ""));
+                let in_example = matches!(tags.last(), Some(("example", _)));
+                if !in_example || TAGS.contains(&name) {
+                    tags.push((name, vec![text]));
+                    continue;
+                }
+            }
+            match tags.last_mut() {
+                Some((_, text)) => text.push(line),
+                None => description.push(line),
+            }
+        }
+
+        DocComment {
+            description: description.join("\n").trim().to_owned(),
+            tags: tags
+                .into_iter()
+                .map(|(name, lines)| Tag::new(name, &lines.join("\n")))
+                .collect(),
+        }
+    }
+
+    /// Description of the parameter with the given name
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find_map(|tag| match tag {
+            Tag::Param {
+                name: param,
+                description,
+            } if param == name => Some(description.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn returns(&self) -> Option<&str> {
+        self.tags.iter().find_map(|tag| match tag {
+            Tag::Returns(description) => Some(description.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl Tag {
+    fn new(name: &str, text: &str) -> Self {
+        match name {
+            "param" => {
+                // A type in braces is redundant with the signature
+                let text = text.trim_start();
+                let text = match text.strip_prefix('{') {
+                    Some(rest) => rest.split_once('}').map_or("", |(_, rest)| rest),
+                    None => text,
+                };
+                let text = text.trim_start();
+                let (name, description) =
+                    text.split_once(char::is_whitespace).unwrap_or((text, ""));
+                let description = description.trim();
+                let description = description.strip_prefix("- ").unwrap_or(description);
+                Tag::Param {
+                    name: name.to_owned(),
+                    description: description.trim().to_owned(),
+                }
+            }
+            "returns" | "return" => Tag::Returns(text.trim().to_owned()),
+            "deprecated" => Tag::Deprecated(text.trim().to_owned()),
+            "example" => Tag::Example(text.trim_matches('\n').trim_end().to_owned()),
+            "see" => Tag::See(text.trim().to_owned()),
+            _ => Tag::Other {
+                name: name.to_owned(),
+                text: text.trim().to_owned(),
+            },
+        }
+    }
+}
+
+/// Text of a description split around its `{@link}` tags
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Inline<'a> {
+    Text(&'a str),
+    /// `{@link target}` or `{@link target text}`
+    Link {
+        target: &'a str,
+        text: &'a str,
+    },
+}
+
+pub fn inlines(text: &str) -> Vec<Inline> {
+    let mut inlines = vec![];
+    let mut rest = text;
+    while let Some(start) = rest.find("{@link") {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        if start > 0 {
+            inlines.push(Inline::Text(&rest[..start]));
+        }
+        let link = rest[start + "{@link".len()..end].trim();
+        let (target, text) = link
+            .split_once(|c: char| c.is_whitespace() || c == '|')
+            .map_or((link, link), |(target, text)| (target, text.trim()));
+        inlines.push(Inline::Link { target, text });
+        rest = &rest[end + 1..];
+    }
+    if !rest.is_empty() {
+        inlines.push(Inline::Text(rest));
+    }
+    inlines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_description_and_tags() {
+        let comment = DocComment::parse(
+            "/**
+             * Logs a single i32 value to stdout
+             *
+             * Values are written on their own line
+             * @param value value to log
+             * @param {i32} base - base to log the value in
+             *   which is 10 unless given
+             * @returns nothing
+             * @since 0.1
+             */",
+        );
+        assert_eq!(
+            comment.description,
+            "Logs a single i32 value to stdout\n\nValues are written on their own line"
+        );
+        assert_eq!(comment.param("value"), Some("value to log"));
+        assert_eq!(
+            comment.param("base"),
+            Some("base to log the value in\n  which is 10 unless given")
+        );
+        assert_eq!(comment.returns(), Some("nothing"));
+        assert_eq!(
+            comment.tags.last(),
+            Some(&Tag::Other {
+                name: "since".to_owned(),
+                text: "0.1".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_empty_tags() {
+        let comment = DocComment::parse("/**\n * \n * @param expected \n * @param actual \n */");
+        assert_eq!(comment.description, "");
+        assert_eq!(comment.param("expected"), Some(""));
+        assert_eq!(comment.param("actual"), Some(""));
+    }
+
+    #[test]
+    fn test_examples_keep_annotations_and_indentation() {
+        let comment = DocComment::parse(
+            "/**
+             * @example
+             * @native(\"env\")
+             * function log(value: i32) {
+             *     println(value);
+             * }
+             * @deprecated use println
+             */",
+        );
+        assert_eq!(
+            comment.tags,
+            vec![
+                Tag::Example(
+                    "@native(\"env\")\nfunction log(value: i32) {\n    println(value);\n}"
+                        .to_owned()
+                ),
+                Tag::Deprecated("use println".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inline_links() {
+        assert_eq!(
+            inlines("Adds {@link Point} to {@link Point.sum the sum} and {@link add|add}."),
+            vec![
+                Inline::Text("Adds "),
+                Inline::Link {
+                    target: "Point",
+                    text: "Point"
+                },
+                Inline::Text(" to "),
+                Inline::Link {
+                    target: "Point.sum",
+                    text: "the sum"
+                },
+                Inline::Text(" and "),
+                Inline::Link {
+                    target: "add",
+                    text: "add"
+                },
+                Inline::Text("."),
+            ]
+        );
+        assert_eq!(
+            inlines("{@link broken"),
+            vec![Inline::Text("{@link broken")]
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{inlines, root_of, signature, Documentation, Inline, Item, ItemKind, Page, Tag};
+use std::fmt::Write;
+
+const STYLE: &str =
+    "body { font-family: sans-serif; max-width: 60rem; margin: 0 auto; padding: 1rem; }
+pre { background: #f4f4f4; padding: 0.5rem; overflow-x: auto; }
+section section { margin-left: 1.5rem; }
+.deprecated { color: #a33; }";
+
+pub(crate) fn page(docs: &Documentation, page: &Page) -> String {
+    let mut body = String::new();
+    for item in &page.items {
+        self::item(docs, page, item, 2, &mut body);
+    }
+    let nav = format!("<a href=\"{}index.html\">Index</a>", root_of(page));
+    document(&page.title, &nav, &body)
+}
+
+pub(crate) fn index(docs: &Documentation) -> String {
+    let mut body = String::from("<ul>\n");
+    for page in &docs.pages {
+        let _ = writeln!(
+            body,
+            "<li><a href=\"{}.html\">{}</a><ul>",
+            page.name,
+            escape(&page.title)
+        );
+        for item in &page.items {
+            let _ = writeln!(
+                body,
+                "<li>{} <a href=\"{}.html#{}\">{}</a></li>",
+                item.kind.name(),
+                page.name,
+                item.anchor,
+                item.name
+            );
+        }
+        body += "</ul></li>\n";
+    }
+    body += "</ul>\n";
+    document("API documentation", "", &body)
+}
+
+fn document(title: &str, nav: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+{style}
+</style>
+</head>
+<body>
+<nav>{nav}</nav>
+<main>
+<h1>{title}</h1>
+{body}</main>
+</body>
+</html>
+",
+        title = escape(title),
+        style = STYLE,
+        nav = nav,
+        body = body
+    )
+}
+
+fn item(docs: &Documentation, page: &Page, item: &Item, level: usize, out: &mut String) {
+    let ty = |ty: &jswt_types::Type| match docs.type_href(page, ty, "html") {
+        Some(href) => format!("<a href=\"{}\">{}</a>", href, ty),
+        None => ty.to_string(),
+    };
+
+    let _ = writeln!(out, "<section id=\"{}\">", item.anchor);
+    let _ = writeln!(
+        out,
+        "<h{level}>{} {}</h{level}>",
+        item.kind.name(),
+        item.name,
+        level = level
+    );
+    let _ = writeln!(out, "<pre><code>{}</code></pre>", signature(item, &ty));
+
+    let doc = item.doc.clone().unwrap_or_default();
+    for tag in &doc.tags {
+        if let Tag::Deprecated(reason) = tag {
+            let _ = writeln!(
+                out,
+                "<p class=\"deprecated\"><strong>Deprecated</strong> {}</p>",
+                text(docs, page, reason)
+            );
+        }
+    }
+    paragraphs(docs, page, &doc.description, out);
+
+    let heading = level + 1;
+    if !item.params.is_empty() {
+        let _ = writeln!(out, "<h{0}>Parameters</h{0}>\n<dl>", heading);
+        for param in &item.params {
+            let _ = writeln!(
+                out,
+                "<dt><code>{}: {}</code></dt>",
+                param.name,
+                ty(&param.ty)
+            );
+            if let Some(description) = doc.param(&param.name).filter(|it| !it.is_empty()) {
+                let _ = writeln!(out, "<dd>{}</dd>", text(docs, page, description));
+            }
+        }
+        out.push_str("</dl>\n");
+    }
+    let returns = item.ty.as_ref().filter(|_| item.kind != ItemKind::Field);
+    if let Some(returns) = returns {
+        let _ = write!(
+            out,
+            "<h{0}>Returns</h{0}>\n<p><code>{1}</code>",
+            heading,
+            ty(returns)
+        );
+        if let Some(description) = doc.returns().filter(|it| !it.is_empty()) {
+            let _ = write!(out, " {}", text(docs, page, description));
+        }
+        out.push_str("</p>\n");
+    }
+
+    for tag in &doc.tags {
+        match tag {
+            Tag::Example(code) => {
+                let _ = writeln!(
+                    out,
+                    "<h{0}>Example</h{0}>\n<pre><code>{1}</code></pre>",
+                    heading,
+                    escape(code)
+                );
+            }
+            Tag::See(target) => {
+                let link = match docs.href(page, target, "html") {
+                    Some(href) => {
+                        format!("<a href=\"{}\"><code>{}</code></a>", href, escape(target))
+                    }
+                    None => text(docs, page, target),
+                };
+                let _ = writeln!(out, "<h{0}>See also</h{0}>\n<p>{1}</p>", heading, link);
+            }
+            Tag::Other { name, text: value } => {
+                let _ = writeln!(
+                    out,
+                    "<h{0}>{1}</h{0}>\n<p>{2}</p>",
+                    heading,
+                    escape(name),
+                    text(docs, page, value)
+                );
+            }
+            Tag::Param { .. } | Tag::Returns(_) | Tag::Deprecated(_) => {}
+        }
+    }
+
+    for member in &item.members {
+        self::item(docs, page, member, level + 1, out);
+    }
+    out.push_str("</section>\n");
+}
+
+fn paragraphs(docs: &Documentation, page: &Page, description: &str, out: &mut String) {
+    for paragraph in description.split("\n\n").filter(|it| !it.trim().is_empty()) {
+        let _ = writeln!(out, "<p>{}</p>", text(docs, page, paragraph.trim()));
+    }
+}
+
+/// Escaped text with its links resolved
+fn text(docs: &Documentation, page: &Page, text: &str) -> String {
+    inlines(text)
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => escape(text),
+            Inline::Link { target, text } => match docs.href(page, target, "html") {
+                Some(href) => format!("<a href=\"{}\"><code>{}</code></a>", href, escape(text)),
+                None => format!("<code>{}</code>", escape(text)),
+            },
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,535 @@
+//! API documentation generated from the `/** */` doc comments of
+//! declarations. Every top level function and class of a source is
+//! visible to the files that import it so they're all documented, along
+//! with the fields, constructors and methods of the classes. Links in
+//! comments and types in signatures are resolved through the global
+//! scope and class bindings of the program.
+
+mod comment;
+mod html;
+mod markdown;
+
+pub use comment::{inlines, DocComment, Inline, Tag};
+
+use jswt_ast::{ClassElement, FormalParameterList, SourceElement};
+use jswt_common::{NodeId, Span};
+use jswt_query::{normalize, Analysis, Database};
+use jswt_types::Type;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemKind {
+    Function,
+    Class,
+    Constructor,
+    Field,
+    Method,
+}
+
+impl ItemKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemKind::Function => "function",
+            ItemKind::Class => "class",
+            ItemKind::Constructor => "constructor",
+            ItemKind::Field => "field",
+            ItemKind::Method => "method",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub ty: Type,
+}
+
+/// A documented declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    /// Id of the item on its page. Members are prefixed by their class
+    pub anchor: String,
+    pub export: bool,
+    pub readonly: bool,
+    pub params: Vec<Param>,
+    /// Return type of functions and methods or the type of a field
+    pub ty: Option<Type>,
+    pub doc: Option<DocComment>,
+    /// Fields, constructor and methods of a class
+    pub members: Vec<Item>,
+}
+
+/// Documentation of a source file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub path: PathBuf,
+    /// Path of the source relative to the documentation root
+    pub title: String,
+    /// Path of the page relative to the output directory without an extension
+    pub name: String,
+    pub items: Vec<Item>,
+}
+
+pub struct Documentation {
+    pub pages: Vec<Page>,
+    analysis: Arc<Analysis>,
+    /// Page and anchor of each documented declaration
+    locations: HashMap<NodeId, (usize, String)>,
+}
+
+impl Documentation {
+    /// Documents the given files of the program the database checks.
+    /// Pages are named after the path of their source relative to root.
+    pub fn new(db: &mut Database, files: &[PathBuf], root: &Path) -> Self {
+        let analysis = db.program();
+        let mut pages = vec![];
+        let mut locations = HashMap::new();
+
+        for path in files {
+            let path = normalize(path);
+            let file = analysis
+                .ast
+                .program
+                .files
+                .iter()
+                .find(|file| file.span.file == path.to_str().unwrap());
+            let file = match file {
+                Some(file) => file,
+                None => continue,
+            };
+
+            let mut items = vec![];
+            for element in &file.source_elements.source_elements {
+                match element {
+                    SourceElement::FunctionDeclaration(function) => {
+                        locations
+                            .insert(function.id, (pages.len(), function.ident.value.to_string()));
+                        items.push(Item {
+                            kind: ItemKind::Function,
+                            name: function.ident.value.to_string(),
+                            anchor: function.ident.value.to_string(),
+                            export: function.decorators.export,
+                            readonly: false,
+                            params: params(&function.params),
+                            ty: function.returns.as_ref().map(|returns| returns.ty.clone()),
+                            doc: doc_comment(db, &function.doc),
+                            members: vec![],
+                        });
+                    }
+                    SourceElement::ClassDeclaration(class) => {
+                        let class_name = class.ident.value.to_string();
+                        locations.insert(class.id, (pages.len(), class_name.clone()));
+                        let members = class
+                            .body
+                            .class_elements
+                            .iter()
+                            .map(|element| {
+                                let (kind, name) = match element {
+                                    ClassElement::Constructor(_) => {
+                                        (ItemKind::Constructor, "constructor".to_owned())
+                                    }
+                                    ClassElement::Method(method) => {
+                                        (ItemKind::Method, method.ident.value.to_string())
+                                    }
+                                    ClassElement::Field(field) => {
+                                        (ItemKind::Field, field.ident.value.to_string())
+                                    }
+                                };
+                                let mut item = Item {
+                                    kind,
+                                    anchor: format!("{}.{}", class_name, name),
+                                    name,
+                                    export: false,
+                                    readonly: false,
+                                    params: vec![],
+                                    ty: None,
+                                    doc: None,
+                                    members: vec![],
+                                };
+                                match element {
+                                    ClassElement::Constructor(constructor) => {
+                                        item.params = params(&constructor.params);
+                                        item.doc = doc_comment(db, &constructor.doc);
+                                    }
+                                    ClassElement::Method(method) => {
+                                        item.params = params(&method.params);
+                                        item.ty = method
+                                            .returns
+                                            .as_ref()
+                                            .map(|returns| returns.ty.clone());
+                                        item.doc = doc_comment(db, &method.doc);
+                                    }
+                                    ClassElement::Field(field) => {
+                                        item.readonly = field.readonly;
+                                        item.ty = Some(field.type_annotation.ty.clone());
+                                        item.doc = doc_comment(db, &field.doc);
+                                    }
+                                }
+                                item
+                            })
+                            .collect();
+                        items.push(Item {
+                            kind: ItemKind::Class,
+                            anchor: class_name.clone(),
+                            name: class_name,
+                            export: false,
+                            readonly: false,
+                            params: vec![],
+                            ty: None,
+                            doc: doc_comment(db, &class.doc),
+                            members,
+                        });
+                    }
+                    SourceElement::Statement(_) => {}
+                }
+            }
+            if items.is_empty() {
+                continue;
+            }
+
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or_else(|_| Path::new(path.file_name().unwrap()));
+            let name: Vec<_> = relative
+                .with_extension("")
+                .iter()
+                .map(|component| component.to_string_lossy().into_owned())
+                .collect();
+            pages.push(Page {
+                title: relative.to_string_lossy().into_owned(),
+                name: name.join("/"),
+                path,
+                items,
+            });
+        }
+
+        Documentation {
+            pages,
+            analysis,
+            locations,
+        }
+    }
+
+    /// Page and anchor of the declaration a link refers to. Names are
+    /// looked up in the global scope and members in their class bindings.
+    pub fn resolve(&self, target: &str) -> Option<(&Page, String)> {
+        let (name, member) = match target.split_once('.') {
+            Some((name, member)) => (name, Some(member)),
+            None => (target, None),
+        };
+        let global = self
+            .analysis
+            .symbols
+            .get_scope(self.analysis.ast.program.id)?;
+        let def = global.defs.get(name)?;
+        let (page, anchor) = self.locations.get(&def.0)?;
+        let anchor = match member {
+            None => anchor.clone(),
+            Some(member) => {
+                let binding = self.analysis.bindings.lookup(name)?;
+                let exists = member == "constructor"
+                    || binding.field(member).is_some()
+                    || binding.method(member).is_some();
+                if !exists {
+                    return None;
+                }
+                format!("{}.{}", anchor, member)
+            }
+        };
+        Some((&self.pages[*page], anchor))
+    }
+
+    /// Link from a page to the declaration the target refers to
+    fn href(&self, from: &Page, target: &str, extension: &str) -> Option<String> {
+        let (page, anchor) = self.resolve(target)?;
+        if page.name == from.name {
+            return Some(format!("#{}", anchor));
+        }
+        Some(format!(
+            "{}{}.{}#{}",
+            root_of(from),
+            page.name,
+            extension,
+            anchor
+        ))
+    }
+
+    /// Link to the class a type refers to, if it's documented
+    fn type_href(&self, from: &Page, ty: &Type, extension: &str) -> Option<String> {
+        match ty {
+            Type::Primitive(_) | Type::Object(_) => self.href(from, &ty.binding_name()?, extension),
+            _ => None,
+        }
+    }
+
+    /// Links in doc comments that don't refer to a declaration
+    pub fn unresolved_links(&self) -> Vec<String> {
+        let mut unresolved = vec![];
+        for page in &self.pages {
+            for item in page
+                .items
+                .iter()
+                .flat_map(|item| std::iter::once(item).chain(&item.members))
+            {
+                let doc = match &item.doc {
+                    Some(doc) => doc,
+                    None => continue,
+                };
+                for text in texts(doc) {
+                    for inline in inlines(text) {
+                        if let Inline::Link { target, .. } = inline {
+                            if self.resolve(target).is_none() {
+                                unresolved.push(format!(
+                                    "{}: '{}' in the docs of '{}' doesn't refer to a declaration",
+                                    page.title, target, item.anchor
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        unresolved
+    }
+
+    pub fn html(&self, page: &Page) -> String {
+        html::page(self, page)
+    }
+
+    pub fn html_index(&self) -> String {
+        html::index(self)
+    }
+
+    pub fn markdown(&self, page: &Page) -> String {
+        markdown::page(self, page)
+    }
+
+    pub fn markdown_index(&self) -> String {
+        markdown::index(self)
+    }
+}
+
+fn params(params: &FormalParameterList) -> Vec<Param> {
+    params
+        .parameters
+        .iter()
+        .map(|param| Param {
+            name: param.ident.value.to_string(),
+            ty: param.type_annotation.ty.clone(),
+        })
+        .collect()
+}
+
+fn doc_comment(db: &mut Database, doc: &Option<Span>) -> Option<DocComment> {
+    let doc = doc.as_ref()?;
+    let source = db.source_text(Path::new(doc.file.as_str()));
+    Some(DocComment::parse(&source[doc.start..doc.end]))
+}
+
+/// Text of a comment that can have links in it
+fn texts(doc: &DocComment) -> impl Iterator<Item = &str> {
+    std::iter::once(doc.description.as_str()).chain(doc.tags.iter().filter_map(|tag| match tag {
+        Tag::Param { description, .. } => Some(description.as_str()),
+        Tag::Returns(text) | Tag::Deprecated(text) | Tag::See(text) => Some(text.as_str()),
+        Tag::Other { text, .. } => Some(text.as_str()),
+        Tag::Example(_) => None,
+    }))
+}
+
+/// Relative path from a page to the output directory
+fn root_of(page: &Page) -> String {
+    "../".repeat(page.name.matches('/').count())
+}
+
+/// Declaration as it's written in source. Types are
+/// written by the given function so they can be linked.
+fn signature(item: &Item, ty: &dyn Fn(&Type) -> String) -> String {
+    let params: Vec<_> = item
+        .params
+        .iter()
+        .map(|param| format!("{}: {}", param.name, ty(&param.ty)))
+        .collect();
+    let returns = match &item.ty {
+        Some(returns) => format!(": {}", ty(returns)),
+        None => String::new(),
+    };
+    match item.kind {
+        ItemKind::Class => format!("class {}", item.name),
+        ItemKind::Function => format!(
+            "{}function {}({}){}",
+            if item.export { "export " } else { "" },
+            item.name,
+            params.join(", "),
+            returns
+        ),
+        ItemKind::Constructor => format!("constructor({})", params.join(", ")),
+        ItemKind::Method => format!("{}({}){}", item.name, params.join(", "), returns),
+        ItemKind::Field => format!(
+            "{}{}{}",
+            if item.readonly { "readonly " } else { "" },
+            item.name,
+            returns
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jswt_assert::assert_snapshot;
+
+    const SOURCE: &str = r#"/**
+ * A point on a plane
+ * @see distance
+ */
+class Point {
+    /** Distance from the y axis */
+    readonly x: i32;
+    y: i32;
+
+    /**
+     * @param x distance from the y axis
+     * @param y distance from the x axis
+     */
+    constructor(x: i32, y: i32) {
+        this.x = x;
+        this.y = y;
+    }
+
+    /**
+     * Sum of the coordinates, see {@link Point.x} and {@link Point.y}
+     * @returns x + y
+     */
+    sum(): i32 {
+        return this.x + this.y;
+    }
+}
+
+/**
+ * Manhattan distance between two points, same as calling
+ * {@link Point.sum the sum} of their difference.
+ *
+ * Points are compared <by value> & not by reference.
+ * @param a first point
+ * @param b second point
+ * @returns the distance
+ * @example
+ * let distance = distance(new Point(1, 2), new Point(3, 4));
+ * @deprecated use {@link Point.sum}
+ */
+export function distance(a: Point, b: Point): i32 {
+    return (b.x - a.x) + (b.y - a.y);
+}
+
+/** Refers to {@link Missing} and {@link Point.z} */
+function undocumented(value: i32) {
+}
+"#;
+
+    fn documentation(name: &str) -> Documentation {
+        let root = normalize(&Path::new(env!("CARGO_MANIFEST_DIR")).join(".."));
+        let runtime = root.join("runtime").join("rt.jswt");
+        let path = root.join("jswt-doc").join(name).join("geometry.jswt");
+
+        let mut db = Database::new();
+        db.set_roots(vec![path.clone(), runtime]);
+        db.set_source_text(&path, SOURCE.to_owned());
+        // The runtime is documented too so types link to it
+        Documentation::new(
+            &mut db,
+            &[path, root.join("runtime").join("i32.jswt")],
+            &root,
+        )
+    }
+
+    #[test]
+    fn test_pages_are_named_after_their_sources() {
+        let docs = documentation("test_pages_are_named_after_their_sources");
+        let names: Vec<_> = docs.pages.iter().map(|page| page.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "jswt-doc/test_pages_are_named_after_their_sources/geometry",
+                "runtime/i32"
+            ]
+        );
+        let items: Vec<_> = docs.pages[0]
+            .items
+            .iter()
+            .map(|item| (item.kind, item.anchor.as_str()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (ItemKind::Class, "Point"),
+                (ItemKind::Function, "distance"),
+                (ItemKind::Function, "undocumented"),
+            ]
+        );
+        let members: Vec<_> = docs.pages[0].items[0]
+            .members
+            .iter()
+            .map(|item| item.anchor.as_str())
+            .collect();
+        assert_eq!(
+            members,
+            vec!["Point.x", "Point.y", "Point.constructor", "Point.sum"]
+        );
+        assert_eq!(
+            docs.pages[0].items[1].doc.as_ref().unwrap().param("b"),
+            Some("second point")
+        );
+    }
+
+    #[test]
+    fn test_links_resolve_through_the_symbol_tables() {
+        let docs = documentation("test_links_resolve_through_the_symbol_tables");
+        let resolve = |target: &str| {
+            docs.resolve(target)
+                .map(|(page, anchor)| format!("{}#{}", page.name, anchor))
+        };
+        let page = "jswt-doc/test_links_resolve_through_the_symbol_tables/geometry";
+        assert_eq!(resolve("Point"), Some(format!("{}#Point", page)));
+        assert_eq!(resolve("Point.sum"), Some(format!("{}#Point.sum", page)));
+        assert_eq!(resolve("i32.add"), Some("runtime/i32#i32.add".to_owned()));
+        // Declarations in sources that aren't documented have nowhere to link to
+        assert_eq!(resolve("println"), None);
+        assert_eq!(resolve("Point.z"), None);
+        assert_eq!(
+            docs.unresolved_links(),
+            vec![
+                format!(
+                    "{}.jswt: 'Missing' in the docs of 'undocumented' doesn't refer to a declaration",
+                    page
+                ),
+                format!(
+                    "{}.jswt: 'Point.z' in the docs of 'undocumented' doesn't refer to a declaration",
+                    page
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_html_page() {
+        let docs = documentation("test_html_page");
+        assert_snapshot!(docs.html(&docs.pages[0]));
+    }
+
+    #[test]
+    fn test_markdown_page() {
+        let docs = documentation("test_markdown_page");
+        assert_snapshot!(docs.markdown(&docs.pages[0]));
+    }
+
+    #[test]
+    fn test_indexes() {
+        let docs = documentation("test_indexes");
+        assert_snapshot!("html_index", docs.html_index());
+        assert_snapshot!("markdown_index", docs.markdown_index());
+    }
+}
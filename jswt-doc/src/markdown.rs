@@ -0,0 +1,120 @@
+use crate::{inlines, root_of, signature, Documentation, Inline, Item, ItemKind, Page, Tag};
+use std::fmt::Write;
+
+pub(crate) fn page(docs: &Documentation, page: &Page) -> String {
+    let mut out = format!("[Index]({}index.md)\n\n# {}\n", root_of(page), page.title);
+    for item in &page.items {
+        self::item(docs, page, item, 2, &mut out);
+    }
+    out
+}
+
+pub(crate) fn index(docs: &Documentation) -> String {
+    let mut out = String::from("# API documentation\n\n");
+    for page in &docs.pages {
+        let _ = writeln!(out, "- [{}]({}.md)", page.title, page.name);
+        for item in &page.items {
+            let _ = writeln!(
+                out,
+                "  - {} [{}]({}.md#{})",
+                item.kind.name(),
+                item.name,
+                page.name,
+                item.anchor
+            );
+        }
+    }
+    out
+}
+
+fn item(docs: &Documentation, page: &Page, item: &Item, level: usize, out: &mut String) {
+    let ty = |ty: &jswt_types::Type| match docs.type_href(page, ty, "md") {
+        Some(href) => format!("[`{}`]({})", ty, href),
+        None => format!("`{}`", ty),
+    };
+
+    // Heading ids differ between renderers so the anchor is given explicitly
+    let _ = writeln!(
+        out,
+        "\n<a id=\"{}\"></a>\n\n{} {} {}\n",
+        item.anchor,
+        "#".repeat(level),
+        item.kind.name(),
+        item.name
+    );
+    let _ = writeln!(
+        out,
+        "```jswt\n{}\n```",
+        signature(item, &|ty| ty.to_string())
+    );
+
+    let doc = item.doc.clone().unwrap_or_default();
+    for tag in &doc.tags {
+        if let Tag::Deprecated(reason) = tag {
+            let _ = writeln!(out, "\n**Deprecated** {}", text(docs, page, reason));
+        }
+    }
+    for paragraph in doc
+        .description
+        .split("\n\n")
+        .filter(|it| !it.trim().is_empty())
+    {
+        let _ = writeln!(out, "\n{}", text(docs, page, paragraph.trim()));
+    }
+
+    if !item.params.is_empty() {
+        out.push_str("\n**Parameters**\n\n");
+        for param in &item.params {
+            let _ = write!(out, "- `{}`: {}", param.name, ty(&param.ty));
+            if let Some(description) = doc.param(&param.name).filter(|it| !it.is_empty()) {
+                let _ = write!(out, " — {}", text(docs, page, description));
+            }
+            out.push('\n');
+        }
+    }
+    let returns = item.ty.as_ref().filter(|_| item.kind != ItemKind::Field);
+    if let Some(returns) = returns {
+        let _ = write!(out, "\n**Returns** {}", ty(returns));
+        if let Some(description) = doc.returns().filter(|it| !it.is_empty()) {
+            let _ = write!(out, " — {}", text(docs, page, description));
+        }
+        out.push('\n');
+    }
+
+    for tag in &doc.tags {
+        match tag {
+            Tag::Example(code) => {
+                let _ = writeln!(out, "\n**Example**\n\n```jswt\n{}\n```", code);
+            }
+            Tag::See(target) => {
+                let link = match docs.href(page, target, "md") {
+                    Some(href) => format!("[`{}`]({})", target, href),
+                    None => text(docs, page, target),
+                };
+                let _ = writeln!(out, "\n**See also** {}", link);
+            }
+            Tag::Other { name, text: value } => {
+                let _ = writeln!(out, "\n**{}** {}", name, text(docs, page, value));
+            }
+            Tag::Param { .. } | Tag::Returns(_) | Tag::Deprecated(_) => {}
+        }
+    }
+
+    for member in &item.members {
+        self::item(docs, page, member, level + 1, out);
+    }
+}
+
+/// Text with its links resolved
+fn text(docs: &Documentation, page: &Page, text: &str) -> String {
+    inlines(text)
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Text(text) => text.to_owned(),
+            Inline::Link { target, text } => match docs.href(page, target, "md") {
+                Some(href) => format!("[`{}`]({})", text, href),
+                None => format!("`{}`", text),
+            },
+        })
+        .collect()
+}
@@ -25,7 +25,7 @@ impl ErrorEmitter {
             } = diagnostic;
 
             let file = &span.file.to_string();
-            let source = fs::read_to_string(file);
+            let source = &*fs::read_to_string(file);
             let location = Location {
                 end: location_from_offset(source, span.end),
                 start: location_from_offset(source, span.start),
@@ -47,6 +47,7 @@ fn create_header(file: &str, location: &LineCol, level: &Level) -> String {
     let err = match level {
         Level::Error => "error".bright_red().bold(),
         Level::Warning => "warning".yellow().bold(),
+        Level::Note => "note".cyan().bold(),
     };
 
     format!("{}: {}:{}:{}", err, file, location.line, location.col)
@@ -11,18 +11,27 @@ use jswt_semantics::*;
 use jswt_tokenizer::TokenizerError;
 
 pub struct DiagnosticMessage {
-    level: Level,
-    message: Cow<'static, str>,
-    span: Span,
-    hint: Option<Cow<'static, str>>,
+    pub level: Level,
+    pub message: Cow<'static, str>,
+    pub span: Span,
+    pub hint: Option<Cow<'static, str>>,
 }
 
 pub enum Level {
     Error,
     Warning,
+    /// Additional context attached to another diagnostic
+    Note,
 }
 
 pub fn print_semantic_error(error: &SemanticError) {
+    let emitter = ErrorEmitter::new();
+    emitter.emit(&semantic_diagnostics(error));
+}
+
+/// Diagnostics describing the error followed by notes that give it context
+pub fn semantic_diagnostics(error: &SemanticError) -> Vec<DiagnosticMessage> {
+    let mut diagnostics = vec![];
     let diagnostic = match error {
         SemanticError::VariableNotDefined { name, span } => DiagnosticMessage {
             level: Level::Error,
@@ -54,9 +63,36 @@ pub fn print_semantic_error(error: &SemanticError) {
             message: format!("Class '{}' was not defined in this scope", ident).into(),
             hint: None,
         },
+        SemanticError::TypeNotDefined { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Type '{}' was not defined in this scope", name).into(),
+            hint: match name.as_ref() {
+                "bool" => Some("Use 'boolean' instead".into()),
+                _ => None,
+            },
+        },
+        SemanticError::InvalidCast { from, to, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("TypeError: '{}' cannot be cast to '{}'.", from, to).into(),
+            hint: None,
+        },
+        SemanticError::InvalidAssignmentTarget { span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: "Invalid left-hand side in assignment".into(),
+            hint: None,
+        },
+        SemanticError::IntegerOutOfRange { ty, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Integer literal is out of range for '{}'", ty).into(),
+            hint: Some("Annotate the value as 'i64' to use a 64 bit integer".into()),
+        },
         SemanticError::NotAFunctionError { span, name_span } => {
             let file = &span.file.to_string();
-            let source = fs::read_to_string(file);
+            let source = &*fs::read_to_string(file);
             let offending_token = &source[name_span.start..name_span.end];
             DiagnosticMessage {
                 level: Level::Error,
@@ -71,7 +107,7 @@ pub fn print_semantic_error(error: &SemanticError) {
             expected,
         } => {
             let file = &span.file.to_string();
-            let source = fs::read_to_string(file);
+            let source = &*fs::read_to_string(file);
             let offending_token = &source[offending_token.start..offending_token.end];
             DiagnosticMessage {
                 level: Level::Error,
@@ -86,7 +122,7 @@ pub fn print_semantic_error(error: &SemanticError) {
         }
         SemanticError::FunctionNotDefined { span, name_span } => {
             let file = &span.file.to_string();
-            let source = fs::read_to_string(file);
+            let source = &*fs::read_to_string(file);
             let offending_token = &source[name_span.start..name_span.end];
             DiagnosticMessage {
                 level: Level::Error,
@@ -119,37 +155,240 @@ pub fn print_semantic_error(error: &SemanticError) {
             message: format!("Method '{}' was already defined in this scope", name).into(),
             hint: None,
         },
+        SemanticError::UnknownAnnotation { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Unknown annotation '@{}'", name).into(),
+            hint: None,
+        },
+        SemanticError::AnnotationNotApplicable { name, target, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Annotation '@{}' cannot be applied to a {}", name, target).into(),
+            hint: None,
+        },
+        SemanticError::UnexpectedAnnotationArgument { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Annotation '@{}' does not accept this argument", name).into(),
+            hint: Some("Remove this argument".into()),
+        },
+        SemanticError::MissingAnnotationArgument {
+            name,
+            argument,
+            span,
+        } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Annotation '@{}' requires argument '{}'", name, argument).into(),
+            hint: None,
+        },
+        SemanticError::DuplicateAnnotationArgument {
+            name,
+            argument,
+            span,
+        } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!(
+                "Argument '{}' of annotation '@{}' was already provided",
+                argument, name
+            )
+            .into(),
+            hint: None,
+        },
+        SemanticError::InvalidAnnotationArgument {
+            name,
+            argument,
+            expected,
+            span,
+        } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!(
+                "TypeError: Argument '{}' of annotation '@{}' must be a {} literal",
+                argument, name, expected
+            )
+            .into(),
+            hint: None,
+        },
+        SemanticError::TypeMismatch {
+            expected,
+            found,
+            span,
+        } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("TypeError: Expected '{}', but found '{}'", expected, found).into(),
+            hint: None,
+        },
+        SemanticError::InvalidOperand {
+            operator,
+            found,
+            span,
+        } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!(
+                "TypeError: Operator '{}' cannot be applied to '{}'",
+                operator, found
+            )
+            .into(),
+            hint: None,
+        },
+        SemanticError::ArgumentCountMismatch {
+            expected,
+            found,
+            span,
+            declaration,
+        } => {
+            let file = &declaration.file.to_string();
+            let source = &*fs::read_to_string(file);
+            let name = &source[declaration.start..declaration.end];
+            DiagnosticMessage {
+                level: Level::Error,
+                span: span.clone(),
+                message: format!(
+                    "'{}' expects {} argument(s), but {} were provided.",
+                    name, expected, found
+                )
+                .into(),
+                hint: None,
+            }
+        }
+        SemanticError::ArgumentTypeMismatch {
+            expected,
+            found,
+            span,
+            ..
+        } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!(
+                "TypeError: Expected argument of type '{}', but found '{}'",
+                expected, found
+            )
+            .into(),
+            hint: None,
+        },
+        SemanticError::MissingReturn { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Not all code paths in '{}' return a value", name).into(),
+            hint: None,
+        },
+        SemanticError::Lint {
+            lint,
+            level,
+            name,
+            span,
+        } => {
+            let name = name.as_deref().unwrap_or_default();
+            let message = match lint {
+                Lint::UnusedVariable => format!("Variable '{}' is never used", name),
+                Lint::UnusedParameter => format!("Parameter '{}' is never used", name),
+                Lint::UnusedNative => format!("Native function '{}' is never used", name),
+                Lint::UnusedImport => format!("Nothing imported from '{}' is used", name),
+                Lint::UnusedFunction => format!("Function '{}' is never used", name),
+                Lint::Shadowing => format!("'{}' shadows a binding in an outer scope", name),
+                Lint::ConstantCondition => "Condition is always the same".to_string(),
+                Lint::SelfAssignment => "Value is assigned to itself".to_string(),
+                Lint::UnreachableCode => "Unreachable code".to_string(),
+                Lint::MissingSemicolon => "Missing semicolon".to_string(),
+            };
+            let level = match level {
+                LintLevel::Deny => Level::Error,
+                _ => Level::Warning,
+            };
+            DiagnosticMessage {
+                level,
+                span: span.clone(),
+                message: format!("{} [{}]", message, lint).into(),
+                hint: Some(format!("Silence this with @allow(\"{}\")", lint).into()),
+            }
+        }
+        SemanticError::UnknownLint { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Unknown lint '{}'", name).into(),
+            hint: None,
+        },
+        SemanticError::AssignmentToConstant { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Cannot assign to '{}' because it is a constant", name).into(),
+            hint: Some("Declare it with 'let' instead".into()),
+        },
+        SemanticError::AssignmentToReadonly { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Cannot assign to '{}' because it is a readonly field", name).into(),
+            hint: Some("Readonly fields can only be assigned in the constructor".into()),
+        },
+        SemanticError::ConstantWithoutInitializer { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Constant '{}' must be initialized", name).into(),
+            hint: None,
+        },
+        SemanticError::UseBeforeAssignment { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Variable '{}' is used before being assigned", name).into(),
+            hint: None,
+        },
+        SemanticError::UninitializedField { name, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("Field '{}' is used before being initialized", name).into(),
+            hint: Some("Assign the field earlier in the constructor".into()),
+        },
     };
+    diagnostics.push(diagnostic);
 
-    let emitter = ErrorEmitter::new();
-    emitter.emit(&[diagnostic]);
+    // Point at the declaration of the function being called
+    if let SemanticError::ArgumentCountMismatch { declaration, .. }
+    | SemanticError::ArgumentTypeMismatch { declaration, .. } = error
+    {
+        diagnostics.push(DiagnosticMessage {
+            level: Level::Note,
+            span: declaration.clone(),
+            message: "Declared here".into(),
+            hint: None,
+        });
+    }
+
+    diagnostics
 }
 
 pub fn print_tokenizer_error(error: &TokenizerError) {
-    let diagnostic = match error {
+    let emitter = ErrorEmitter::new();
+    emitter.emit(&[tokenizer_diagnostic(error)]);
+}
+
+pub fn tokenizer_diagnostic(error: &TokenizerError) -> DiagnosticMessage {
+    match error {
         TokenizerError::UnreconizedToken {
             file,
             token,
             offset,
         } => DiagnosticMessage {
             level: Level::Error,
-            span: Span::new(
-                file.clone().into(),
-                file.clone().into(),
-                *offset,
-                *offset + 1,
-            ),
+            span: Span::new(file.clone(), file.clone(), *offset, *offset + 1),
             message: format!("SyntaxError: Unrecognized token '{}'.", token).into(),
             hint: Some("Remove this token".into()),
         },
         TokenizerError::UnexpectedEof => todo!(),
-    };
-    let emitter = ErrorEmitter::new();
-    emitter.emit(&[diagnostic]);
+    }
 }
 
 pub fn print_parser_error(error: &ParseError) {
-    let diagnostic = match error {
+    let emitter = ErrorEmitter::new();
+    emitter.emit(&[parser_diagnostic(error)]);
+}
+
+pub fn parser_diagnostic(error: &ParseError) -> DiagnosticMessage {
+    match error {
         ParseError::MismatchedToken {
             expected,
             actual,
@@ -173,13 +412,41 @@ pub fn print_parser_error(error: &ParseError) {
             level: Level::Error,
             span: span.clone(),
             message: format!(
-                "NoViableAlternative: Expected {:?}, but found {:?}",
-                expected, actual
+                "SyntaxError: Expected one of {}, but found '{}'",
+                expected
+                    .iter()
+                    .map(|ty| format!("'{}'", ty))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                actual
             )
             .into(),
             hint: None,
         },
-    };
-    let emitter = ErrorEmitter::new();
-    emitter.emit(&[diagnostic]);
+
+        ParseError::MissingOperand { operator, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!("SyntaxError: Expected an expression after '{}'", operator).into(),
+            hint: Some("Add an operand after this operator".into()),
+        },
+
+        ParseError::JumpOutsideLoop { keyword, span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: format!(
+                "SyntaxError: Illegal '{}' statement outside of a loop",
+                keyword
+            )
+            .into(),
+            hint: None,
+        },
+
+        ParseError::IntegerTooLarge { span } => DiagnosticMessage {
+            level: Level::Error,
+            span: span.clone(),
+            message: "SyntaxError: Integer literal is too large".into(),
+            hint: Some("Integer literals have to fit in 64 bits".into()),
+        },
+    }
 }
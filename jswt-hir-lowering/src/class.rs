@@ -1,17 +1,16 @@
-use std::borrow::Cow;
-
-use jswt_ast::{transform::TransformVisitor, *};
-use jswt_common::Spannable;
+use jswt_ast::*;
+use jswt_common::{Atom, NodeId, Spannable};
 use jswt_symbols::{BindingsTable, ClassBinding};
 use jswt_synthetic::*;
+use jswt_types::Type;
 
 pub struct HirClassLoweringContext<'a> {
-    class_name: Cow<'static, str>,
+    class_name: Atom,
     class_binding: &'a ClassBinding,
 }
 
 impl<'a> HirClassLoweringContext<'a> {
-    pub fn new(class: &'a ClassDeclarationElement, bindings: &'a BindingsTable) -> Self {
+    pub fn new(class: &ClassDeclarationElement, bindings: &'a BindingsTable) -> Self {
         let class_name = class.ident.value.clone();
         let class_binding = bindings.lookup(&class_name).expect(&format!(
             "class binding '{}' missing from bindings table",
@@ -22,27 +21,31 @@ impl<'a> HirClassLoweringContext<'a> {
             class_binding,
         }
     }
-}
 
-/// Minimum tree walker
-impl<'a> TransformVisitor for HirClassLoweringContext<'a> {
-    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) -> SourceElements {
-        transform::walk_class_declaration(self, node)
+    /// Lowers the class into a function per constructor and method. The
+    /// declaration is consumed so member bodies are moved, not cloned.
+    pub fn lower(&mut self, class: ClassDeclarationElement) -> Vec<SourceElement> {
+        let mut source_elements = vec![];
+        for class_element in class.body.class_elements {
+            match class_element {
+                ClassElement::Constructor(elem) => {
+                    source_elements.push(self.lower_constructor(elem).into())
+                }
+                ClassElement::Method(elem) => source_elements.push(self.lower_method(elem).into()),
+                // Class fields are not desugared
+                ClassElement::Field(_) => {}
+            }
+        }
+        source_elements
     }
 
-    fn visit_class_body(&mut self, node: &ClassBody) -> SourceElements {
-        transform::walk_class_body(self, node)
-    }
+    fn lower_constructor(&mut self, node: ClassConstructorElement) -> FunctionDeclarationElement {
+        let span = node.span();
 
-    fn visit_class_constructor_declaration(
-        &mut self,
-        node: &ClassConstructorElement,
-    ) -> SourceElements {
         // Size to allocate for the class instance
         let class_size = self.class_binding.size();
 
-        // Walk the constructor body
-        let mut body = self.visit_block_statement(&node.body);
+        let mut body = node.body.statements;
 
         // Allocate the class instance
         let this = variable_decl_stmt("this".into(), malloc(class_size));
@@ -55,175 +58,84 @@ impl<'a> TransformVisitor for HirClassLoweringContext<'a> {
 
         // Build the synthetic function declaration representing the constructor
         let synthetic_constructor_name = format!("{}#constructor", self.class_name);
-        SourceElements {
-            span: node.span(),
-            source_elements: vec![SourceElement::FunctionDeclaration(
-                FunctionDeclarationElement {
-                    span: node.span(),
-                    decorators: FunctionDecorators {
-                        annotations: vec![],
-                        export: false,
-                    },
-                    ident: Identifier {
-                        span: node.span(),
-                        value: synthetic_constructor_name.into(),
-                    },
-                    params: node.params.clone(),
-                    // Class constructors always return a pointer
-                    returns: Some(TypeAnnotation {
-                        span: node.span(),
-                        ty: type_ptr(),
-                    }),
-                    body: BlockStatement {
-                        span: node.body.span(),
-                        statements: body,
-                    },
-                },
-            )],
+        FunctionDeclarationElement {
+            id: node.id,
+            span: span.clone(),
+            doc: node.doc,
+            decorators: FunctionDecorators {
+                annotations: vec![],
+                export: false,
+            },
+            ident: Identifier {
+                span: span.clone(),
+                value: synthetic_constructor_name.into(),
+            },
+            params: node.params,
+            // Class constructors always return a pointer
+            returns: Some(TypeAnnotation {
+                span,
+                ty: type_ptr(),
+            }),
+            body: BlockStatement {
+                id: node.body.id,
+                span: node.body.span,
+                statements: body,
+            },
         }
     }
 
-    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) -> SourceElements {
-        let method_name = &node.ident.value;
+    fn lower_method(&mut self, node: ClassMethodElement) -> FunctionDeclarationElement {
+        let span = node.span();
+
         // Generate synthetic function name ClassName#methodName
         let ident = Identifier {
             span: node.ident.span(),
-            value: format!("{}#{}", self.class_name, method_name).into(),
+            value: format!("{}#{}", self.class_name, node.ident.value).into(),
         };
 
         // Generate function parameters
-        // The first parameter is the 'this' instance
-        let mut params = node.params.clone();
+        // The first parameter is the 'this' instance, primitive classes
+        // receive their value while other classes receive a pointer
+        let this_ty = match Type::from_name(&self.class_name) {
+            Type::Object(_) => type_ptr(),
+            ty => ty,
+        };
+        let mut params = node.params;
         params.parameters.insert(
             0,
             FormalParameterArg {
-                span: node.span(),
+                id: NodeId::DUMMY,
+                span: span.clone(),
+                annotations: vec![],
                 ident: Identifier {
-                    span: node.span(),
+                    span: span.clone(),
                     value: "this".into(),
                 },
                 type_annotation: TypeAnnotation {
-                    span: node.span(),
-                    ty: type_ptr(),
+                    span: span.clone(),
+                    ty: this_ty,
                 },
             },
         );
 
-        // Generate the function body
-        let block = BlockStatement {
-            span: node.span(),
-            statements: self.visit_block_statement(&node.body),
-        };
-
-        SourceElements {
-            span: node.span(),
-            source_elements: vec![SourceElement::FunctionDeclaration(
-                FunctionDeclarationElement {
-                    span: node.span(),
-                    decorators: FunctionDecorators {
-                        annotations: node.annotations.clone(),
-                        export: false,
-                    },
-                    ident,
-                    params,
-                    returns: node.returns.clone(),
-                    body: block,
-                },
-            )],
-        }
-    }
-
-    fn visit_assignment_expression(&mut self, node: &BinaryExpression) -> SingleExpression {
-        if let SingleExpression::MemberDot(dot) = &*node.left {
-            if let SingleExpression::This(_) = &*dot.target {
-                // Handle this.field = value assignments inside of class methods
-                // The member dot target should always be an identifier
-                let lhs = dot.expression.as_identifier().unwrap();
-                let rhs = &*node.right;
-                let field_name = &lhs.ident.value;
-
-                let field = self.class_binding.field(field_name).expect(&format!(
-                    "missing field '{}' in class '{}'.", // This should never happen
-                    field_name, self.class_name
-                ));
-                // Assignment is
-                return i32_store("this", field.index as i32 * 4, rhs.clone());
-            }
-        }
-        transform::walk_assignment_expression(self, node)
-    }
-
-    fn visit_class_field_declaration(&mut self, node: &ClassFieldElement) -> SourceElements {
-        // Class fields are not desugared
-        SourceElements {
-            span: node.span(),
-            source_elements: vec![],
-        }
-    }
-
-    fn visit_argument_expression(&mut self, node: &ArgumentsExpression) -> SingleExpression {
-        // Resolve the expression arguments
-        let argument_list = &node.arguments;
-        let mut arguments = vec![];
-        for arg in &argument_list.arguments {
-            arguments.push(self.visit_single_expression(arg));
-        }
-
-        // If the ident portion of the arguments is a memberdot expression
-        if let SingleExpression::MemberDot(member_dot) = &*node.ident {
-            // and the target is a this expression. We want to desurar the call
-            // into a call to the class member function.
-            let target = &*member_dot.target;
-            if let SingleExpression::This(_) = target {
-                // The rhs should be an identifier targetting the member function
-                let expr = member_dot
-                    .expression
-                    .as_identifier()
-                    .expect("expected identifier expression on member dot expression");
-
-                let method_name = &expr.ident.value;
-                let method = self.class_binding.method(&method_name).expect(&format!(
-                    "missing method '{}' in class '{}'.", // This should never happen
-                    method_name, self.class_name
-                ));
-
-                // Generate a function that the class member function with
-                // this as the first argument and the node args as the rest.
-                let function_name = format!("{}#{}", self.class_name, method_name);
-                // insert this as the first argument of the arguments list
-                arguments.insert(0, ident_exp("this".into()));
-                return function_call(
-                    function_name.into(),
-                    arguments,
-                    method.signature.returns.clone(),
-                );
-                // Call the function associated with the field
-            }
-        }
-        transform::walk_argument_expression(self, node)
-    }
-
-    fn visit_member_dot(&mut self, node: &MemberDotExpression) -> SingleExpression {
-        if let SingleExpression::This(_) = &*node.target {
-            // Handle this.field access inside of class methods
-            // Determine if the target is an identifier or arguments expression
-            match &*node.expression {
-                SingleExpression::Identifier(ident_exp) => {
-                    let field_name = &ident_exp.ident.value;
-                    let field = self.class_binding.field(field_name).expect(&format!(
-                        "missing field '{}' in class '{}'.", // This should never happen
-                        field_name, self.class_name
-                    ));
-                    // Load the value associated with the field
-                    return i32_load("this", field.index as i32 * 4);
-                }
-                _ => {
-                    // We only care about lowering this expressions at this stage
-                    // so we can just continue to walk the tree as is.
-                }
-            };
+        FunctionDeclarationElement {
+            id: node.id,
+            span: span.clone(),
+            doc: node.doc,
+            decorators: FunctionDecorators {
+                annotations: node.annotations,
+                export: false,
+            },
+            ident,
+            params,
+            returns: node.returns,
+            // Generate the function body
+            body: BlockStatement {
+                id: node.body.id,
+                span,
+                statements: node.body.statements,
+            },
         }
-        transform::walk_member_dot(self, node)
     }
 }
 
@@ -235,6 +147,7 @@ mod test {
     use jswt_semantics::GlobalSemanticResolver;
     use jswt_symbols::ScopedSymbolTable;
     use jswt_tokenizer::Tokenizer;
+    use jswt_types::TypeTable;
 
     use super::*;
     use crate::HirLoweringContext;
@@ -268,8 +181,9 @@ mod test {
         // No errors in global resolver
         assert!(global_resolver.errors().len() == 0);
 
-        let mut lowering = HirLoweringContext::new(&mut bindings_table, &symbol_table);
-        let lowered = lowering.lower(&ast);
+        let types = TypeTable::default();
+        let mut lowering = HirLoweringContext::new(&mut bindings_table, &symbol_table, &types);
+        let lowered = lowering.lower(ast);
         assert_debug_snapshot!(lowered);
     }
 
@@ -297,8 +211,9 @@ mod test {
         // No errors in global resolver
         assert!(global_resolver.errors().len() == 0);
 
-        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table);
-        let lowered = lowering.lower(&ast);
+        let types = TypeTable::default();
+        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table, &types);
+        let lowered = lowering.lower(ast);
         assert_debug_snapshot!(lowered);
     }
 
@@ -327,8 +242,9 @@ mod test {
         // No errors in global resolver
         assert!(global_resolver.errors().len() == 0);
 
-        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table);
-        let lowered = lowering.lower(&ast);
+        let types = TypeTable::default();
+        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table, &types);
+        let lowered = lowering.lower(ast);
         assert_debug_snapshot!(lowered);
     }
 
@@ -361,8 +277,9 @@ mod test {
         // No errors in global resolver
         assert!(global_resolver.errors().len() == 0);
 
-        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table);
-        let lowered = lowering.lower(&ast);
+        let types = TypeTable::default();
+        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table, &types);
+        let lowered = lowering.lower(ast);
         assert_debug_snapshot!(lowered);
     }
 }
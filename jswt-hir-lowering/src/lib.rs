@@ -1,9 +1,13 @@
 mod class;
+mod member;
 mod new;
 
 use class::HirClassLoweringContext;
-use jswt_ast::{transform::*, *};
-use jswt_symbols::{BindingsTable, ScopedSymbolTable};
+use jswt_ast::{mut_visit::*, *};
+use jswt_common::Identifiable;
+use jswt_symbols::{BindingsTable, ClassBinding, ScopedSymbolTable};
+use jswt_synthetic::{function_call, ident_exp};
+use jswt_types::TypeTable;
 use new::HirNewLoweringContext;
 
 /// HIR lowering focuses on reducing high level calls and constructs into
@@ -14,32 +18,163 @@ use new::HirNewLoweringContext;
 pub struct HirLoweringContext<'a> {
     bindings: &'a BindingsTable,
     symbols: &'a ScopedSymbolTable,
+    types: &'a TypeTable,
+    /// Binding of the class whose members are being lowered
+    class: Option<&'a ClassBinding>,
 }
 
 impl<'a> HirLoweringContext<'a> {
-    pub fn new(bindings: &'a BindingsTable, symbols: &'a ScopedSymbolTable) -> Self {
-        Self { bindings, symbols }
+    pub fn new(
+        bindings: &'a BindingsTable,
+        symbols: &'a ScopedSymbolTable,
+        types: &'a TypeTable,
+    ) -> Self {
+        Self {
+            bindings,
+            symbols,
+            types,
+            class: None,
+        }
     }
 
-    pub fn lower(&mut self, ast: &Ast) -> Ast {
-        let program = self.visit_program(&ast.program);
-        Ast { program }
+    /// Lowers the tree in place, nodes are moved into their lowered
+    /// form rather than the tree being rebuilt.
+    pub fn lower(&mut self, mut ast: Ast) -> Ast {
+        self.visit_program(&mut ast.program);
+        ast.assign_ids();
+        ast
+    }
+
+    /// Resolves the class binding of the object a member is accessed on.
+    /// `this` refers to the class being lowered and other objects are
+    /// resolved from the type assigned during type checking.
+    fn member_binding(&self, target: &SingleExpression) -> Option<&'a ClassBinding> {
+        match target {
+            SingleExpression::This(_) => self.class,
+            target => self
+                .bindings
+                .lookup(&self.types.ty(target.id()).binding_name()?),
+        }
+    }
+
+    /// Lowers the object a member is accessed on
+    fn member_target(&mut self, mut target: SingleExpression) -> SingleExpression {
+        match target {
+            SingleExpression::This(this) => ident_exp("this".into()).with_id(this.id),
+            _ => {
+                self.visit_single_expression(&mut target);
+                target
+            }
+        }
     }
-}
 
-impl<'a> TransformVisitor for HirLoweringContext<'a> {
-    fn visit_program(&mut self, node: &Program) -> Program {
-        transform::walk_program(self, node)
+    /// Lower field accesses into loads from the object's memory
+    fn lower_member_dot(&mut self, node: &mut MemberDotExpression) -> Option<SingleExpression> {
+        let field = match (
+            self.member_binding(&node.target),
+            node.expression.as_identifier(),
+        ) {
+            (Some(binding), Some(member)) => binding.field(&member.ident.value)?,
+            _ => return None,
+        };
+
+        let target = self.member_target(node.target.take());
+        Some(member::field_load(target, field).with_id(node.id))
     }
 
+    /// Lower field assignments into stores to the object's memory
+    fn lower_assignment(&mut self, node: &mut BinaryExpression) -> Option<SingleExpression> {
+        let dot = match &mut *node.left {
+            SingleExpression::MemberDot(dot) => dot,
+            _ => return None,
+        };
+        let field = match (
+            self.member_binding(&dot.target),
+            dot.expression.as_identifier(),
+        ) {
+            (Some(binding), Some(member)) => binding.field(&member.ident.value)?,
+            _ => return None,
+        };
+
+        let target = self.member_target(dot.target.take());
+        let mut value = node.right.take();
+        self.visit_single_expression(&mut value);
+        Some(member::field_store(target, field, value))
+    }
+
+    /// Lower method calls into calls to the class member function with
+    /// the object passed in as the first argument
+    fn lower_arguments(&mut self, node: &mut ArgumentsExpression) -> Option<SingleExpression> {
+        let dot = match &mut *node.ident {
+            SingleExpression::MemberDot(dot) => dot,
+            _ => return None,
+        };
+        let (binding, method) = match (
+            self.member_binding(&dot.target),
+            dot.expression.as_identifier(),
+        ) {
+            (Some(binding), Some(member)) => (binding, binding.method(&member.ident.value)?),
+            _ => return None,
+        };
+
+        let mut arguments = vec![self.member_target(dot.target.take())];
+        for mut arg in node.arguments.arguments.drain(..) {
+            self.visit_single_expression(&mut arg);
+            arguments.push(arg);
+        }
+
+        let function_name = format!("{}#{}", binding.name, method.name);
+        Some(function_call(function_name.into(), arguments).with_id(node.id))
+    }
+
+    fn lower_new(&mut self, node: &mut NewExpression) -> SingleExpression {
+        // Lower the constructor arguments before the constructor call
+        walk_new(self, node);
+        let mut lowering = HirNewLoweringContext::new(self.bindings);
+        lowering.lower(node)
+    }
+}
+
+impl<'a> MutVisitor for HirLoweringContext<'a> {
     /// Lower class declarations into a series of functions
-    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) -> SourceElements {
-        let mut lowering = HirClassLoweringContext::new(node, &self.bindings);
-        lowering.visit_class_declaration(node)
+    fn visit_source_elements(&mut self, node: &mut SourceElements) {
+        let elements = std::mem::take(&mut node.source_elements);
+        for mut element in elements {
+            let class = match element {
+                SourceElement::ClassDeclaration(class) => class,
+                _ => {
+                    self.visit_source_element(&mut element);
+                    node.source_elements.push(element);
+                    continue;
+                }
+            };
+
+            let mut lowering = HirClassLoweringContext::new(&class, self.bindings);
+            let binding = self.bindings.lookup(&class.ident.value);
+            let mut functions = lowering.lower(class);
+
+            // Lower member accesses in the generated functions
+            self.class = binding;
+            for function in functions.iter_mut() {
+                self.visit_source_element(function);
+            }
+            self.class = None;
+            node.source_elements.append(&mut functions);
+        }
     }
 
-    fn visit_new(&mut self, node: &NewExpression) -> SingleExpression {
-        let mut lowering = HirNewLoweringContext::new(&self.bindings);
-        lowering.visit_new(node)
+    fn visit_single_expression(&mut self, node: &mut SingleExpression) {
+        let lowered = match node {
+            SingleExpression::MemberDot(dot) => self.lower_member_dot(dot),
+            SingleExpression::Assignment(assignment) => self.lower_assignment(assignment),
+            SingleExpression::Arguments(arguments) => self.lower_arguments(arguments),
+            SingleExpression::New(new) => Some(self.lower_new(new)),
+            _ => None,
+        };
+
+        match lowered {
+            Some(lowered) => *node = lowered,
+            None => walk_single_expression(self, node),
+        }
     }
 }
@@ -0,0 +1,91 @@
+use jswt_ast::SingleExpression;
+use jswt_codegen::value_type;
+use jswt_symbols::Field;
+use jswt_synthetic::{
+    f32_load, f32_store, f64_load, f64_store, i32_load, i32_store, i64_load, i64_store,
+};
+use jswt_wast::ValueType;
+
+/// Loads the value of `field` from the object `target` points to
+pub fn field_load(target: SingleExpression, field: &Field) -> SingleExpression {
+    let offset = field.offset as i32;
+    match value_type(&field.ty) {
+        ValueType::I32 => i32_load(target, offset),
+        ValueType::I64 => i64_load(target, offset),
+        ValueType::F32 => f32_load(target, offset),
+        ValueType::F64 => f64_load(target, offset),
+    }
+}
+
+/// Stores `value` into `field` of the object `target` points to
+pub fn field_store(
+    target: SingleExpression,
+    field: &Field,
+    value: SingleExpression,
+) -> SingleExpression {
+    let offset = field.offset as i32;
+    match value_type(&field.ty) {
+        ValueType::I32 => i32_store(target, offset, value),
+        ValueType::I64 => i64_store(target, offset, value),
+        ValueType::F32 => f32_store(target, offset, value),
+        ValueType::F64 => f64_store(target, offset, value),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_parser::Parser;
+    use jswt_semantics::{GlobalSemanticResolver, LocalSemanticResolver, TypeChecker};
+    use jswt_symbols::{BindingsTable, ScopedSymbolTable};
+    use jswt_tokenizer::Tokenizer;
+
+    use crate::HirLoweringContext;
+
+    #[test]
+    fn test_member_lowering_lowers_members_of_objects() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_member_lowering_lowers_members_of_objects",
+            r"
+        class Point {
+            x: i32;
+            scale: f32;
+
+            constructor() {}
+
+            sum(other: i32): i32 {
+                return this.x + other;
+            }
+        }
+
+        function main(p: Point): i32 {
+            p.scale = 2.0;
+            return p.sum(p.x);
+        }
+    ",
+        );
+
+        let ast = Parser::new(&mut tokenizer).parse();
+
+        let mut symbol_table = ScopedSymbolTable::default();
+        let mut bindings_table = BindingsTable::default();
+        let mut global_resolver =
+            GlobalSemanticResolver::new(&mut bindings_table, &mut symbol_table);
+        global_resolver.resolve(&ast);
+        let mut local_resolver = LocalSemanticResolver::new(&mut bindings_table, &mut symbol_table);
+        local_resolver.resolve(&ast);
+        let resolutions = local_resolver.resolutions;
+        let mut checker = TypeChecker::new(&mut bindings_table, &mut symbol_table, &resolutions);
+        checker.resolve(&ast);
+
+        // Members are resolved using the types assigned by the checker
+        assert!(checker.errors().is_empty());
+        let types = checker.types;
+
+        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table, &types);
+        let lowered = lowering.lower(ast);
+        assert_debug_snapshot!(lowered);
+    }
+}
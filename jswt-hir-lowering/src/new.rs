@@ -1,5 +1,4 @@
-use jswt_ast::{transform::TransformVisitor, NewExpression, SingleExpression};
-use jswt_common::Type;
+use jswt_ast::{NewExpression, SingleExpression};
 use jswt_symbols::BindingsTable;
 
 #[derive(Debug)]
@@ -11,31 +10,28 @@ impl<'a> HirNewLoweringContext<'a> {
     pub fn new(bindings: &'a BindingsTable) -> Self {
         Self { bindings }
     }
-}
-
-impl<'a> TransformVisitor for HirNewLoweringContext<'a> {
-    fn visit_new(&mut self, node: &NewExpression) -> SingleExpression {
-        // New expressions are desugared to a call to a constructor function.
-        // visit the arguments to ensure that they are lowered if necessary
-        let node_as_args = node.expression.as_arguments().expect(&format!(
-            "New expressions should be followed by an arguments call"
-        ));
 
-        let args_exp = self.visit_argument_expression(node_as_args);
-        let mut args_exp = args_exp.as_arguments().unwrap().clone();
+    /// New expressions are desugared to a call to a constructor function.
+    /// The arguments call is moved out of the new expression and retargeted.
+    pub fn lower(&mut self, node: &mut NewExpression) -> SingleExpression {
+        let mut args_exp = match node.expression.take() {
+            SingleExpression::Arguments(args_exp) => args_exp,
+            _ => panic!("New expressions should be followed by an arguments call"),
+        };
 
         // Change the target of the arguments call to the constructor function
         let ident_exp = args_exp.ident.as_identifier_mut().unwrap();
-        let ident_name = ident_exp.ident.value.clone();
+        let ident_name = &ident_exp.ident.value;
 
         // find the class binding for the identifier
-        let _ = self.bindings.lookup(&ident_name).expect(&format!(
+        let _ = self.bindings.lookup(ident_name).expect(&format!(
             "Could not find class binding for identifier {}",
             ident_name
         ));
 
         ident_exp.ident.value = format!("{}#constructor", ident_name).into();
-        args_exp.ty = Type::Binding(ident_name);
+        // The call takes the place of the new expression
+        args_exp.id = node.id;
         SingleExpression::Arguments(args_exp)
     }
 }
@@ -48,6 +44,7 @@ mod test {
     use jswt_semantics::GlobalSemanticResolver;
     use jswt_symbols::ScopedSymbolTable;
     use jswt_tokenizer::Tokenizer;
+    use jswt_types::TypeTable;
 
     use super::*;
     use crate::HirLoweringContext;
@@ -80,8 +77,9 @@ mod test {
         // No errors in global resolver
         assert!(global_resolver.errors().len() == 0);
 
-        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table);
-        let lowered = lowering.lower(&ast);
+        let types = TypeTable::default();
+        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table, &types);
+        let lowered = lowering.lower(ast);
         assert_debug_snapshot!(lowered);
     }
 }
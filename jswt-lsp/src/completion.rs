@@ -0,0 +1,146 @@
+use crate::protocol::{CompletionItem, CompletionItemKind};
+use jswt_ast::{visit::*, SingleExpression};
+use jswt_common::{Identifiable, NodeId, Spannable};
+use jswt_query::{Analysis, Index};
+use jswt_types::Type;
+use std::ops::Range;
+
+/// Member access being typed, like `this.point.` or `point.x`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberAccess {
+    /// Offsets of the expression whose members are accessed
+    pub target: Range<usize>,
+    /// Offsets of the dot and the member name typed so far
+    pub member: Range<usize>,
+}
+
+impl MemberAccess {
+    /// Finds the member access around the offset. Only chains of
+    /// identifiers are recognized since they can't have side effects.
+    pub fn at(text: &str, offset: usize) -> Option<Self> {
+        let bytes = text.as_bytes();
+        let is_ident = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$';
+
+        let mut dot = offset.min(bytes.len());
+        while dot > 0 && is_ident(bytes[dot - 1]) {
+            dot -= 1;
+        }
+        if dot == 0 || bytes[dot - 1] != b'.' {
+            return None;
+        }
+        dot -= 1;
+
+        let mut end = offset.min(bytes.len());
+        while end < bytes.len() && is_ident(bytes[end]) {
+            end += 1;
+        }
+
+        let mut start = dot;
+        loop {
+            let segment_end = start;
+            while start > 0 && is_ident(bytes[start - 1]) {
+                start -= 1;
+            }
+            if start == segment_end || bytes[start].is_ascii_digit() {
+                return None;
+            }
+            if start > 0 && bytes[start - 1] == b'.' {
+                start -= 1;
+            } else {
+                break;
+            }
+        }
+
+        Some(MemberAccess {
+            target: start..dot,
+            member: dot..end,
+        })
+    }
+
+    /// The text with the member left out so the target can be parsed
+    /// and checked as an expression of its own
+    pub fn without_member(&self, text: &str) -> String {
+        let mut text = text.to_owned();
+        let blank = " ".repeat(self.member.len());
+        text.replace_range(self.member.clone(), &blank);
+        text
+    }
+}
+
+/// Fields and methods of the class the target of the access evaluates to
+pub fn complete(
+    analysis: &Analysis,
+    index: &Index,
+    file: &str,
+    access: &MemberAccess,
+) -> Vec<CompletionItem> {
+    let mut finder = ExpressionFinder {
+        file,
+        range: access.target.clone(),
+        id: None,
+    };
+    finder.visit_program(&analysis.ast.program);
+
+    let class = finder
+        .id
+        .and_then(|id| analysis.types.ty(id).binding_name());
+    let class = match class {
+        Some(class) => class,
+        None => return vec![],
+    };
+
+    index
+        .members(class.as_ref().into())
+        .into_iter()
+        .map(|member| CompletionItem {
+            label: member.name.to_string(),
+            kind: match member.ty {
+                Type::Function(_) => CompletionItemKind::Method,
+                _ => CompletionItemKind::Field,
+            },
+            detail: member.detail.clone(),
+        })
+        .collect()
+}
+
+/// Finds the outermost expression with the given offsets
+struct ExpressionFinder<'a> {
+    file: &'a str,
+    range: Range<usize>,
+    id: Option<NodeId>,
+}
+
+impl<'a> Visitor for ExpressionFinder<'a> {
+    fn visit_single_expression(&mut self, node: &SingleExpression) {
+        if self.id.is_some() {
+            return;
+        }
+        let span = node.span();
+        if span.file == self.file && span.start == self.range.start && span.end == self.range.end {
+            self.id = Some(node.id());
+            return;
+        }
+        walk_single_expression(self, node);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_member_access_is_found_before_the_cursor() {
+        let text = "let a = this.point.x;";
+        let offset = text.find('x').unwrap();
+        let access = MemberAccess::at(text, offset).unwrap();
+        assert_eq!(&text[access.target.clone()], "this.point");
+        assert_eq!(&text[access.member.clone()], ".x");
+        assert_eq!(access.without_member(text), "let a = this.point  ;");
+    }
+
+    #[test]
+    fn test_member_access_needs_a_dot() {
+        assert_eq!(MemberAccess::at("let a = point", 13), None);
+        assert_eq!(MemberAccess::at("let a = 1.", 10), None);
+    }
+}
@@ -0,0 +1,1097 @@
+//! Language server for editors that speak the Language Server Protocol.
+//! Messages are JSON-RPC framed by a `Content-Length` header. The program
+//! is checked through the query database as documents change so only
+//! what the edit affects is parsed again.
+
+mod completion;
+mod protocol;
+mod symbols;
+
+pub use protocol::{read_message, write_message};
+
+use completion::MemberAccess;
+use jswt_common::Span;
+use jswt_errors::{parser_diagnostic, semantic_diagnostics, tokenizer_diagnostic, Level};
+use jswt_query::{item_source_name, normalize, statements_source_name, Analysis, Database, Index};
+use jswt_refactor::{extract_function, inline_variable, rename, RefactorError};
+use jswt_tokenizer::TokenizerError;
+use protocol::*;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REQUEST_FAILED: i64 = -32803;
+
+#[derive(Debug, Serialize)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+impl ResponseError {
+    fn new<T: Into<String>>(code: i64, message: T) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_params() -> Self {
+        Self::new(INVALID_PARAMS, "Invalid params")
+    }
+
+    fn refactor(error: RefactorError) -> Self {
+        Self::new(REQUEST_FAILED, error.to_string())
+    }
+}
+
+pub struct Server {
+    db: Database,
+    /// Paths of the open documents by their uri
+    documents: HashMap<String, PathBuf>,
+    /// Runtime every document is checked along with
+    runtime: Option<PathBuf>,
+    shutdown: bool,
+    exit: bool,
+}
+
+impl Server {
+    pub fn new(runtime: Option<PathBuf>) -> Self {
+        Self {
+            db: Database::new(),
+            documents: HashMap::new(),
+            runtime: runtime.map(|runtime| normalize(&runtime)),
+            shutdown: false,
+            exit: false,
+        }
+    }
+
+    /// True once the client asked the server to exit
+    pub fn exited(&self) -> bool {
+        self.exit
+    }
+
+    /// Handles a message from the client and returns the messages to send back,
+    /// the response to a request followed by any notifications
+    pub fn handle(&mut self, message: Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str);
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        match (method, message.get("id")) {
+            (Some(method), Some(id)) => {
+                let response = match self.request(method, &params) {
+                    Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                    Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+                };
+                vec![response]
+            }
+            (Some(method), None) => self.notification(method, &params),
+            // Responses to requests the server never sends
+            (None, _) => vec![],
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, ResponseError> {
+        if self.shutdown {
+            return Err(ResponseError::new(
+                INVALID_REQUEST,
+                "Server is shutting down",
+            ));
+        }
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    // Documents are synced by sending their full text
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "completionProvider": {"triggerCharacters": ["."]},
+                    "documentSymbolProvider": true,
+                    "renameProvider": true,
+                    "codeActionProvider": {
+                        "codeActionKinds": ["refactor.extract", "refactor.inline"],
+                    },
+                },
+                "serverInfo": {"name": "jswt"},
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => {
+                let (uri, position) = text_document_position(params)?;
+                Ok(json!(self.definition(&uri, position)))
+            }
+            "textDocument/references" => {
+                let (uri, position) = text_document_position(params)?;
+                let include_declaration = params
+                    .pointer("/context/includeDeclaration")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true);
+                Ok(json!(self.references(&uri, position, include_declaration)))
+            }
+            "textDocument/hover" => {
+                let (uri, position) = text_document_position(params)?;
+                Ok(json!(self.hover(&uri, position)))
+            }
+            "textDocument/completion" => {
+                let (uri, position) = text_document_position(params)?;
+                Ok(json!(self.completion(&uri, position)))
+            }
+            "textDocument/documentSymbol" => {
+                let uri = text_document_uri(params)?;
+                Ok(json!(self.document_symbols(&uri)))
+            }
+            "textDocument/rename" => {
+                let (uri, position) = text_document_position(params)?;
+                let name = params
+                    .get("newName")
+                    .and_then(Value::as_str)
+                    .ok_or_else(ResponseError::invalid_params)?;
+                Ok(json!(self.rename(&uri, position, name)?))
+            }
+            "textDocument/codeAction" => {
+                let uri = text_document_uri(params)?;
+                let range = params
+                    .get("range")
+                    .and_then(Range::from_value)
+                    .ok_or_else(ResponseError::invalid_params)?;
+                Ok(json!(self.code_actions(&uri, range)))
+            }
+            _ => Err(ResponseError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method '{}'", method),
+            )),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        match method {
+            "exit" => {
+                self.exit = true;
+                vec![]
+            }
+            "textDocument/didOpen" => {
+                let document = params.get("textDocument");
+                let uri = document
+                    .and_then(|it| it.get("uri"))
+                    .and_then(Value::as_str);
+                let text = document
+                    .and_then(|it| it.get("text"))
+                    .and_then(Value::as_str);
+                match (uri, text) {
+                    (Some(uri), Some(text)) => self.open(uri, text.to_owned()),
+                    _ => vec![],
+                }
+            }
+            "textDocument/didChange" => {
+                let uri = params.pointer("/textDocument/uri").and_then(Value::as_str);
+                // Full sync sends the whole text as the last change
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Value::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Value::as_str);
+                match (uri, text) {
+                    (Some(uri), Some(text)) => self.open(uri, text.to_owned()),
+                    _ => vec![],
+                }
+            }
+            "textDocument/didClose" => {
+                let uri = params.pointer("/textDocument/uri").and_then(Value::as_str);
+                match uri {
+                    Some(uri) => self.close(uri),
+                    None => vec![],
+                }
+            }
+            // Other notifications don't need anything from the server
+            _ => vec![],
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: String) -> Vec<Value> {
+        let path = match uri_to_path(uri) {
+            Some(path) => normalize(&path),
+            None => return vec![],
+        };
+        self.db.set_source_text(&path, text);
+        self.documents.insert(uri.to_owned(), path.clone());
+        vec![self.publish_diagnostics(uri, &path)]
+    }
+
+    fn close(&mut self, uri: &str) -> Vec<Value> {
+        let path = match self.documents.remove(uri) {
+            Some(path) => path,
+            None => return vec![],
+        };
+        // Unsaved edits are gone once the document is closed
+        let text = std::fs::read_to_string(&path).unwrap_or_default();
+        self.db.set_source_text(&path, text);
+        vec![notification(
+            "textDocument/publishDiagnostics",
+            json!({"uri": uri, "diagnostics": []}),
+        )]
+    }
+
+    /// Makes the document the root of the program
+    fn set_root(&mut self, path: &Path) {
+        let mut roots = vec![path.to_path_buf()];
+        roots.extend(self.runtime.clone());
+        self.db.set_roots(roots);
+    }
+
+    /// Checks the program the document is the root of
+    fn analyze(&mut self, path: &Path) -> (Arc<Analysis>, Arc<Index>) {
+        self.set_root(path);
+        (self.db.program(), self.db.index())
+    }
+
+    /// Path of an open document along with its byte offset at the position
+    fn document(&mut self, uri: &str, position: Position) -> Option<(PathBuf, usize)> {
+        let path = self.documents.get(uri)?.clone();
+        let offset = position.offset(&self.db.source_text(&path));
+        Some((path, offset))
+    }
+
+    fn location(&mut self, span: &Span) -> Location {
+        let path = Path::new(span.file.as_str());
+        let text = self.db.source_text(path);
+        Location {
+            uri: path_to_uri(path),
+            range: Range::from_offsets(&text, span.start, span.end),
+        }
+    }
+
+    fn definition(&mut self, uri: &str, position: Position) -> Option<Location> {
+        let (path, offset) = self.document(uri, position)?;
+        let (_, index) = self.analyze(&path);
+        let occurrence = index.occurrence_at(path.to_str()?, offset)?;
+        let definition = index.definitions.get(&occurrence.target)?;
+        Some(self.location(&definition.span))
+    }
+
+    fn references(
+        &mut self,
+        uri: &str,
+        position: Position,
+        include_declaration: bool,
+    ) -> Vec<Location> {
+        let (path, offset) = match self.document(uri, position) {
+            Some(document) => document,
+            None => return vec![],
+        };
+        let (_, index) = self.analyze(&path);
+        let target = match index.occurrence_at(path.to_str().unwrap(), offset) {
+            Some(occurrence) => occurrence.target.clone(),
+            None => return vec![],
+        };
+        let declaration = index.definitions.get(&target).map(|it| &it.span);
+        index
+            .references(&target)
+            .filter(|span| include_declaration || Some(*span) != declaration)
+            .map(|span| self.location(span))
+            .collect()
+    }
+
+    fn hover(&mut self, uri: &str, position: Position) -> Option<Hover> {
+        let (path, offset) = self.document(uri, position)?;
+        let (_, index) = self.analyze(&path);
+        let occurrence = index.occurrence_at(path.to_str()?, offset)?;
+        let definition = index.definitions.get(&occurrence.target)?;
+        Some(Hover {
+            contents: MarkupContent::code(&definition.detail),
+            range: self.location(&occurrence.span).range,
+        })
+    }
+
+    fn completion(&mut self, uri: &str, position: Position) -> Vec<CompletionItem> {
+        let (path, offset) = match self.document(uri, position) {
+            Some(document) => document,
+            None => return vec![],
+        };
+        let text = self.db.source_text(&path);
+        let access = match MemberAccess::at(&text, offset) {
+            Some(access) => access,
+            None => return vec![],
+        };
+
+        // The access is incomplete while it's being typed so the program
+        // is checked without it. The text is restored afterwards.
+        self.db.set_source_text(&path, access.without_member(&text));
+        let (analysis, index) = self.analyze(&path);
+        let items = completion::complete(&analysis, &index, path.to_str().unwrap(), &access);
+        self.db.set_source_text(&path, text.to_string());
+        items
+    }
+
+    fn document_symbols(&mut self, uri: &str) -> Vec<DocumentSymbol> {
+        let path = match self.documents.get(uri) {
+            Some(path) => path.clone(),
+            None => return vec![],
+        };
+        let text = self.db.source_text(&path);
+        let parsed = self.db.parse(&path);
+        symbols::document_symbols(&text, &parsed.file)
+    }
+
+    fn rename(
+        &mut self,
+        uri: &str,
+        position: Position,
+        name: &str,
+    ) -> Result<WorkspaceEdit, ResponseError> {
+        let (path, offset) = self
+            .document(uri, position)
+            .ok_or_else(ResponseError::invalid_params)?;
+        self.set_root(&path);
+        let edits = rename(&mut self.db, &path, offset, name).map_err(ResponseError::refactor)?;
+        Ok(self.workspace_edit(&edits))
+    }
+
+    /// Refactorings that can be applied to the range. Ones that
+    /// would fail aren't offered.
+    fn code_actions(&mut self, uri: &str, range: Range) -> Vec<CodeAction> {
+        let path = match self.documents.get(uri) {
+            Some(path) => path.clone(),
+            None => return vec![],
+        };
+        let text = self.db.source_text(&path);
+        let start = range.start.offset(&text);
+        let end = range.end.offset(&text);
+        let (_, index) = self.analyze(&path);
+
+        let mut actions = vec![];
+        if let Ok(edits) = inline_variable(&mut self.db, &path, start) {
+            actions.push(CodeAction {
+                title: "Inline variable".to_owned(),
+                kind: "refactor.inline",
+                edit: self.workspace_edit(&edits),
+            });
+        }
+        if start < end {
+            // The function gets a name that isn't taken
+            // which can be renamed once it's extracted
+            let name = (1..)
+                .map(|n| match n {
+                    1 => "extracted".to_owned(),
+                    n => format!("extracted{}", n),
+                })
+                .find(|name| {
+                    !index
+                        .definitions
+                        .values()
+                        .any(|definition| definition.name == name.as_str())
+                })
+                .unwrap();
+            if let Ok(edits) = extract_function(&mut self.db, &path, start..end, &name) {
+                actions.push(CodeAction {
+                    title: "Extract function".to_owned(),
+                    kind: "refactor.extract",
+                    edit: self.workspace_edit(&edits),
+                });
+            }
+        }
+        actions
+    }
+
+    fn workspace_edit(&mut self, edits: &[jswt_refactor::TextEdit]) -> WorkspaceEdit {
+        let mut changes: BTreeMap<String, Vec<TextEdit>> = BTreeMap::new();
+        for edit in edits {
+            let text = self.db.source_text(&edit.path);
+            changes
+                .entry(path_to_uri(&edit.path))
+                .or_default()
+                .push(TextEdit {
+                    range: Range::from_offsets(&text, edit.range.start, edit.range.end),
+                    new_text: edit.text.clone(),
+                });
+        }
+        WorkspaceEdit { changes }
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str, path: &Path) -> Value {
+        self.set_root(path);
+        let parsed = self.db.parse(path);
+        let path = normalize(path);
+        let file = path.to_str().unwrap();
+
+        let mut messages = vec![];
+        for error in &parsed.tokenizer_errors {
+            // Running out of input is reported by the parser as well
+            if let TokenizerError::UnreconizedToken { .. } = error {
+                messages.push(vec![tokenizer_diagnostic(error)]);
+            }
+        }
+        for error in &parsed.parse_errors {
+            messages.push(vec![parser_diagnostic(error)]);
+        }
+
+        // Items and the statements around them are checked and linted on
+        // their own so an edit only checks what it changes. Offsets of the
+        // errors of an item are relative to the item.
+        let mut sources = vec![(statements_source_name(&path), 0)];
+        let mut errors = vec![];
+        for name in self.db.items(&path).iter() {
+            let range = match self.db.item_range(&path, name) {
+                Some(range) => range,
+                None => continue,
+            };
+            sources.push((item_source_name(&path, name.clone()), range.start));
+            errors.extend(self.db.check_item(&path, name.clone()).iter().cloned());
+            errors.extend(
+                self.db
+                    .lint_item(&path, name.clone())
+                    .errors
+                    .iter()
+                    .cloned(),
+            );
+        }
+        errors.extend(self.db.check_statements(&path).iter().cloned());
+        errors.extend(self.db.lint_statements(&path).errors.iter().cloned());
+        errors.extend(self.db.lint_file(&path).iter().cloned());
+
+        for error in &errors {
+            let mut diagnostics = semantic_diagnostics(error);
+            // Notes on the declarations of other items can't be placed in the file
+            diagnostics.retain_mut(|diagnostic| {
+                let span = &diagnostic.span;
+                if span.file == file {
+                    return true;
+                }
+                let start = sources
+                    .iter()
+                    .find(|(source, _)| span.file == source.as_str())
+                    .map(|(_, start)| *start);
+                match start {
+                    Some(start) => {
+                        let (start, end) = (start + span.start, start + span.end);
+                        diagnostic.span = Span::new(file, span.module.clone(), start, end);
+                        true
+                    }
+                    None => false,
+                }
+            });
+            messages.push(diagnostics);
+        }
+
+        let mut diagnostics = vec![];
+        for mut messages in messages.into_iter().filter(|messages| !messages.is_empty()) {
+            let message = messages.remove(0);
+            if message.span.file != file {
+                continue;
+            }
+            let mut text = message.message.to_string();
+            if let Some(hint) = &message.hint {
+                text = format!("{}\n{}", text, hint);
+            }
+            let related_information = messages
+                .iter()
+                .map(|note| DiagnosticRelatedInformation {
+                    location: self.location(&note.span),
+                    message: note.message.to_string(),
+                })
+                .collect();
+            diagnostics.push(Diagnostic {
+                range: self.location(&message.span).range,
+                severity: match message.level {
+                    Level::Error => 1,
+                    Level::Warning => 2,
+                    Level::Note => 3,
+                },
+                source: "jswt",
+                message: text,
+                related_information,
+            });
+        }
+
+        notification(
+            "textDocument/publishDiagnostics",
+            json!({"uri": uri, "diagnostics": diagnostics}),
+        )
+    }
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({"jsonrpc": "2.0", "method": method, "params": params})
+}
+
+fn text_document_uri(params: &Value) -> Result<String, ResponseError> {
+    params
+        .pointer("/textDocument/uri")
+        .and_then(Value::as_str)
+        .map(str::to_owned)
+        .ok_or_else(ResponseError::invalid_params)
+}
+
+fn text_document_position(params: &Value) -> Result<(String, Position), ResponseError> {
+    let uri = text_document_uri(params)?;
+    let position = params
+        .get("position")
+        .and_then(Position::from_value)
+        .ok_or_else(ResponseError::invalid_params)?;
+    Ok((uri, position))
+}
+
+/// Serves the client until it asks the server to exit or closes the input
+pub fn run<R: BufRead, W: Write>(
+    mut input: R,
+    mut output: W,
+    runtime: Option<PathBuf>,
+) -> io::Result<()> {
+    let mut server = Server::new(runtime);
+    while !server.exited() {
+        let message = match read_message(&mut input) {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                let error = ResponseError::new(PARSE_ERROR, error.to_string());
+                let response = json!({"jsonrpc": "2.0", "id": null, "error": error});
+                write_message(&mut output, &response)?;
+                continue;
+            }
+            Err(error) => return Err(error),
+        };
+        for message in server.handle(message) {
+            write_message(&mut output, &message)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jswt_query::QueryKey;
+    use std::io::Cursor;
+
+    const SOURCE: &str = r#"class Point {
+    x: i32;
+    y: i32;
+
+    constructor(x: i32, y: i32) {
+        this.x = x;
+        this.y = y;
+    }
+
+    sum(): i32 {
+        return this.x + this.y;
+    }
+}
+
+function add(a: i32, b: i32): i32 {
+    return a + b;
+}
+
+export function main(): i32 {
+    let point: Point = new Point(1, 2);
+    return add(point.x, point.sum());
+}
+"#;
+
+    /// Client talking to a server in the same process
+    struct Client {
+        server: Server,
+        uri: String,
+        next_id: u64,
+    }
+
+    impl Client {
+        /// Opens a document next to the runtime
+        fn open(name: &str, source: &str) -> (Client, Vec<Value>) {
+            let root = normalize(&Path::new(env!("CARGO_MANIFEST_DIR")).join(".."));
+            let runtime = root.join("runtime").join("rt.jswt");
+            let mut client = Client {
+                server: Server::new(Some(runtime)),
+                uri: path_to_uri(&root.join(name).join("main.jswt")),
+                next_id: 0,
+            };
+            client.request("initialize", json!({"capabilities": {}}));
+            let document =
+                json!({"uri": client.uri, "languageId": "jswt", "version": 1, "text": source});
+            let messages =
+                client.notify("textDocument/didOpen", json!({ "textDocument": document }));
+            (client, messages)
+        }
+
+        fn request(&mut self, method: &str, params: Value) -> Value {
+            self.next_id += 1;
+            let request =
+                json!({"jsonrpc": "2.0", "id": self.next_id, "method": method, "params": params});
+            let mut messages = self.server.handle(request);
+            assert_eq!(messages.len(), 1);
+            let response = messages.remove(0);
+            assert_eq!(response["id"], json!(self.next_id));
+            response
+        }
+
+        fn notify(&mut self, method: &str, params: Value) -> Vec<Value> {
+            self.server
+                .handle(json!({"jsonrpc": "2.0", "method": method, "params": params}))
+        }
+
+        fn change(&mut self, source: &str) -> Vec<Value> {
+            let params = json!({
+                "textDocument": {"uri": self.uri, "version": 2},
+                "contentChanges": [{"text": source}],
+            });
+            self.notify("textDocument/didChange", params)
+        }
+
+        /// Sends a request about the position of the nth occurrence of the needle
+        fn request_at(&mut self, method: &str, source: &str, needle: &str, n: usize) -> Value {
+            let offset = source.match_indices(needle).nth(n).unwrap().0;
+            let params = json!({
+                "textDocument": {"uri": self.uri},
+                "position": Position::from_offset(source, offset),
+                "context": {"includeDeclaration": true},
+            });
+            self.request(method, params)["result"].clone()
+        }
+    }
+
+    /// Text the range of a location covers in the source
+    fn text<'a>(source: &'a str, range: &Value) -> &'a str {
+        let position = |value: &Value| Position::from_value(value).unwrap().offset(source);
+        &source[position(&range["start"])..position(&range["end"])]
+    }
+
+    fn diagnostics(messages: &[Value]) -> &Vec<Value> {
+        assert_eq!(messages[0]["method"], "textDocument/publishDiagnostics");
+        messages[0]["params"]["diagnostics"].as_array().unwrap()
+    }
+
+    #[test]
+    fn test_initialize_advertises_capabilities() {
+        let mut server = Server::new(None);
+        let request = json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}});
+        let response = server.handle(request).remove(0);
+        let capabilities = &response["result"]["capabilities"];
+        assert_eq!(capabilities["textDocumentSync"], 1);
+        assert_eq!(
+            capabilities["completionProvider"]["triggerCharacters"],
+            json!(["."])
+        );
+        for provider in [
+            "definitionProvider",
+            "referencesProvider",
+            "hoverProvider",
+            "documentSymbolProvider",
+            "renameProvider",
+        ] {
+            assert_eq!(capabilities[provider], true);
+        }
+    }
+
+    #[test]
+    fn test_unknown_requests_are_errors() {
+        let (mut client, _) = Client::open("test_unknown_requests_are_errors", SOURCE);
+        let response = client.request("workspace/symbol", json!({}));
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_diagnostics_are_published_as_documents_change() {
+        let (mut client, messages) =
+            Client::open("test_diagnostics_are_published_as_documents_change", SOURCE);
+        assert!(diagnostics(&messages).is_empty());
+
+        let source = SOURCE.replace("return a + b;", "return a + c;");
+        let messages = client.change(&source);
+        let published = diagnostics(&messages);
+        assert_eq!(published.len(), 2);
+        assert_eq!(published[0]["severity"], 1);
+        assert_eq!(published[0]["source"], "jswt");
+        assert_eq!(
+            published[0]["message"],
+            "Variable 'c' was not defined in this scope"
+        );
+        assert_eq!(text(&source, &published[0]["range"]), "c");
+        // Lints are warnings with their hint appended
+        assert_eq!(published[1]["severity"], 2);
+        assert_eq!(
+            published[1]["message"],
+            "Parameter 'b' is never used [unused-parameter]\nSilence this with @allow(\"unused-parameter\")"
+        );
+
+        let messages = client.change(SOURCE);
+        assert!(diagnostics(&messages).is_empty());
+    }
+
+    #[test]
+    fn test_edits_only_check_the_items_they_change() {
+        let (mut client, _) = Client::open("test_edits_only_check_the_items_they_change", SOURCE);
+        client.server.db.take_executed();
+
+        let source = SOURCE.replace("return a + b;", "return a + c;");
+        let messages = client.change(&source);
+        assert_eq!(text(&source, &diagnostics(&messages)[0]["range"]), "c");
+
+        let executed = client.server.db.take_executed();
+        let checked: Vec<_> = executed
+            .iter()
+            .filter_map(|key| match key {
+                QueryKey::CheckItem(_, name) => Some(name.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(checked, vec!["add"]);
+        // Neither is the program as a whole
+        assert!(!executed.contains(&QueryKey::Program));
+        assert!(!executed.contains(&QueryKey::Index));
+    }
+
+    #[test]
+    fn test_program_lints_and_statements_are_published() {
+        let source = format!(
+            "{}\nfunction unused(): i32 {{\n    return 1;\n}}\n\nlet z: i32 = missing;\n",
+            SOURCE
+        );
+        let (mut client, messages) =
+            Client::open("test_program_lints_and_statements_are_published", &source);
+        let published = diagnostics(&messages);
+        let texts: Vec<_> = published
+            .iter()
+            .map(|diagnostic| text(&source, &diagnostic["range"]))
+            .collect();
+        assert_eq!(texts, vec!["missing", "unused"]);
+        assert!(published[1]["message"]
+            .as_str()
+            .unwrap()
+            .contains("[unused-function]"));
+
+        // Using the function from another item clears its lint
+        let source = source.replace("return a + b;", "return a + b + unused();");
+        let messages = client.change(&source);
+        let published = diagnostics(&messages);
+        assert_eq!(published.len(), 1);
+        assert_eq!(text(&source, &published[0]["range"]), "missing");
+        assert!(!client
+            .server
+            .db
+            .take_executed()
+            .contains(&QueryKey::Program));
+    }
+
+    #[test]
+    fn test_syntax_errors_are_published() {
+        let source = SOURCE.replace("return a + b;", "return a + ;");
+        let (_, messages) = Client::open("test_syntax_errors_are_published", &source);
+        let diagnostics = diagnostics(&messages);
+        assert!(!diagnostics.is_empty());
+        assert_eq!(diagnostics[0]["severity"], 1);
+    }
+
+    #[test]
+    fn test_closing_a_document_clears_its_diagnostics() {
+        let source = SOURCE.replace("return a + b;", "return a + c;");
+        let (mut client, _) =
+            Client::open("test_closing_a_document_clears_its_diagnostics", &source);
+        let params = json!({"textDocument": {"uri": client.uri}});
+        let messages = client.notify("textDocument/didClose", params);
+        assert!(diagnostics(&messages).is_empty());
+    }
+
+    #[test]
+    fn test_definition_of_a_parameter() {
+        let (mut client, _) = Client::open("test_definition_of_a_parameter", SOURCE);
+        let location = client.request_at("textDocument/definition", SOURCE, "b;", 0);
+        assert_eq!(location["uri"], client.uri);
+        // The second b in the source is the parameter
+        let offset = SOURCE.match_indices("b: i32").next().unwrap().0;
+        assert_eq!(
+            location["range"]["start"],
+            json!(Position::from_offset(SOURCE, offset))
+        );
+        assert_eq!(text(SOURCE, &location["range"]), "b");
+    }
+
+    #[test]
+    fn test_definition_of_a_member() {
+        let (mut client, _) = Client::open("test_definition_of_a_member", SOURCE);
+        let location = client.request_at("textDocument/definition", SOURCE, "sum()", 1);
+        let offset = SOURCE.find("sum()").unwrap();
+        assert_eq!(
+            location["range"]["start"],
+            json!(Position::from_offset(SOURCE, offset))
+        );
+    }
+
+    #[test]
+    fn test_definition_of_a_class() {
+        let (mut client, _) = Client::open("test_definition_of_a_class", SOURCE);
+        let location = client.request_at("textDocument/definition", SOURCE, "Point(1", 0);
+        let offset = SOURCE.find("Point").unwrap();
+        assert_eq!(
+            location["range"]["start"],
+            json!(Position::from_offset(SOURCE, offset))
+        );
+    }
+
+    #[test]
+    fn test_references_to_a_field() {
+        let (mut client, _) = Client::open("test_references_to_a_field", SOURCE);
+        let locations = client.request_at("textDocument/references", SOURCE, "x: i32;", 0);
+        let starts: Vec<_> = locations
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|location| Position::from_value(&location["range"]["start"]).unwrap())
+            .collect();
+        // The declaration, the assignment and reads in sum and main
+        let expected = [
+            SOURCE.find("x: i32;").unwrap(),
+            SOURCE.find("x = x").unwrap(),
+            SOURCE.find("x + this").unwrap(),
+            SOURCE.find("x, point").unwrap(),
+        ];
+        let expected: Vec<_> = expected
+            .iter()
+            .map(|offset| Position::from_offset(SOURCE, *offset))
+            .collect();
+        assert_eq!(starts, expected);
+    }
+
+    #[test]
+    fn test_references_to_a_function() {
+        let (mut client, _) = Client::open("test_references_to_a_function", SOURCE);
+        let locations = client.request_at("textDocument/references", SOURCE, "add", 1);
+        let texts: Vec<_> = locations
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|location| text(SOURCE, &location["range"]))
+            .collect();
+        assert_eq!(texts, vec!["add", "add"]);
+    }
+
+    #[test]
+    fn test_hover_shows_the_declaration() {
+        let (mut client, _) = Client::open("test_hover_shows_the_declaration", SOURCE);
+        let hover = client.request_at("textDocument/hover", SOURCE, "add(point", 0);
+        assert_eq!(hover["contents"]["kind"], "markdown");
+        assert_eq!(
+            hover["contents"]["value"],
+            "```jswt\nfunction add(a: i32, b: i32): i32\n```"
+        );
+
+        let hover = client.request_at("textDocument/hover", SOURCE, "point.x", 0);
+        assert_eq!(hover["contents"]["value"], "```jswt\nlet point: Point\n```");
+        assert_eq!(text(SOURCE, &hover["range"]), "point");
+    }
+
+    #[test]
+    fn test_completion_of_class_members() {
+        // The member is still being typed
+        let source = SOURCE.replace("return add(point.x, point.sum());", "point.\n    return 0;");
+        let (mut client, _) = Client::open("test_completion_of_class_members", &source);
+        let offset = source.find("point.\n").unwrap() + "point.".len();
+        let params = json!({
+            "textDocument": {"uri": client.uri},
+            "position": Position::from_offset(&source, offset),
+        });
+        let items = client.request("textDocument/completion", params)["result"].clone();
+        assert_eq!(
+            items,
+            json!([
+                {"label": "x", "kind": 5, "detail": "x: i32"},
+                {"label": "y", "kind": 5, "detail": "y: i32"},
+                {"label": "sum", "kind": 2, "detail": "sum(): i32"},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_completion_of_this() {
+        let source = SOURCE.replace("return this.x + this.y;", "return this.s");
+        let (mut client, _) = Client::open("test_completion_of_this", &source);
+        let offset = source.find("this.s").unwrap() + "this.s".len();
+        let params = json!({
+            "textDocument": {"uri": client.uri},
+            "position": Position::from_offset(&source, offset),
+        });
+        let items = client.request("textDocument/completion", params)["result"].clone();
+        let labels: Vec<_> = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["x", "y", "sum"]);
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let (mut client, _) = Client::open("test_document_symbols", SOURCE);
+        let params = json!({"textDocument": {"uri": client.uri}});
+        let symbols = client.request("textDocument/documentSymbol", params)["result"].clone();
+        let outline: Vec<_> = symbols
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|symbol| {
+                let children: Vec<_> = symbol["children"]
+                    .as_array()
+                    .map(|children| {
+                        children
+                            .iter()
+                            .map(|child| (child["name"].clone(), child["kind"].clone()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (symbol["name"].clone(), symbol["kind"].clone(), children)
+            })
+            .collect();
+        assert_eq!(
+            outline,
+            vec![
+                (
+                    json!("Point"),
+                    json!(5),
+                    vec![
+                        (json!("x"), json!(8)),
+                        (json!("y"), json!(8)),
+                        (json!("constructor"), json!(9)),
+                        (json!("sum"), json!(6)),
+                    ]
+                ),
+                (json!("add"), json!(12), vec![]),
+                (json!("main"), json!(12), vec![]),
+            ]
+        );
+        assert_eq!(text(SOURCE, &symbols[1]["selectionRange"]), "add");
+        assert_eq!(symbols[1]["detail"], "(a: i32, b: i32): i32");
+    }
+
+    /// Applies the edits of a workspace edit to the document
+    fn apply(source: &str, edit: &Value, uri: &str) -> String {
+        let mut edits: Vec<_> = edit["changes"][uri]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edit| {
+                let position = |value: &Value| Position::from_value(value).unwrap().offset(source);
+                let range = &edit["range"];
+                let text = edit["newText"].as_str().unwrap();
+                (position(&range["start"]), position(&range["end"]), text)
+            })
+            .collect();
+        edits.sort();
+        let mut text = source.to_owned();
+        for (start, end, new_text) in edits.into_iter().rev() {
+            text.replace_range(start..end, new_text);
+        }
+        text
+    }
+
+    #[test]
+    fn test_rename_edits_every_reference() {
+        let (mut client, _) = Client::open("test_rename_edits_every_reference", SOURCE);
+        let offset = SOURCE.find("add(point").unwrap();
+        let params = json!({
+            "textDocument": {"uri": client.uri},
+            "position": Position::from_offset(SOURCE, offset),
+            "newName": "plus",
+        });
+        let edit = client.request("textDocument/rename", params)["result"].clone();
+        let uri = client.uri.clone();
+        assert_eq!(apply(SOURCE, &edit, &uri), SOURCE.replace("add(", "plus("));
+    }
+
+    #[test]
+    fn test_rename_to_an_invalid_name_fails() {
+        let (mut client, _) = Client::open("test_rename_to_an_invalid_name_fails", SOURCE);
+        let offset = SOURCE.find("add(point").unwrap();
+        let params = json!({
+            "textDocument": {"uri": client.uri},
+            "position": Position::from_offset(SOURCE, offset),
+            "newName": "let",
+        });
+        let response = client.request("textDocument/rename", params);
+        assert_eq!(response["error"]["code"], REQUEST_FAILED);
+        assert_eq!(
+            response["error"]["message"],
+            "'let' is not a valid identifier"
+        );
+    }
+
+    #[test]
+    fn test_code_actions_extract_and_inline() {
+        let source = SOURCE.replace(
+            "    return add(point.x, point.sum());",
+            "    let sum = point.sum();\n    println(sum);\n    return 0;",
+        );
+        let (mut client, _) = Client::open("test_code_actions_extract_and_inline", &source);
+        let uri = client.uri.clone();
+
+        let mut code_actions = |start: usize, end: usize| {
+            let params = json!({
+                "textDocument": {"uri": uri},
+                "range": Range::from_offsets(&source, start, end),
+                "context": {"diagnostics": []},
+            });
+            client.request("textDocument/codeAction", params)["result"].clone()
+        };
+
+        let cursor = source.find("sum =").unwrap();
+        let actions = code_actions(cursor, cursor);
+        assert_eq!(actions.as_array().unwrap().len(), 1);
+        assert_eq!(actions[0]["title"], "Inline variable");
+        assert_eq!(actions[0]["kind"], "refactor.inline");
+        assert_eq!(
+            apply(&source, &actions[0]["edit"], &uri),
+            source.replace(
+                "    let sum = point.sum();\n    println(sum);",
+                "    println(point.sum());",
+            )
+        );
+
+        let start = source.find("let sum").unwrap();
+        let end = source.find("return 0").unwrap();
+        let actions = code_actions(start, end);
+        assert_eq!(actions.as_array().unwrap().len(), 1);
+        assert_eq!(actions[0]["title"], "Extract function");
+        assert_eq!(actions[0]["kind"], "refactor.extract");
+        let extracted = apply(&source, &actions[0]["edit"], &uri);
+        assert!(extracted.contains("    extracted(point);\n    return 0;"));
+        assert!(extracted.ends_with(
+            "\n\nfunction extracted(point: Point) {\n    let sum = point.sum();\n    println(sum);\n}\n"
+        ));
+    }
+
+    #[test]
+    fn test_run_serves_framed_messages_until_exit() {
+        let mut input = vec![];
+        for message in [
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "shutdown"}),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "shutdown"}),
+        ] {
+            write_message(&mut input, &message).unwrap();
+        }
+
+        let mut output = vec![];
+        run(Cursor::new(input), &mut output, None).unwrap();
+
+        let mut output = Cursor::new(output);
+        let mut responses = vec![];
+        while let Some(message) = read_message(&mut output).unwrap() {
+            responses.push(message);
+        }
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(
+            responses[1],
+            json!({"jsonrpc": "2.0", "id": 2, "result": null})
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+/// Reads a message framed by a `Content-Length` header.
+/// Returns `None` once the input is closed.
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "Missing Content-Length header")
+    })?;
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+    let message = serde_json::from_slice(&content)?;
+    Ok(Some(message))
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let content = serde_json::to_string(message)?;
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+/// Zero based line and UTF-16 offset into the line
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+impl Position {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        Some(Position {
+            line: value.get("line")?.as_u64()? as usize,
+            character: value.get("character")?.as_u64()? as usize,
+        })
+    }
+
+    /// Position of the byte offset in the text
+    pub fn from_offset(text: &str, offset: usize) -> Self {
+        let offset = offset.min(text.len());
+        let start = text[..offset].rfind('\n').map_or(0, |newline| newline + 1);
+        Position {
+            line: text[..start].matches('\n').count(),
+            character: text[start..offset].encode_utf16().count(),
+        }
+    }
+
+    /// Byte offset of the position in the text
+    pub fn offset(&self, text: &str) -> usize {
+        let mut start = 0;
+        for _ in 0..self.line {
+            match text[start..].find('\n') {
+                Some(newline) => start += newline + 1,
+                None => return text.len(),
+            }
+        }
+        let mut units = 0;
+        for (index, ch) in text[start..].char_indices() {
+            if units >= self.character || ch == '\n' {
+                return start + index;
+            }
+            units += ch.len_utf16();
+        }
+        text.len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Range {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        Some(Range {
+            start: Position::from_value(value.get("start")?)?,
+            end: Position::from_value(value.get("end")?)?,
+        })
+    }
+
+    pub fn from_offsets(text: &str, start: usize, end: usize) -> Self {
+        Range {
+            start: Position::from_offset(text, start),
+            end: Position::from_offset(text, end),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub range: Range,
+    /// 1 for errors, 2 for warnings and 3 for information
+    pub severity: u8,
+    pub source: &'static str,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<DiagnosticRelatedInformation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticRelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Hover {
+    pub contents: MarkupContent,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarkupContent {
+    pub kind: &'static str,
+    pub value: String,
+}
+
+impl MarkupContent {
+    /// Markdown code block of jswt source
+    pub fn code(code: &str) -> Self {
+        MarkupContent {
+            kind: "markdown",
+            value: format!("```jswt\n{}\n```", code),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(into = "u8")]
+pub enum CompletionItemKind {
+    Method = 2,
+    Field = 5,
+}
+
+impl From<CompletionItemKind> for u8 {
+    fn from(kind: CompletionItemKind) -> Self {
+        kind as u8
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: CompletionItemKind,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(into = "u8")]
+pub enum SymbolKind {
+    Class = 5,
+    Method = 6,
+    Field = 8,
+    Constructor = 9,
+    Function = 12,
+    Variable = 13,
+}
+
+impl From<SymbolKind> for u8 {
+    fn from(kind: SymbolKind) -> Self {
+        kind as u8
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSymbol {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub kind: SymbolKind,
+    /// Range of the whole declaration
+    pub range: Range,
+    /// Range of the name of the declaration
+    pub selection_range: Range,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<DocumentSymbol>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+/// Edits to make to documents by their uri
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkspaceEdit {
+    pub changes: BTreeMap<String, Vec<TextEdit>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CodeAction {
+    pub title: String,
+    /// Hierarchical kind such as `refactor.extract`
+    pub kind: &'static str,
+    pub edit: WorkspaceEdit,
+}
+
+/// Path of a `file://` uri
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut chars = path.bytes();
+    while let Some(byte) = chars.next() {
+        if byte == b'%' {
+            let hex = [chars.next()?, chars.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri += &format!("%{:02X}", byte),
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_messages_are_framed_by_their_length() {
+        let message = json!({"jsonrpc": "2.0", "method": "initialized", "params": {}});
+        let mut buffer = vec![];
+        write_message(&mut buffer, &message).unwrap();
+        write_message(&mut buffer, &message).unwrap();
+
+        let mut input = Cursor::new(buffer);
+        assert_eq!(read_message(&mut input).unwrap(), Some(message.clone()));
+        assert_eq!(read_message(&mut input).unwrap(), Some(message));
+        assert_eq!(read_message(&mut input).unwrap(), None);
+    }
+
+    #[test]
+    fn test_positions_count_utf16_code_units() {
+        let text = "let a = \"😀\";\nlet b = 1;";
+        let offset = text.find(';').unwrap();
+        let position = Position::from_offset(text, offset);
+        assert_eq!(
+            position,
+            Position {
+                line: 0,
+                character: 12
+            }
+        );
+        assert_eq!(position.offset(text), offset);
+
+        let offset = text.find('b').unwrap();
+        let position = Position::from_offset(text, offset);
+        assert_eq!(
+            position,
+            Position {
+                line: 1,
+                character: 4
+            }
+        );
+        assert_eq!(position.offset(text), offset);
+    }
+
+    #[test]
+    fn test_uris_round_trip_through_paths() {
+        let path = Path::new("/home/user/my project/main.jswt");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///home/user/my%20project/main.jswt");
+        assert_eq!(uri_to_path(&uri).unwrap(), path);
+    }
+}
@@ -0,0 +1,102 @@
+use crate::protocol::{DocumentSymbol, Range, SymbolKind};
+use jswt_ast::{
+    AssignableElement, ClassElement, File, Identifier, SourceElement, StatementElement,
+};
+use jswt_common::{Span, Spannable};
+use jswt_query::signature;
+
+/// Outline of the declarations in a file
+pub fn document_symbols(text: &str, file: &File) -> Vec<DocumentSymbol> {
+    let outline = Outline { text };
+    file.source_elements
+        .source_elements
+        .iter()
+        .filter_map(|element| outline.element(element))
+        .collect()
+}
+
+struct Outline<'a> {
+    text: &'a str,
+}
+
+impl<'a> Outline<'a> {
+    fn element(&self, element: &SourceElement) -> Option<DocumentSymbol> {
+        match element {
+            SourceElement::FunctionDeclaration(function) => Some(self.symbol(
+                SymbolKind::Function,
+                &function.span,
+                &function.ident,
+                Some(signature(&function.params, &function.returns)),
+            )),
+            SourceElement::ClassDeclaration(class) => {
+                let mut symbol = self.symbol(SymbolKind::Class, &class.span, &class.ident, None);
+                symbol.children = class
+                    .body
+                    .class_elements
+                    .iter()
+                    .map(|element| self.class_element(element))
+                    .collect();
+                Some(symbol)
+            }
+            SourceElement::Statement(StatementElement::Variable(variable)) => {
+                let AssignableElement::Identifier(ident) = &variable.target;
+                let detail = variable
+                    .type_annotation
+                    .as_ref()
+                    .map(|annotation| annotation.ty.to_string());
+                Some(self.symbol(SymbolKind::Variable, &variable.span, ident, detail))
+            }
+            SourceElement::Statement(_) => None,
+        }
+    }
+
+    fn class_element(&self, element: &ClassElement) -> DocumentSymbol {
+        match element {
+            ClassElement::Constructor(constructor) => {
+                let span = constructor.span();
+                // Constructors are named by their keyword
+                let ident = Identifier::new(
+                    "constructor",
+                    Span::new(
+                        span.file.clone(),
+                        span.module.clone(),
+                        span.start,
+                        span.start + 11,
+                    ),
+                );
+                let detail = signature(&constructor.params, &None);
+                self.symbol(SymbolKind::Constructor, &span, &ident, Some(detail))
+            }
+            ClassElement::Field(field) => {
+                let detail = field.type_annotation.ty.to_string();
+                self.symbol(SymbolKind::Field, &field.span, &field.ident, Some(detail))
+            }
+            ClassElement::Method(method) => {
+                let detail = signature(&method.params, &method.returns);
+                self.symbol(
+                    SymbolKind::Method,
+                    &method.span,
+                    &method.ident,
+                    Some(detail),
+                )
+            }
+        }
+    }
+
+    fn symbol(
+        &self,
+        kind: SymbolKind,
+        span: &Span,
+        ident: &Identifier,
+        detail: Option<String>,
+    ) -> DocumentSymbol {
+        DocumentSymbol {
+            name: ident.value.to_string(),
+            detail,
+            kind,
+            range: Range::from_offsets(self.text, span.start, span.end),
+            selection_range: Range::from_offsets(self.text, ident.span.start, ident.span.end),
+            children: vec![],
+        }
+    }
+}
@@ -1,25 +1,52 @@
-use jswt_ast::{transform::TransformVisitor, *};
+mod operators;
+
+use jswt_ast::{mut_visit::*, *};
 use jswt_symbols::{BindingsTable, ScopedSymbolTable};
-use jswt_synthetic::ident_exp;
+use jswt_types::TypeTable;
+use operators::MirOperatorsLoweringContext;
 
 pub struct MirLoweringContext<'a> {
     bindings: &'a BindingsTable,
     symbols: &'a ScopedSymbolTable,
+    types: &'a TypeTable,
 }
 
 impl<'a> MirLoweringContext<'a> {
-    pub fn new(bindings: &'a BindingsTable, symbols: &'a ScopedSymbolTable) -> Self {
-        Self { bindings, symbols }
+    pub fn new(
+        bindings: &'a BindingsTable,
+        symbols: &'a ScopedSymbolTable,
+        types: &'a TypeTable,
+    ) -> Self {
+        Self {
+            bindings,
+            symbols,
+            types,
+        }
     }
 
-    pub fn lower(&mut self, ast: &Ast) -> Ast {
-        let program = self.visit_program(&ast.program);
-        Ast { program }
+    pub fn lower(&mut self, mut ast: Ast) -> Ast {
+        self.visit_program(&mut ast.program);
+
+        // Operators are lowered once `this` has been replaced
+        let mut operators = MirOperatorsLoweringContext::new(self.bindings, self.types);
+        operators.visit_program(&mut ast.program);
+        ast.assign_ids();
+        ast
     }
 }
 
-impl<'a> TransformVisitor for MirLoweringContext<'a> {
-    fn visit_this_expression(&mut self, _: &ThisExpression) -> SingleExpression {
-        ident_exp("this".into())
+impl<'a> MutVisitor for MirLoweringContext<'a> {
+    fn visit_single_expression(&mut self, node: &mut SingleExpression) {
+        match node {
+            // The receiver keeps the type of the class it is an instance of
+            SingleExpression::This(this) => {
+                *node = SingleExpression::Identifier(IdentifierExpression {
+                    id: this.id,
+                    span: this.span.clone(),
+                    ident: Identifier::new("this", this.span.clone()),
+                })
+            }
+            _ => walk_single_expression(self, node),
+        }
     }
 }
@@ -1,27 +1,66 @@
-use jswt_ast::{
-    transform::{walk_binary_expression, TransformVisitor},
-    BinaryExpression,
-};
+use jswt_ast::{mut_visit::*, BinaryExpression, FunctionDeclarationElement, SingleExpression};
+use jswt_common::Identifiable;
 use jswt_symbols::BindingsTable;
+use jswt_synthetic::function_call;
+use jswt_types::TypeTable;
 
-use crate::SymbolTable;
-
+/// Lowers binary operators into calls to the method overloading the
+/// operator on the class of the left operand, for example `a + b`
+/// becomes `Vector#add(a, b)`. Operators without an overload are left
+/// to be generated as instructions.
 pub struct MirOperatorsLoweringContext<'a> {
     bindings: &'a BindingsTable,
-    symbols: &'a SymbolTable,
+    types: &'a TypeTable,
+    // Global initializers must be constant so they aren't lowered into calls
+    in_function: bool,
 }
 
 impl<'a> MirOperatorsLoweringContext<'a> {
-    pub fn new(bindings: &'a BindingsTable, symbols: &'a SymbolTable) -> Self {
-        Self { bindings, symbols }
+    pub fn new(bindings: &'a BindingsTable, types: &'a TypeTable) -> Self {
+        Self {
+            bindings,
+            types,
+            in_function: false,
+        }
+    }
+
+    fn lower_binary_expression(&mut self, node: &mut BinaryExpression) -> Option<SingleExpression> {
+        let name = node.op.method_name().filter(|_| self.in_function)?;
+        let binding = self
+            .bindings
+            .lookup(&self.types.ty(node.left.id()).binding_name()?)?;
+        let method = binding.method(name)?;
+
+        let mut arguments = vec![node.left.take(), node.right.take()];
+        for argument in arguments.iter_mut() {
+            self.visit_single_expression(argument);
+        }
+        let function_name = format!("{}#{}", binding.name, method.name);
+        Some(function_call(function_name.into(), arguments).with_id(node.id))
     }
 }
 
-impl<'a> TransformVisitor for MirOperatorsLoweringContext<'a> {
-    fn visit_binary_expression(&mut self, node: &BinaryExpression) -> jswt_ast::SingleExpression {
-        println!("Binary expression: {:?}", node);
+impl<'a> MutVisitor for MirOperatorsLoweringContext<'a> {
+    fn visit_function_declaration(&mut self, node: &mut FunctionDeclarationElement) {
+        self.in_function = true;
+        walk_function_declaration(self, node);
+        self.in_function = false;
+    }
+
+    fn visit_single_expression(&mut self, node: &mut SingleExpression) {
+        let lowered = match node {
+            SingleExpression::Additive(exp)
+            | SingleExpression::Multiplicative(exp)
+            | SingleExpression::Equality(exp)
+            | SingleExpression::Relational(exp)
+            | SingleExpression::Bitwise(exp) => self.lower_binary_expression(exp),
+            _ => None,
+        };
 
-        walk_binary_expression(self, node)
+        match lowered {
+            Some(lowered) => *node = lowered,
+            None => walk_single_expression(self, node),
+        }
     }
 }
 
@@ -29,13 +68,13 @@ impl<'a> TransformVisitor for MirOperatorsLoweringContext<'a> {
 mod test {
 
     use jswt_assert::assert_debug_snapshot;
+    use jswt_hir_lowering::HirLoweringContext;
     use jswt_parser::Parser;
-    use jswt_semantics::GlobalResolver;
-    use jswt_symbols::SymbolTable;
+    use jswt_semantics::{GlobalSemanticResolver, LocalSemanticResolver, TypeChecker};
+    use jswt_symbols::{BindingsTable, ScopedSymbolTable};
     use jswt_tokenizer::Tokenizer;
 
-    use super::*;
-    use crate::HirLoweringContext;
+    use crate::MirLoweringContext;
 
     #[test]
     fn test_operator_lowering_lowers_plus_symbol_into_function_call() {
@@ -53,23 +92,33 @@ mod test {
             let a: i32 = 1;
             let b: i32 = 2;
             let x = a + b;
-            return 0;
+            let y = a * b;
+            return x;
         }
     ",
         );
 
         let ast = Parser::new(&mut tokenizer).parse();
 
-        let mut symbol_table = SymbolTable::default();
+        let mut symbol_table = ScopedSymbolTable::default();
         let mut bindings_table = BindingsTable::default();
-        let mut global_resolver = GlobalResolver::new(&mut bindings_table, &mut symbol_table);
+        let mut global_resolver =
+            GlobalSemanticResolver::new(&mut bindings_table, &mut symbol_table);
         global_resolver.resolve(&ast);
+        let mut local_resolver = LocalSemanticResolver::new(&mut bindings_table, &mut symbol_table);
+        local_resolver.resolve(&ast);
+        let resolutions = local_resolver.resolutions;
+        let mut checker = TypeChecker::new(&mut bindings_table, &mut symbol_table, &resolutions);
+        checker.resolve(&ast);
 
-        // No errors in global resolver
-        assert!(global_resolver.errors.len() == 0);
+        // No errors in type checking
+        assert!(checker.errors().is_empty());
+        let types = checker.types;
 
-        let mut lowering = HirLoweringContext::new(&bindings_table, &symbol_table);
-        let lowered = lowering.lower(&ast);
+        let mut hir_lowering = HirLoweringContext::new(&bindings_table, &symbol_table, &types);
+        let hir = hir_lowering.lower(ast);
+        let mut lowering = MirLoweringContext::new(&bindings_table, &symbol_table, &types);
+        let lowered = lowering.lower(hir);
         assert_debug_snapshot!(lowered);
     }
 }
@@ -1,21 +1,28 @@
 use crate::{consume, ident, ParseError, ParseResult, Parser};
 
 use jswt_ast::*;
-use jswt_common::{Span, Spannable};
+use jswt_common::{NodeId, Span, Spannable};
 use jswt_tokenizer::TokenType;
 
 impl<'a> Parser<'a> {
     /// ClassDeclaration
-    ///   : 'class' Indentifier ClassBody
+    ///   : Annotation* 'class' Indentifier ClassBody
     ///   ;
-    pub(crate) fn class_declaration(&mut self) -> ParseResult<ClassDeclarationElement> {
+    pub(crate) fn class_declaration(
+        &mut self,
+        doc: Option<Span>,
+        annotations: Vec<Annotation>,
+    ) -> ParseResult<ClassDeclarationElement> {
         let start = consume!(self, TokenType::Class)?;
         let ident = ident!(self)?;
 
         let body = self.class_body()?;
 
         Ok(ClassDeclarationElement {
+            id: NodeId::DUMMY,
             span: start + body.span(),
+            doc,
+            annotations,
             ident,
             body,
         })
@@ -28,25 +35,50 @@ impl<'a> Parser<'a> {
         let start = consume!(self, TokenType::LeftBrace)?;
 
         let mut class_elements = vec![];
-        while !self.lookahead_is(TokenType::RightBrace) {
-            class_elements.push(self.class_element()?);
+        while !self.lookahead_is(TokenType::RightBrace) && !self.lookahead_is(TokenType::Eof) {
+            let element_start = self.lookahead_span();
+            match self.class_element() {
+                Ok(element) => class_elements.push(element),
+                // Members don't have a placeholder, the broken member is dropped
+                Err(err) => {
+                    self.handle_error_and_recover(
+                        err,
+                        element_start,
+                        &[TokenType::Constructor, TokenType::At],
+                        Some(TokenType::RightBrace),
+                    );
+                }
+            }
         }
 
         let end = consume!(self, TokenType::RightBrace)?;
         Ok(ClassBody {
+            id: NodeId::DUMMY,
             span: start + end,
             class_elements,
         })
     }
 
     /// ClassElement
-    ///   : ClassConstructor
+    ///   : Annotation* ClassConstructor
+    ///   | Annotation* ClassPropertyMember
     ///   ;
     pub(crate) fn class_element(&mut self) -> ParseResult<ClassElement> {
+        let doc = self.lookahead_doc();
+        let annotations = self.annotations()?;
         let elem = match self.lookahead_type() {
-            Some(TokenType::Constructor) => self.class_constructor()?.into(),
-            Some(TokenType::Identifier) | Some(TokenType::At) => self.class_property_member()?.into(),
-            _ => todo!(),
+            Some(TokenType::Constructor) => self.class_constructor(doc, annotations)?.into(),
+            Some(TokenType::Identifier) | Some(TokenType::Readonly) => {
+                self.class_property_member(doc, annotations)?
+            }
+            _ => {
+                return Err(self.no_viable_alternative(&[
+                    TokenType::Constructor,
+                    TokenType::Identifier,
+                    TokenType::At,
+                    TokenType::Readonly,
+                ]))
+            }
         };
 
         Ok(elem)
@@ -55,31 +87,45 @@ impl<'a> Parser<'a> {
     /// ClassConstructor
     ///   : 'constructor' FormalParameterList Block
     ///   ;
-    pub(crate) fn class_constructor(&mut self) -> ParseResult<ClassConstructorElement> {
+    pub(crate) fn class_constructor(
+        &mut self,
+        doc: Option<Span>,
+        annotations: Vec<Annotation>,
+    ) -> ParseResult<ClassConstructorElement> {
         let start = consume!(self, TokenType::Constructor)?;
 
         let params = self.formal_parameter_list()?;
         let body = self.block()?;
 
         Ok(ClassConstructorElement {
+            id: NodeId::DUMMY,
             span: start + body.span(),
+            doc,
+            annotations,
             params,
             body,
         })
     }
 
     /// ClassPropertyMember
-    ///   : Annotation? Identifier '(' FormalParameterList ')' ':' TypeAnnotation Block    #ClassMethod
-    ///   : Annotation? Identifier ':' TypeAnnotaiton     #ClassField
+    ///   : Identifier '(' FormalParameterList ')' ':' TypeAnnotation Block    #ClassMethod
+    ///   : 'readonly'? Identifier ':' TypeAnnotaiton     #ClassField
     ///   ;
-    pub(crate) fn class_property_member(&mut self) -> ParseResult<ClassElement> {
-        let mut annotations = vec![];
-        while self.lookahead_is(TokenType::At) {
-            annotations.push(self.annotation()?);
+    pub(crate) fn class_property_member(
+        &mut self,
+        doc: Option<Span>,
+        annotations: Vec<Annotation>,
+    ) -> ParseResult<ClassElement> {
+
+        let mut readonly = false;
+        if self.lookahead_is(TokenType::Readonly) {
+            consume!(self, TokenType::Readonly)?;
+            readonly = true;
         }
 
         let ident = ident!(self)?;
-        if self.lookahead_is(TokenType::LeftParen) {
+        // Only fields can be readonly
+        if !readonly && self.lookahead_is(TokenType::LeftParen) {
             let params = self.formal_parameter_list()?;
             //Parse return value
             let mut returns = None;
@@ -90,7 +136,9 @@ impl<'a> Parser<'a> {
             let body = self.block()?;
 
             return Ok(ClassElement::Method(ClassMethodElement {
+                id: NodeId::DUMMY,
                 span: ident.span() + body.span(),
+                doc,
                 ident,
                 params,
                 returns,
@@ -99,12 +147,22 @@ impl<'a> Parser<'a> {
             }));
         }
 
+        if !self.lookahead_is(TokenType::Colon) {
+            if readonly {
+                return Err(self.no_viable_alternative(&[TokenType::Colon]));
+            }
+            return Err(self.no_viable_alternative(&[TokenType::LeftParen, TokenType::Colon]));
+        }
+
         let type_annotation = self.type_annotation()?;
-        consume!(self, TokenType::Semi)?;
+        self.semicolon()?;
 
         Ok(ClassElement::Field(ClassFieldElement {
+            id: NodeId::DUMMY,
             span: ident.span(),
+            doc,
             annotations,
+            readonly,
             ident,
             type_annotation,
         }))
@@ -155,4 +213,43 @@ mod test {
         assert_debug_snapshot!(actual);
         assert_eq!(parser.errors.len(), 0);
     }
+
+    #[test]
+    fn test_class_declaration_with_annotations() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_class_declaration_with_annotations",
+            "@inline class A { @inline a: i32; }",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn test_class_constructor_with_annotations() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_class_constructor_with_annotations",
+            "class A { @allow(\"unused-parameter\") constructor(a: i32) { } }",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn test_class_declaration_with_readonly_field() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_class_declaration_with_readonly_field",
+            "class A { readonly a: i32; }",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
 }
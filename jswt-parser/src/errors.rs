@@ -13,4 +13,29 @@ pub enum ParseError {
         actual: TokenType,
         span: Span,
     },
+    MissingOperand {
+        operator: TokenType,
+        span: Span,
+    },
+    JumpOutsideLoop {
+        keyword: TokenType,
+        span: Span,
+    },
+    /// Integer literals have to fit in 64 bits
+    IntegerTooLarge {
+        span: Span,
+    },
+}
+
+impl ParseError {
+    /// Span of the code the error is reported on
+    pub fn span(&self) -> &Span {
+        match self {
+            ParseError::MismatchedToken { span, .. }
+            | ParseError::NoViableAlternative { span, .. }
+            | ParseError::MissingOperand { span, .. }
+            | ParseError::JumpOutsideLoop { span, .. }
+            | ParseError::IntegerTooLarge { span } => span,
+        }
+    }
 }
@@ -0,0 +1,629 @@
+use jswt_ast::*;
+use jswt_common::{NodeId, Span, Spannable};
+use jswt_tokenizer::TokenType;
+
+use crate::{consume, consume_unchecked, ident};
+use crate::{ParseError, ParseResult, Parser};
+
+/// Binding power of an operator, ordered from the loosest
+/// to the tightest binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Precedence {
+    Lowest,
+    Assignment,
+    BitwiseOr,
+    BitwiseAnd,
+    Equality,
+    Relational,
+    Cast,
+    Additive,
+    Multiplicative,
+    Prefix,
+    Postfix,
+    Member,
+}
+
+impl Precedence {
+    /// The next tightest level of binding. This is the minimum precedence
+    /// of the right hand side of a left associative operator.
+    pub fn tighter(self) -> Precedence {
+        match self {
+            Precedence::Lowest => Precedence::Assignment,
+            Precedence::Assignment => Precedence::BitwiseOr,
+            Precedence::BitwiseOr => Precedence::BitwiseAnd,
+            Precedence::BitwiseAnd => Precedence::Equality,
+            Precedence::Equality => Precedence::Relational,
+            Precedence::Relational => Precedence::Cast,
+            Precedence::Cast => Precedence::Additive,
+            Precedence::Additive => Precedence::Multiplicative,
+            Precedence::Multiplicative => Precedence::Prefix,
+            Precedence::Prefix => Precedence::Postfix,
+            Precedence::Postfix | Precedence::Member => Precedence::Member,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Associativity {
+    Left,
+    Right,
+}
+
+type UnaryOperatorFn = fn(Span) -> UnaryOperator;
+
+/// The node built once an infix or postfix operator has been matched
+#[derive(Clone, Copy)]
+enum Operator {
+    Binary(
+        fn(BinaryExpression) -> SingleExpression,
+        fn(Span) -> BinaryOperator,
+    ),
+    Postfix(UnaryOperatorFn),
+    Cast,
+    Index,
+    Call,
+    Dot,
+}
+
+/// An operator that appears after its left operand
+struct InfixOperator {
+    token: TokenType,
+    precedence: Precedence,
+    associativity: Associativity,
+    operator: Operator,
+}
+
+macro_rules! infix {
+    ($token:ident, $precedence:ident, $associativity:ident, $operator:expr) => {
+        InfixOperator {
+            token: TokenType::$token,
+            precedence: Precedence::$precedence,
+            associativity: Associativity::$associativity,
+            operator: $operator,
+        }
+    };
+}
+
+macro_rules! binary {
+    ($exp:ident, $op:ident) => {
+        Operator::Binary(SingleExpression::$exp, BinaryOperator::$op)
+    };
+}
+
+/// Every operator that continues an expression once its left operand has
+/// been parsed. Adding an operator only requires adding an entry here.
+const INFIX_OPERATORS: &[InfixOperator] = &[
+    infix!(Equal, Assignment, Right, binary!(Assignment, Assign)),
+    infix!(Or, BitwiseOr, Left, binary!(Bitwise, Or)),
+    infix!(And, BitwiseAnd, Left, binary!(Bitwise, And)),
+    infix!(EqualEqual, Equality, Left, binary!(Equality, Equal)),
+    infix!(BangEqual, Equality, Left, binary!(Equality, NotEqual)),
+    infix!(Greater, Relational, Left, binary!(Relational, Greater)),
+    infix!(
+        GreaterEqual,
+        Relational,
+        Left,
+        binary!(Relational, GreaterEqual)
+    ),
+    infix!(Less, Relational, Left, binary!(Relational, Less)),
+    infix!(LessEqual, Relational, Left, binary!(Relational, LessEqual)),
+    infix!(As, Cast, Left, Operator::Cast),
+    infix!(Plus, Additive, Left, binary!(Additive, Plus)),
+    infix!(Minus, Additive, Left, binary!(Additive, Minus)),
+    infix!(Star, Multiplicative, Left, binary!(Multiplicative, Mult)),
+    infix!(Slash, Multiplicative, Left, binary!(Multiplicative, Div)),
+    infix!(
+        PlusPlus,
+        Postfix,
+        Left,
+        Operator::Postfix(UnaryOperator::PostIncrement)
+    ),
+    infix!(
+        MinusMinus,
+        Postfix,
+        Left,
+        Operator::Postfix(UnaryOperator::PostDecrement)
+    ),
+    infix!(LeftBracket, Member, Left, Operator::Index),
+    infix!(LeftParen, Member, Left, Operator::Call),
+    infix!(Dot, Member, Left, Operator::Dot),
+];
+
+/// Operators that appear before their operand
+const PREFIX_OPERATORS: &[(TokenType, UnaryOperatorFn)] = &[
+    (TokenType::Plus, UnaryOperator::Plus),
+    (TokenType::Minus, UnaryOperator::Minus),
+    (TokenType::Not, UnaryOperator::Not),
+];
+
+/// Tokens that can begin an expression
+const EXPRESSION_START: &[TokenType] = &[
+    TokenType::New,
+    TokenType::Plus,
+    TokenType::Minus,
+    TokenType::Not,
+    TokenType::This,
+    TokenType::Identifier,
+    TokenType::LeftParen,
+    TokenType::LeftBracket,
+    TokenType::Integer,
+    TokenType::HexInteger,
+    TokenType::Float,
+    TokenType::String,
+    TokenType::True,
+    TokenType::False,
+];
+
+fn infix_operator(token: TokenType) -> Option<&'static InfixOperator> {
+    INFIX_OPERATORS.iter().find(|op| op.token == token)
+}
+
+fn prefix_operator(token: TokenType) -> Option<UnaryOperatorFn> {
+    PREFIX_OPERATORS
+        .iter()
+        .find(|(ty, _)| *ty == token)
+        .map(|(_, op)| *op)
+}
+
+impl<'a> Parser<'a> {
+    /// SingleExpression
+    ///   : SingleExpression '=' SingleExpression
+    ///   | 'new' Identifier Arguments
+    ///   | SingleExpression '|' SingleExpression
+    ///   | SingleExpression '&' SingleExpression
+    ///   | SingleExpression ('==' | '!=') SingleExpression
+    ///   | SingleExpression ('<' | '>' | '<=' | '>=') SingleExpression
+    ///   | SingleExpression 'as' TypeReference
+    ///   | SingleExpression ('+' | '-') SingleExpression
+    ///   | SingleExpression ('*' | '/') SingleExpression
+    ///   | ('!' | '-' | '+') SingleExpression
+    ///   | SingleExpression ('++' | '--')
+    ///   | SingleExpression '[' SingleExpression ']'
+    ///   | SingleExpression Arguments
+    ///   | SingleExpression '.' Identifier
+    ///   | 'this'
+    ///   | Identifier
+    ///   | '(' SingleExpression ')'
+    ///   | ArrayLiteral
+    ///   | Literal
+    ///   ;
+    ///
+    /// Operators are listed from the loosest to the tightest binding.
+    /// Assignment is right associative, everything else is left associative.
+    pub fn single_expression(&mut self) -> ParseResult<SingleExpression> {
+        self.expression(Precedence::Lowest)
+    }
+
+    /// Parses an expression made up of operators that bind
+    /// at least as tightly as the given precedence
+    pub(crate) fn expression(&mut self, precedence: Precedence) -> ParseResult<SingleExpression> {
+        let mut left = self.prefix_expression()?;
+        while let Some(infix) = self.lookahead_type().and_then(infix_operator) {
+            if infix.precedence < precedence {
+                break;
+            }
+            // Restricted production. A postfix operator on a new line
+            // ends the expression instead of applying to it
+            if matches!(infix.operator, Operator::Postfix(_)) && self.can_insert_semicolon() {
+                break;
+            }
+            left = self.infix_expression(left, infix)?;
+        }
+        Ok(left)
+    }
+
+    /// Parses the operand of the operator that was just consumed, reporting
+    /// the operator itself when the operand is missing entirely
+    fn operand(
+        &mut self,
+        operator: TokenType,
+        span: Span,
+        precedence: Precedence,
+    ) -> ParseResult<SingleExpression> {
+        let starts_expression = self
+            .lookahead_type()
+            .map(|ty| EXPRESSION_START.contains(&ty))
+            .unwrap_or(false);
+        if !starts_expression {
+            // Stand in for the operand so that the rest of
+            // the expression is kept in the tree
+            let placeholder = Span::new(span.file.clone(), span.module.clone(), span.end, span.end);
+            self.errors
+                .push(ParseError::MissingOperand { operator, span });
+            return Ok(SingleExpression::Error(ErrorExpression {
+                id: NodeId::DUMMY,
+                span: placeholder,
+            }));
+        }
+        self.expression(precedence)
+    }
+
+    /// PrefixExpression
+    ///   : 'new' Identifier Arguments
+    ///   | ('!' | '-' | '+') SingleExpression
+    ///   | PrimaryExpression
+    ///   ;
+    fn prefix_expression(&mut self) -> ParseResult<SingleExpression> {
+        let token = match self.lookahead_type() {
+            Some(token) => token,
+            None => return self.primary_expression(),
+        };
+
+        if token == TokenType::New {
+            let start = consume_unchecked!(self);
+            // The arguments belong to the new expression so that
+            // members after them apply to the constructed object
+            let ident = ident!(self)?;
+            let class = SingleExpression::Identifier(IdentifierExpression {
+                id: NodeId::DUMMY,
+                span: ident.span.to_owned(),
+                ident,
+            });
+            let arguments = self.argument_list()?;
+            let expression = SingleExpression::Arguments(ArgumentsExpression {
+                id: NodeId::DUMMY,
+                span: class.span() + arguments.span(),
+                ident: Box::new(class),
+                arguments,
+            });
+            return Ok(SingleExpression::New(NewExpression {
+                id: NodeId::DUMMY,
+                span: start + expression.span(),
+                expression: Box::new(expression),
+            }));
+        }
+
+        if let Some(op) = prefix_operator(token) {
+            let span = consume_unchecked!(self);
+            let expr = self.operand(token, span.clone(), Precedence::Prefix)?;
+            return Ok(SingleExpression::Unary(UnaryExpression {
+                id: NodeId::DUMMY,
+                span: span.to_owned() + expr.span(),
+                op: op(span),
+                expr: Box::new(expr),
+            }));
+        }
+
+        self.primary_expression()
+    }
+
+    /// Continues the expression with the given left operand
+    /// using the matched infix or postfix operator
+    fn infix_expression(
+        &mut self,
+        left: SingleExpression,
+        infix: &InfixOperator,
+    ) -> ParseResult<SingleExpression> {
+        let expression = match infix.operator {
+            Operator::Binary(exp, op) => {
+                let op_span = consume_unchecked!(self);
+                let precedence = match infix.associativity {
+                    Associativity::Left => infix.precedence.tighter(),
+                    Associativity::Right => infix.precedence,
+                };
+                let right = self.operand(infix.token, op_span.clone(), precedence)?;
+                exp(BinaryExpression {
+                    id: NodeId::DUMMY,
+                    span: left.span() + right.span(),
+                    left: Box::new(left),
+                    op: op(op_span),
+                    right: Box::new(right),
+                })
+            }
+            Operator::Postfix(op) => {
+                let op_span = consume_unchecked!(self);
+                SingleExpression::Unary(UnaryExpression {
+                    id: NodeId::DUMMY,
+                    span: op_span.to_owned() + left.span(),
+                    op: op(op_span),
+                    expr: Box::new(left),
+                })
+            }
+            Operator::Cast => {
+                consume_unchecked!(self);
+                let target = self.type_reference()?;
+                SingleExpression::Cast(CastExpression {
+                    id: NodeId::DUMMY,
+                    span: left.span() + target.span(),
+                    expression: Box::new(left),
+                    target,
+                })
+            }
+            Operator::Index => {
+                let start = consume_unchecked!(self);
+                let index = self.operand(infix.token, start, Precedence::Lowest)?;
+                let end = consume!(self, TokenType::RightBracket)?;
+                SingleExpression::MemberIndex(MemberIndexExpression {
+                    id: NodeId::DUMMY,
+                    span: left.span() + end,
+                    target: Box::new(left),
+                    index: Box::new(index),
+                })
+            }
+            Operator::Call => {
+                let arguments = self.argument_list()?;
+                SingleExpression::Arguments(ArgumentsExpression {
+                    id: NodeId::DUMMY,
+                    span: left.span() + arguments.span(),
+                    ident: Box::new(left),
+                    arguments,
+                })
+            }
+            Operator::Dot => {
+                consume_unchecked!(self);
+                let ident = ident!(self)?;
+                let expression = SingleExpression::Identifier(IdentifierExpression {
+                    id: NodeId::DUMMY,
+                    span: ident.span.to_owned(),
+                    ident,
+                });
+                SingleExpression::MemberDot(MemberDotExpression {
+                    id: NodeId::DUMMY,
+                    span: left.span() + expression.span(),
+                    expression: Box::new(expression),
+                    target: Box::new(left),
+                })
+            }
+        };
+        Ok(expression)
+    }
+
+    /// ArgumentList
+    ///   :  '(' SingleExpression (',' SingleExpression)* ')'
+    ///   ;
+    fn argument_list(&mut self) -> ParseResult<ArgumentsList> {
+        let mut arguments = vec![];
+        let start = consume!(self, TokenType::LeftParen)?;
+        while !self.lookahead_is(TokenType::RightParen) {
+            let param = self.single_expression()?;
+            arguments.push(param);
+            if !self.lookahead_is(TokenType::Comma) {
+                break;
+            }
+            // Eat the comma
+            consume_unchecked!(self);
+        }
+        let end = consume!(self, TokenType::RightParen)?;
+        // return params
+        Ok(ArgumentsList {
+            span: start + end,
+            arguments,
+        })
+    }
+
+    /// PrimaryExpression
+    ///   : 'this'
+    ///   | Identifier
+    ///   | '(' SingleExpression ')'
+    ///   | ArrayLiteral
+    ///   | Literal
+    ///   ;
+    fn primary_expression(&mut self) -> ParseResult<SingleExpression> {
+        match self.lookahead_type() {
+            Some(TokenType::This) => {
+                let span = consume_unchecked!(self);
+                Ok(SingleExpression::This(ThisExpression {
+                    id: NodeId::DUMMY,
+                    span,
+                }))
+            }
+            Some(TokenType::Identifier) => {
+                let ident = ident!(self)?;
+                Ok(SingleExpression::Identifier(IdentifierExpression {
+                    id: NodeId::DUMMY,
+                    span: ident.span.to_owned(),
+                    ident,
+                }))
+            }
+            Some(TokenType::LeftParen) => {
+                // Parentheses only group, the tree already records the order
+                consume_unchecked!(self);
+                let expression = self.single_expression()?;
+                consume!(self, TokenType::RightParen)?;
+                Ok(expression)
+            }
+            Some(TokenType::LeftBracket) => self.array_literal_expression(),
+            _ => self.literal(),
+        }
+    }
+
+    /// ArrayLiteral
+    ///   :  '[' (SingleExpression ,)* ']'
+    ///   ;
+    fn array_literal_expression(&mut self) -> ParseResult<SingleExpression> {
+        let start = consume!(self, TokenType::LeftBracket)?;
+
+        let mut elements = vec![];
+        while !self.lookahead_is(TokenType::RightBracket) {
+            let element = self.single_expression()?;
+            elements.push(element);
+            if !self.lookahead_is(TokenType::Comma) {
+                break;
+            }
+            // Eat the comma
+            consume_unchecked!(self);
+        }
+
+        let end = consume!(self, TokenType::RightBracket)?;
+
+        Ok(SingleExpression::Literal(Literal::Array(ArrayLiteral {
+            id: NodeId::DUMMY,
+            span: start + end,
+            elements,
+        })))
+    }
+
+    /// Literal
+    ///   : boolean
+    ///   | number
+    ///   | string
+    ///   ;
+    fn literal(&mut self) -> ParseResult<SingleExpression> {
+        let literal: Literal = match self.lookahead_type() {
+            Some(TokenType::True) => {
+                let span = consume_unchecked!(self);
+                BooleanLiteral {
+                    id: NodeId::DUMMY,
+                    span,
+                    value: true,
+                }
+                .into()
+            }
+            Some(TokenType::False) => {
+                let span = consume_unchecked!(self);
+                BooleanLiteral {
+                    id: NodeId::DUMMY,
+                    span,
+                    value: false,
+                }
+                .into()
+            }
+            Some(TokenType::String) => {
+                let span = consume_unchecked!(self);
+                let lexme = span.lexme();
+                StringLiteral {
+                    id: NodeId::DUMMY,
+                    span,
+                    // Drop quoute characters from value
+                    value: lexme[1..lexme.len() - 1].into(),
+                }
+                .into()
+            }
+            Some(TokenType::Integer) => {
+                let span = consume_unchecked!(self);
+                // The tokenizer only matches digits so parsing
+                // can only fail if the value is too large
+                let value = match span.lexme().parse() {
+                    Ok(value) => value,
+                    Err(_) => self.integer_too_large(&span),
+                };
+                IntegerLiteral {
+                    id: NodeId::DUMMY,
+                    span,
+                    value,
+                }
+                .into()
+            }
+            Some(TokenType::HexInteger) => {
+                let span = consume_unchecked!(self);
+                let lexme = span.lexme();
+                let without_prefix = lexme.trim_start_matches("0x");
+                let value = match u64::from_str_radix(without_prefix, 16) {
+                    // Allow integer overflows in this specific instance
+                    Ok(value) => value as i64,
+                    Err(_) => self.integer_too_large(&span),
+                };
+                IntegerLiteral {
+                    id: NodeId::DUMMY,
+                    span,
+                    value,
+                }
+                .into()
+            }
+            Some(TokenType::Float) => {
+                let span = consume_unchecked!(self);
+                let lexme = span.lexme();
+                FloatingPointLiteral {
+                    id: NodeId::DUMMY,
+                    span,
+                    value: lexme.parse::<f64>().unwrap(),
+                }
+                .into()
+            }
+            _ => {
+                return Err(self.no_viable_alternative(EXPRESSION_START));
+            }
+        };
+
+        Ok(SingleExpression::Literal(literal))
+    }
+
+    /// Reports an integer literal that doesn't fit in 64 bits and
+    /// returns the value that stands in for it
+    fn integer_too_large(&mut self, span: &Span) -> i64 {
+        self.errors
+            .push(ParseError::IntegerTooLarge { span: span.clone() });
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_tokenizer::Tokenizer;
+
+    #[test]
+    fn test_assignment_is_right_associative() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("test_assignment_is_right_associative", "a = b = 1;");
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn test_prefix_binds_looser_than_postfix() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("test_prefix_binds_looser_than_postfix", "-i++ * 2;");
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn test_parentheses_override_precedence() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_parentheses_override_precedence",
+            "(1 + 2) * -(a = b);",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn test_integers_too_large_are_reported() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_integers_too_large_are_reported",
+            "let a = 99999999999999999999;\nlet b = 0x1FFFFFFFFFFFFFFFF;\nlet c = 1;",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        parser.parse();
+        assert_debug_snapshot!(parser.errors);
+    }
+
+    #[test]
+    fn test_members_apply_to_the_new_object() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("test_members_apply_to_the_new_object", "new Foo(1).m(2);");
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn test_member_expressions_chain() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("test_member_expressions_chain", "this.items[0].get(1);");
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn test_error_on_missing_operand() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("test_error_on_missing_operand", "let x = 1 + ; let y = -;");
+        let mut parser = Parser::new(&mut tokenizer);
+        parser.parse();
+        assert_debug_snapshot!(parser.errors);
+    }
+}
@@ -1,5 +1,5 @@
 use jswt_ast::*;
-use jswt_common::{Span, Spannable};
+use jswt_common::{NodeId, Span, Spannable};
 use jswt_tokenizer::TokenType;
 
 use crate::{consume, consume_unchecked, ident, maybe_consume};
@@ -7,14 +7,13 @@ use crate::{ParseError, ParseResult, Parser};
 
 impl<'a> Parser<'a> {
     /// FunctionDeclaration
-    ///   :  Annotation? 'export'? 'function' Identifier ( FormalParameterList? ) TypeAnnotation? FunctionBody
+    ///   :  Annotation* 'export'? 'function' Identifier ( FormalParameterList? ) TypeAnnotation? FunctionBody
     ///   ;
-    pub(crate) fn function_declaration(&mut self) -> ParseResult<FunctionDeclarationElement> {
-        let mut annotations = vec![];
-        while self.lookahead_is(TokenType::At) {
-            annotations.push(self.annotation()?);
-        }
-
+    pub(crate) fn function_declaration(
+        &mut self,
+        doc: Option<Span>,
+        annotations: Vec<Annotation>,
+    ) -> ParseResult<FunctionDeclarationElement> {
         let export_span = maybe_consume!(self, TokenType::Export);
         let function_span = consume!(self, TokenType::Function)?;
         let start_span = export_span.to_owned().unwrap_or(function_span);
@@ -35,7 +34,9 @@ impl<'a> Parser<'a> {
             export: export_span.is_some(),
         };
         Ok(FunctionDeclarationElement {
+            id: NodeId::DUMMY,
             span: start_span + body.span(),
+            doc,
             decorators,
             ident,
             params,
@@ -131,6 +132,19 @@ mod test {
         assert_debug_snapshot!(actual);
     }
 
+    #[test]
+    fn test_parse_function_with_named_annotation_arguments() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_parse_function_with_named_annotation_arguments",
+            "@native(module = \"env\") function a(@inline b: i32) {}",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_eq!(parser.errors.len(), 0);
+        assert_debug_snapshot!(actual);
+    }
+
     #[test]
     fn test_parse_function_with_native_annotations() {
         let mut tokenizer = Tokenizer::default();
@@ -1,16 +1,40 @@
 mod class;
 mod errors;
+mod expression;
 mod function;
 
 pub use errors::ParseError;
+pub use expression::Precedence;
 use std::vec;
 
 use jswt_ast::*;
-use jswt_common::{Span, Spannable, Type};
+use jswt_common::{NodeId, Span, Spannable};
 use jswt_tokenizer::{Token, TokenType, Tokenizer, TokenizerError};
+use jswt_types::Type;
 
 type ParseResult<T> = Result<T, ParseError>;
 
+/// Tokens that begin a top level declaration
+const DECLARATION_START: &[TokenType] = &[
+    TokenType::Export,
+    TokenType::Function,
+    TokenType::Class,
+    TokenType::At,
+];
+
+/// Tokens that unambiguously begin a statement. Blocks are left out
+/// so that recovery skips over the body of a broken declaration
+/// instead of parsing it as a free standing block.
+const STATEMENT_START: &[TokenType] = &[
+    TokenType::If,
+    TokenType::While,
+    TokenType::Return,
+    TokenType::Let,
+    TokenType::Const,
+    TokenType::Break,
+    TokenType::Continue,
+];
+
 /// Returns true if a token matching the given token type
 /// was consumed.
 #[macro_export]
@@ -31,7 +55,7 @@ macro_rules! maybe_consume {
 macro_rules! consume_unchecked {
     ($self:ident) => {{
         let span = $self.lookahead_span();
-        $self.lookahead = $self.tokenizer.next_token();
+        $self.advance();
         span
     }};
 }
@@ -52,7 +76,7 @@ macro_rules! consume {
         }
         // Advance lookahead
         let span = $self.lookahead_span();
-        $self.lookahead = $self.tokenizer.next_token();
+        $self.advance();
         Ok::<Span, ParseError>(span)
     }};
 }
@@ -73,44 +97,22 @@ macro_rules! ident {
         }
         let ident = Identifier::new(token.span.lexme(), token.span.clone());
         // Advance lookahead
-        $self.lookahead = $self.tokenizer.next_token();
+        $self.advance();
         Ok::<Identifier, ParseError>(ident)
     }};
 }
 
-macro_rules! binary_expression {
-    ($name:ident, next: $next:ident, $([exp => $exp:ident, op => $op:ident, token => $token:ident]),*) => {
-        fn $name(&mut self) -> ParseResult<SingleExpression>{
-            let mut left = self.$next()?;
-            while let Some(token) = self.lookahead_type() {
-                match token {
-                    $(
-                        TokenType::$token => {
-                            let op_span = consume_unchecked!(self);
-                            let right = self.$next()?;
-                            left = SingleExpression::$exp(BinaryExpression {
-                                span: left.span() + right.span(),
-                                left: Box::new(left),
-                                op: BinaryOperator::$op(op_span),
-                                right: Box::new(right),
-                                ty: jswt_common::Type::Unknown,
-                            });
-                        }
-                    )*
-                    _ => break,
-                }
-            }
-
-            Ok(left)
-        }
-    };
-}
-
 /// Predictive LL(1) parser
 pub struct Parser<'a> {
     tokenizer: &'a mut Tokenizer,
     lookahead: Option<Token>,
+    // Span of the last consumed token
+    previous: Option<Span>,
     errors: Vec<ParseError>,
+    // Number of loops enclosing the statement being parsed
+    loop_depth: usize,
+    // Semicolons inserted by ASI
+    inserted_semicolons: Vec<Span>,
 }
 
 impl<'a> Parser<'a> {
@@ -118,7 +120,10 @@ impl<'a> Parser<'a> {
         Self {
             tokenizer,
             lookahead: None,
+            previous: None,
             errors: vec![],
+            loop_depth: 0,
+            inserted_semicolons: vec![],
         }
     }
 
@@ -128,6 +133,13 @@ impl<'a> Parser<'a> {
         Ast::new(self.program())
     }
 
+    /// Parses the sources as more files of an existing tree
+    pub fn parse_into(&mut self, ast: &mut Ast) {
+        self.lookahead = self.tokenizer.next_token();
+        let program = self.program();
+        ast.append(program);
+    }
+
     /// Entry point of the program
     ///
     /// Program
@@ -138,7 +150,10 @@ impl<'a> Parser<'a> {
         while self.lookahead.is_some() {
             files.push(self.file());
         }
-        Program { files }
+        Program {
+            id: NodeId::DUMMY,
+            files,
+        }
     }
 
     /// File
@@ -150,6 +165,7 @@ impl<'a> Parser<'a> {
         // Eat the EOF token
         let end = consume_unchecked!(self);
         File {
+            id: NodeId::DUMMY,
             span: start + end,
             source_elements,
         }
@@ -163,22 +179,14 @@ impl<'a> Parser<'a> {
         let mut source_elements = vec![];
         let start = self.lookahead_span();
         while self.lookahead_type().is_some() && self.lookahead_type() != terminal {
+            let element_start = self.lookahead_span();
             match self.source_element() {
                 Ok(element) => source_elements.push(element),
-                Err(err) => self.handle_error_and_recover(
-                    err,
-                    &[
-                        // FunctionDeclaration start tokens
-                        TokenType::Export,
-                        TokenType::Function,
-                        // Statement start tokens
-                        TokenType::LeftBrace,
-                        TokenType::Semi,
-                        TokenType::Return,
-                        TokenType::Let,
-                        TokenType::Const,
-                    ],
-                ),
+                Err(err) => {
+                    let sync = [DECLARATION_START, STATEMENT_START].concat();
+                    let error = self.handle_error_and_recover(err, element_start, &sync, terminal);
+                    source_elements.push(SourceElement::Statement(error.into()));
+                }
             };
         }
 
@@ -196,11 +204,28 @@ impl<'a> Parser<'a> {
     ///   ;
     fn source_element(&mut self) -> ParseResult<SourceElement> {
         let elem = match self.lookahead_type() {
-            // Need to check for optional function decorators
-            Some(TokenType::Function) | Some(TokenType::Export) | Some(TokenType::At) => {
-                self.function_declaration()?.into()
+            // Annotations can be attached to either kind of declaration
+            Some(TokenType::At) => {
+                let doc = self.lookahead_doc();
+                let annotations = self.annotations()?;
+                match self.lookahead_type() {
+                    Some(TokenType::Function) | Some(TokenType::Export) => {
+                        self.function_declaration(doc, annotations)?.into()
+                    }
+                    Some(TokenType::Class) => self.class_declaration(doc, annotations)?.into(),
+                    _ => {
+                        return Err(self.no_viable_alternative(&[
+                            TokenType::Function,
+                            TokenType::Export,
+                            TokenType::Class,
+                        ]))
+                    }
+                }
             }
-            Some(TokenType::Class) => self.class_declaration()?.into(),
+            Some(TokenType::Function) | Some(TokenType::Export) => self
+                .function_declaration(self.lookahead_doc(), vec![])?
+                .into(),
+            Some(TokenType::Class) => self.class_declaration(self.lookahead_doc(), vec![])?.into(),
             _ => self.statement()?.into(),
         };
         Ok(elem)
@@ -212,6 +237,8 @@ impl<'a> Parser<'a> {
     ///   |  IfStatement
     ///   |  IterationStatement
     ///   |  ReturnStatement
+    ///   |  BreakStatement
+    ///   |  ContinueStatement
     ///   |  VariableStatement
     ///   |  ExpressionStatement
     ///   ;
@@ -222,6 +249,8 @@ impl<'a> Parser<'a> {
             Some(TokenType::If) => self.if_statement()?.into(),
             Some(TokenType::While) => self.iteration_statement()?.into(),
             Some(TokenType::Return) => self.return_statement()?.into(),
+            Some(TokenType::Break) => self.break_statement()?.into(),
+            Some(TokenType::Continue) => self.continue_statement()?.into(),
             Some(TokenType::Let) | Some(TokenType::Const) => self.variable_statement()?.into(),
             _ => self.expression_statement()?.into(),
         };
@@ -236,6 +265,7 @@ impl<'a> Parser<'a> {
         let statements = self.statement_list(Some(TokenType::RightBrace))?;
         let end = consume!(self, TokenType::RightBrace)?;
         Ok(BlockStatement {
+            id: NodeId::DUMMY,
             span: start + end,
             statements,
         })
@@ -246,7 +276,10 @@ impl<'a> Parser<'a> {
     ///   ;
     fn empty_statement(&mut self) -> ParseResult<EmptyStatement> {
         let span = consume!(self, TokenType::Semi)?;
-        Ok(EmptyStatement { span })
+        Ok(EmptyStatement {
+            id: NodeId::DUMMY,
+            span,
+        })
     }
 
     /// IfStatement
@@ -272,6 +305,7 @@ impl<'a> Parser<'a> {
             .unwrap_or_else(|| consequence.span());
 
         Ok(IfStatement {
+            id: NodeId::DUMMY,
             span: start + end,
             condition,
             consequence,
@@ -299,9 +333,13 @@ impl<'a> Parser<'a> {
         consume!(self, TokenType::LeftParen)?;
         let expression = self.single_expression()?;
         consume!(self, TokenType::RightParen)?;
-        let block = self.block()?;
+        self.loop_depth += 1;
+        let block = self.block();
+        self.loop_depth -= 1;
+        let block = block?;
 
         Ok(WhileIterationElement {
+            id: NodeId::DUMMY,
             span: start + block.span(),
             expression,
             block,
@@ -310,19 +348,60 @@ impl<'a> Parser<'a> {
     }
 
     /// ReturnStatement
-    ///   : 'return' SingleExpression ';'
+    ///   : 'return' [no LineTerminator here] SingleExpression? ';'
     ///   ;
     fn return_statement(&mut self) -> ParseResult<ReturnStatement> {
         let start = consume!(self, TokenType::Return)?;
-        let expression = self.single_expression()?;
-        let end = consume!(self, TokenType::Semi)?;
+        let mut expression = None;
+        if !self.can_insert_semicolon() && !self.lookahead_is(TokenType::Semi) {
+            expression = Some(self.single_expression()?);
+        }
+        let end = self.semicolon()?;
 
         Ok(ReturnStatement {
+            id: NodeId::DUMMY,
             span: start + end,
             expression,
         })
     }
 
+    /// BreakStatement
+    ///   : 'break' ';'
+    ///   ;
+    fn break_statement(&mut self) -> ParseResult<BreakStatement> {
+        let start = consume!(self, TokenType::Break)?;
+        self.expect_inside_loop(TokenType::Break, &start);
+        let end = self.semicolon()?;
+        Ok(BreakStatement {
+            id: NodeId::DUMMY,
+            span: start + end,
+        })
+    }
+
+    /// ContinueStatement
+    ///   : 'continue' ';'
+    ///   ;
+    fn continue_statement(&mut self) -> ParseResult<ContinueStatement> {
+        let start = consume!(self, TokenType::Continue)?;
+        self.expect_inside_loop(TokenType::Continue, &start);
+        let end = self.semicolon()?;
+        Ok(ContinueStatement {
+            id: NodeId::DUMMY,
+            span: start + end,
+        })
+    }
+
+    /// Jumps are only valid inside of a loop body. The error doesn't
+    /// prevent the statement from being parsed.
+    fn expect_inside_loop(&mut self, keyword: TokenType, span: &Span) {
+        if self.loop_depth == 0 {
+            self.errors.push(ParseError::JumpOutsideLoop {
+                keyword,
+                span: span.clone(),
+            });
+        }
+    }
+
     /// StatementList
     ///   :  Statement
     ///   |  StatementList Statement
@@ -330,41 +409,48 @@ impl<'a> Parser<'a> {
     fn statement_list(&mut self, terminal: Option<TokenType>) -> ParseResult<StatementList> {
         let mut statements = vec![];
         while self.lookahead_type().is_some() && self.lookahead_type() != terminal {
+            let statement_start = self.lookahead_span();
             match self.statement() {
                 Ok(element) => statements.push(element),
-                Err(err) => self.handle_error_and_recover(
-                    err,
-                    &[
-                        TokenType::LeftBrace,
-                        TokenType::RightBrace,
-                        TokenType::Semi,
-                        TokenType::Return,
-                        TokenType::Let,
-                        TokenType::Const,
-                    ],
-                ),
+                Err(err) => {
+                    let error = self.handle_error_and_recover(
+                        err,
+                        statement_start,
+                        STATEMENT_START,
+                        terminal,
+                    );
+                    statements.push(error.into());
+                }
             }
         }
         Ok(StatementList { statements })
     }
 
     /// VariableStatement
-    ///   :  VariableModifier Assignable '=' singleExpression ';'
+    ///   :  VariableModifier Assignable TypeAnnotation? '=' singleExpression ';'
+    ///   |  VariableModifier Assignable TypeAnnotation ';'
     ///   ;
     fn variable_statement(&mut self) -> ParseResult<VariableStatement> {
         let modifier = self.variable_modifier()?;
         let target = self.assignable()?;
 
         let mut type_annotation = None;
-        if self.lookahead_is(TokenType::Colon) {
-            type_annotation = Some(self.type_annotation()?);
+        match self.lookahead_type() {
+            Some(TokenType::Colon) => type_annotation = Some(self.type_annotation()?),
+            Some(TokenType::Equal) => {}
+            _ => return Err(self.no_viable_alternative(&[TokenType::Colon, TokenType::Equal])),
         }
 
-        consume!(self, TokenType::Equal)?;
-        let expression = self.single_expression()?;
-        let end = consume!(self, TokenType::Semi)?;
+        // Declarations with a type don't need to be initialized immediately
+        let mut expression = None;
+        if type_annotation.is_none() || self.lookahead_is(TokenType::Equal) {
+            consume!(self, TokenType::Equal)?;
+            expression = Some(self.single_expression()?);
+        }
+        let end = self.semicolon()?;
 
         Ok(VariableStatement {
+            id: NodeId::DUMMY,
             span: modifier.span() + end,
             modifier,
             target,
@@ -408,443 +494,36 @@ impl<'a> Parser<'a> {
     ///   ;
     fn expression_statement(&mut self) -> ParseResult<ExpressionStatement> {
         let expression = self.single_expression()?;
-        let end = consume!(self, TokenType::Semi)?;
+        let end = self.semicolon()?;
 
         Ok(ExpressionStatement {
+            id: NodeId::DUMMY,
             span: expression.span() + end,
             expression,
         })
     }
 
-    /// SingleExpression
-    ///   : SingleExpression '=' SingleExpression
-    ///   | 'new' SingleExpression
-    ///   | SingleExpression '[' SingleExpression ']'
-    ///   | SingleExpression '|' SingleExpression
-    ///   | SingleExpression '&' SingleExpression
-    ///   | SingleExpression ('==' | '!=') SingleExpression
-    ///   | SingleExpression ('<' | '>' | '<=' | '>=') SingleExpression
-    ///   | SingleExpression ('+' | '-') SingleExpression
-    ///   | SingleExpression ('*' | '/') SingleExpression
-    ///   | '!' SingleExpression
-    ///   | '-' SingleExpression
-    ///   | '+' SingleExpression
-    ///   | SingleExpression Arguments
-    ///   | SingleExpression '.' Identifier
-    ///   | 'this'
-    ///   | Identifier
-    ///   | ArrayLiteral
-    ///   | Literal
-    ///   ;
-    pub fn single_expression(&mut self) -> ParseResult<SingleExpression> {
-        self.assignment_expression()
-    }
-
-    // AssignmentExpression
-    //   : MemberDotExpression '=' MemberDotExpression
-    //   ;
-    binary_expression!(
-        assignment_expression,
-        next: new_expression,
-        [exp => Assignment, op => Assign, token => Equal]
-    );
-
-    /// NewExpression
-    ///   : 'new' SingleExpression
-    ///   ;
-    fn new_expression(&mut self) -> ParseResult<SingleExpression> {
-        if self.lookahead_is(TokenType::New) {
-            let start = consume_unchecked!(self);
-            let expression = self.single_expression()?;
-            return Ok(SingleExpression::New(NewExpression {
-                span: start + expression.span(),
-                expression: Box::new(expression),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-
-        self.member_index_expression()
-    }
-
-    /// MemberIndexExpression
-    ///   : BitwiseOrExpression '[' BitwiseOrExpression ']'
-    ///   ;
-    fn member_index_expression(&mut self) -> ParseResult<SingleExpression> {
-        let target = self.bitwise_or_expression()?;
-        if self.lookahead_is(TokenType::LeftBracket) {
-            consume_unchecked!(self);
-
-            let index = self.bitwise_or_expression()?;
-            let end = consume!(self, TokenType::RightBracket)?;
-            return Ok(SingleExpression::MemberIndex(MemberIndexExpression {
-                span: target.span() + end,
-                target: Box::new(target),
-                index: Box::new(index),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-        Ok(target)
-    }
-
-    //  BitwiseOrExpression
-    //    : BitwiseAndExpression '|' BitwiseAndExpression
-    //    ;
-    binary_expression!(
-        bitwise_or_expression,
-        next: bitwise_and_expression,
-        [exp => Bitwise, op => Or, token => Or]
-    );
-
-    //  BitwiseAndExpression
-    //    : EqualityExpression '&' EqualityExpression
-    //    ;
-    binary_expression!(
-        bitwise_and_expression,
-        next: equality_expression,
-        [exp => Bitwise, op => And, token => And]
-    );
-
-    // EqualityExpression
-    //    : RelationalExpression ('==' | '!=') RelationalExpression
-    //    ;
-    binary_expression!(
-        equality_expression,
-        next: relational_expression,
-        [exp => Equality, op => Equal, token => EqualEqual],
-        [exp => Equality, op => NotEqual, token => BangEqual]
-    );
-
-    //  RelationalExpression
-    //    : AdditiveExpression ('<' | '>' | '<=' | '>=') AdditiveExpression
-    //    ;
-    binary_expression!(
-        relational_expression,
-        next: additive_expression,
-        [exp => Relational, op => Greater, token => Greater],
-        [exp => Relational, op => GreaterEqual, token => GreaterEqual],
-        [exp => Relational, op => Less, token => Less],
-        [exp => Relational, op => LessEqual, token => LessEqual]
-    );
-
-    // AdditiveExpression
-    //    : MultiplicativeExpression ('+' | '-') MultiplicativeExpression
-    //    ;
-    binary_expression!(
-        additive_expression,
-        next: multipicative_expression,
-        [exp => Additive, op => Plus, token => Plus],
-        [exp => Additive, op => Minus, token => Minus]
-    );
-
-    // MultiplicativeExpression
-    //    : PostfixUnaryExpression ('*' | '/' | '%') PostfixUnaryExpression
-    //    ;
-    binary_expression!(
-        multipicative_expression,
-        next: postfix_unary_expression,
-        [exp => Multiplicative, op => Mult, token => Star],
-        [exp => Multiplicative, op => Div, token => Slash]
-    );
-
-    // PostfixUnaryExpression
-    //    : PrefixUnaryExpression ('++' | '--')
-    //    ;
-    fn postfix_unary_expression(&mut self) -> ParseResult<SingleExpression> {
-        let expr = self.prefix_unary_expression()?;
-        if self.lookahead_is(TokenType::PlusPlus) {
-            let op = consume_unchecked!(self);
-            return Ok(SingleExpression::Unary(UnaryExpression {
-                span: op.to_owned() + expr.span(),
-                op: UnaryOperator::PostIncrement(op),
-                expr: Box::new(expr),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-        if self.lookahead_is(TokenType::MinusMinus) {
-            let op = consume_unchecked!(self);
-            return Ok(SingleExpression::Unary(UnaryExpression {
-                span: op.to_owned() + expr.span(),
-                op: UnaryOperator::PostDecrement(op),
-                expr: Box::new(expr),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-        Ok(expr)
-    }
-
-    /// PrefixUnaryExpression
-    ///   : '+' ArgumentsExpression
-    ///   | '-' ArgumentsExpression
-    ///   | '!' ArgumentsExpression
-    ///   ;
-    fn prefix_unary_expression(&mut self) -> ParseResult<SingleExpression> {
-        if self.lookahead_is(TokenType::Plus) {
-            let op = consume_unchecked!(self);
-            let expr = self.arguments_expression()?;
-            return Ok(SingleExpression::Unary(UnaryExpression {
-                span: op.to_owned() + expr.span(),
-                op: UnaryOperator::Plus(op),
-                expr: Box::new(expr),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-
-        if self.lookahead_is(TokenType::Minus) {
-            let op = consume_unchecked!(self);
-            let expr = self.arguments_expression()?;
-            return Ok(SingleExpression::Unary(UnaryExpression {
-                span: op.to_owned() + expr.span(),
-                op: UnaryOperator::Minus(op),
-                expr: Box::new(expr),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-
-        if self.lookahead_is(TokenType::Not) {
-            let op = consume_unchecked!(self);
-            let expr = self.arguments_expression()?;
-            return Ok(SingleExpression::Unary(UnaryExpression {
-                span: op.to_owned() + expr.span(),
-                op: UnaryOperator::Not(op),
-                expr: Box::new(expr),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-
-        self.arguments_expression()
-    }
-
-    /// ArgumentsExpression
-    ///   :  IdentifierExpression ArgumentList
-    ///   ;
-    ///
-    fn arguments_expression(&mut self) -> ParseResult<SingleExpression> {
-        // Eventually descend to ident
-        let left = self.member_dot_expression()?;
-        if self.lookahead_is(TokenType::LeftParen) {
-            let args = self.argument_list()?;
-            return Ok(SingleExpression::Arguments(ArgumentsExpression {
-                span: left.span() + args.span(),
-                ident: Box::new(left),
-                arguments: args,
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-
-        Ok(left)
-    }
-
-    /// ArgumentList
-    ///   :  '(' SingleExpression (',' SingleExpression)* ')'
-    ///   ;
-    fn argument_list(&mut self) -> ParseResult<ArgumentsList> {
-        let mut arguments = vec![];
-        let start = consume!(self, TokenType::LeftParen)?;
-        while !self.lookahead_is(TokenType::RightParen) {
-            let param = self.single_expression()?;
-            arguments.push(param);
-            if !self.lookahead_is(TokenType::Comma) {
-                break;
-            }
-            // Eat the comma
-            consume_unchecked!(self);
-        }
-        let end = consume!(self, TokenType::RightParen)?;
-        // return params
-        Ok(ArgumentsList {
-            span: start + end,
-            arguments,
-        })
-    }
-
-    /// MemberDotExpression
-    ///   : NewExpression '.' Identifier ArgumentsList?
-    ///   ;
-    fn member_dot_expression(&mut self) -> ParseResult<SingleExpression> {
-        // target.expression
-        let target = self.this()?;
-        if self.lookahead_is(TokenType::Dot) {
-            consume_unchecked!(self);
-            let expression = self.identifier_expression()?;
-            return Ok(SingleExpression::MemberDot(MemberDotExpression {
-                span: target.span() + expression.span(),
-                expression: Box::new(expression),
-                target: Box::new(target),
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-        Ok(target)
-    }
-
-    /// ThisExpression
-    ///   :  'this'
-    ///   ;
-    fn this(&mut self) -> ParseResult<SingleExpression> {
-        if self.lookahead_is(TokenType::This) {
-            let span = consume!(self, TokenType::This)?;
-            return Ok(SingleExpression::This(ThisExpression {
-                span,
-                ty: jswt_common::Type::Unknown,
-            }));
-        }
-        self.identifier_expression()
-    }
-
-    /// IdentifierExpression
-    ///   : Identifier
-    ///   ;
-    fn identifier_expression(&mut self) -> ParseResult<SingleExpression> {
-        if self.lookahead_is(TokenType::Identifier) {
-            let ident = ident!(self)?;
-            return Ok(SingleExpression::Identifier(IdentifierExpression {
-                ty: jswt_common::Type::Unknown,
-                span: ident.span.to_owned(),
-                ident,
-            }));
-        }
-        // If we can't find an ident, continue by
-        // trying to resolve an array literal
-        self.array_literal_expression()
-    }
-
-    /// ArrayLiteral
-    ///   :  '[' (SingleExpression ,)* ']'
-    ///   ;
-    fn array_literal_expression(&mut self) -> ParseResult<SingleExpression> {
-        if self.lookahead_is(TokenType::LeftBracket) {
-            let start = consume_unchecked!(self);
-
-            let mut elements = vec![];
-            while !self.lookahead_is(TokenType::RightBracket) {
-                let element = self.single_expression()?;
-                elements.push(element);
-                if !self.lookahead_is(TokenType::Comma) {
-                    break;
-                }
-                // Eat the comma
-                consume_unchecked!(self);
-            }
-
-            let end = consume!(self, TokenType::RightBracket)?;
-
-            return Ok(SingleExpression::Literal(Literal::Array(ArrayLiteral {
-                span: start + end,
-                elements,
-                ty: jswt_common::Type::Unknown,
-            })));
-        };
-
-        self.literal()
-    }
-
-    /// Literal
-    ///   : boolean
-    ///   | number
-    ///   | string
-    ///   ;
-    fn literal(&mut self) -> ParseResult<SingleExpression> {
-        let literal: Literal = match self.lookahead_type() {
-            Some(TokenType::True) => {
-                let span = consume_unchecked!(self);
-                BooleanLiteral {
-                    span,
-                    value: true,
-                    ty: jswt_common::Type::Unknown,
-                }
-                .into()
-            }
-            Some(TokenType::False) => {
-                let span = consume_unchecked!(self);
-                BooleanLiteral {
-                    span,
-                    value: false,
-                    ty: jswt_common::Type::Unknown,
-                }
-                .into()
-            }
-            Some(TokenType::String) => {
-                let span = consume_unchecked!(self);
-                let lexme = span.lexme();
-                StringLiteral {
-                    span,
-                    // Drop quoute characters from value
-                    value: &lexme[1..lexme.len() - 1],
-                    ty: jswt_common::Type::Unknown,
-                }
-                .into()
-            }
-            Some(TokenType::Integer) => {
-                let span = consume_unchecked!(self);
-                let lexme = span.lexme();
-                IntegerLiteral {
-                    span,
-                    // Should be safe to unwrap since
-                    // the tokenizer matched this
-                    value: lexme.parse().unwrap(),
-                    ty: jswt_common::Type::Unknown,
-                }
-                .into()
-            }
-            Some(TokenType::HexInteger) => {
-                let span = consume_unchecked!(self);
-                let without_prefix = span.lexme().trim_start_matches("0x");
-                IntegerLiteral {
-                    span,
-                    // Allow integer overflows in this specific instance
-                    value: u32::from_str_radix(without_prefix, 16).unwrap() as i32,
-                    ty: jswt_common::Type::Unknown,
-                }
-                .into()
-            }
-            Some(TokenType::Float) => {
-                let span = consume_unchecked!(self);
-                let lexme = span.lexme();
-                FloatingPointLiteral {
-                    span,
-                    value: lexme.parse::<f32>().unwrap(),
-                    ty: jswt_common::Type::Unknown,
-                }
-                .into()
-            }
-            _ => {
-                // TODO -rename this error.to something more descriptive
-                return Err(ParseError::NoViableAlternative {
-                    expected: vec![
-                        TokenType::Identifier,
-                        TokenType::Integer,
-                        TokenType::HexInteger,
-                        TokenType::Float,
-                        TokenType::String,
-                        TokenType::True,
-                        TokenType::False,
-                    ],
-                    actual: self.lookahead_type().unwrap(),
-                    span: self.lookahead_span(),
-                });
-            }
-        };
-
-        Ok(SingleExpression::Literal(literal))
-    }
-
     /// TypeAnnotation
-    ///   : ':' (PrimitiveType | ObjectType)
+    ///   : ':' TypeReference
     ///   ;
     fn type_annotation(&mut self) -> ParseResult<TypeAnnotation> {
         consume!(self, TokenType::Colon)?;
+        self.type_reference()
+    }
+
+    /// TypeReference
+    ///   : (PrimitiveType | ObjectType) ('[' ']')*
+    ///   ;
+    fn type_reference(&mut self) -> ParseResult<TypeAnnotation> {
         let name = ident!(self)?;
-        let mut ty = Type::Binding(name.value.clone());
+        let mut ty = Type::from_name(name.value.clone());
 
         let start = name.span();
         let mut end = name.span();
         while self.lookahead_is(TokenType::LeftBracket) {
             consume_unchecked!(self);
             end = consume!(self, TokenType::RightBracket)?;
-            // TODO
-            // ty = Type::Binding(ArrayType {
-            //     ident: Box::new(ty),
-            // })
-            ty = Type::Unknown;
+            ty = Type::array(ty);
         }
         Ok(TypeAnnotation {
             ty,
@@ -852,28 +531,75 @@ impl<'a> Parser<'a> {
         })
     }
 
+    /// Annotations
+    ///   : Annotation*
+    ///   ;
+    fn annotations(&mut self) -> ParseResult<Vec<Annotation>> {
+        let mut annotations = vec![];
+        while self.lookahead_is(TokenType::At) {
+            annotations.push(self.annotation()?);
+        }
+        Ok(annotations)
+    }
+
     /// Annotation
-    ///   : '@' Identifier ('(' SingleExpression ')')?
+    ///   : '@' Identifier ('(' AnnotationArgument (',' AnnotationArgument)* ')')?
     ///   ;
     fn annotation(&mut self) -> ParseResult<Annotation> {
         let start = consume!(self, TokenType::At)?;
         let ident = ident!(self)?;
 
         let mut end = ident.span.to_owned();
-        let mut expr = None;
+        let mut arguments = vec![];
         if self.lookahead_is(TokenType::LeftParen) {
             consume!(self, TokenType::LeftParen)?;
-            expr = Some(self.single_expression()?);
+            if !self.lookahead_is(TokenType::RightParen) {
+                loop {
+                    arguments.push(self.annotation_argument()?);
+                    if !self.lookahead_is(TokenType::Comma) {
+                        break;
+                    }
+                    consume_unchecked!(self);
+                }
+            }
             end = consume!(self, TokenType::RightParen)?;
         }
 
         Ok(Annotation {
             span: start + end,
             name: ident,
-            expr,
+            arguments,
         })
     }
 
+    /// AnnotationArgument
+    ///   : (Identifier '=')? SingleExpression
+    ///   ;
+    fn annotation_argument(&mut self) -> ParseResult<AnnotationArgument> {
+        let expression = self.single_expression()?;
+        let span = expression.span();
+        // Named arguments look like an assignment to an identifier
+        // so they are split apart after the fact
+        let argument = match expression {
+            SingleExpression::Assignment(BinaryExpression { left, right, .. })
+                if left.as_identifier().is_some() =>
+            {
+                let name = left.as_identifier().unwrap().ident.clone();
+                AnnotationArgument {
+                    span,
+                    name: Some(name),
+                    value: *right,
+                }
+            }
+            value => AnnotationArgument {
+                span,
+                name: None,
+                value,
+            },
+        };
+        Ok(argument)
+    }
+
     /// FormalParameterList
     ///   :  '(' FormalParameterArg ')'
     ///   |  '(' FormalParameterArg , FormalParameterArg ')'
@@ -898,18 +624,75 @@ impl<'a> Parser<'a> {
     }
 
     /// FormalParameterArg
-    ///   :  Ident TypeAnnotation
+    ///   :  Annotation* Ident TypeAnnotation
     ///   ;
     fn formal_parameter_arg(&mut self) -> ParseResult<FormalParameterArg> {
+        let annotations = self.annotations()?;
         let ident = ident!(self)?;
         let type_annotation = self.type_annotation()?;
         Ok(FormalParameterArg {
+            id: NodeId::DUMMY,
             span: ident.span() + type_annotation.span(),
+            annotations,
             ident,
             type_annotation,
         })
     }
 
+    /// Consumes the ';' terminating a statement. Following javascript's
+    /// automatic semicolon insertion rules a missing ';' is inserted when the
+    /// next token is on a new line, is a '}' or is the end of the file.
+    /// Returns the span of the last token of the statement.
+    pub(crate) fn semicolon(&mut self) -> ParseResult<Span> {
+        if self.lookahead_is(TokenType::Semi) {
+            return Ok(consume_unchecked!(self));
+        }
+
+        // A missing semicolon is reported but the statement before it is
+        // kept so the rest of the program is checked as written
+        if !self.can_insert_semicolon() {
+            self.errors.push(ParseError::MismatchedToken {
+                expected: TokenType::Semi,
+                actual: self.lookahead_type().unwrap_or(TokenType::Eof),
+                span: self.lookahead_span(),
+            });
+            return Ok(self
+                .previous
+                .clone()
+                .unwrap_or_else(|| self.lookahead_span()));
+        }
+
+        let end = self
+            .previous
+            .clone()
+            .unwrap_or_else(|| self.lookahead_span());
+        self.inserted_semicolons.push(Span::new(
+            end.file.clone(),
+            end.module.clone(),
+            end.end,
+            end.end,
+        ));
+        Ok(end)
+    }
+
+    /// Checks if a semicolon can be inserted before the lookahead token
+    pub(crate) fn can_insert_semicolon(&self) -> bool {
+        match &self.lookahead {
+            Some(token) => {
+                token.newline_before
+                    || token.kind == TokenType::RightBrace
+                    || token.kind == TokenType::Eof
+            }
+            None => true,
+        }
+    }
+
+    /// Moves the lookahead to the next token
+    fn advance(&mut self) {
+        self.previous = self.lookahead.as_ref().map(|token| token.span.clone());
+        self.lookahead = self.tokenizer.next_token();
+    }
+
     /// Return an owned token type value of the
     /// current lookahead token
     fn lookahead_type(&self) -> Option<TokenType> {
@@ -935,32 +718,87 @@ impl<'a> Parser<'a> {
         token.span.clone()
     }
 
-    /// Bail out of the current parse context
-    /// by throwing out tokens until we find a new
-    /// token that allows us to recover
-    fn handle_error_and_recover(&mut self, e: ParseError, recovery_set: &[TokenType]) {
-        let mut fixed_with_single_char_insertion = false;
-        if let ParseError::MismatchedToken { expected, .. } = &e {
-            match expected {
-                TokenType::Semi => fixed_with_single_char_insertion = true,
-                _ => {
-                    fixed_with_single_char_insertion = false;
-                }
-            }
-        };
+    /// Doc comment before the lookahead token
+    fn lookahead_doc(&self) -> Option<Span> {
+        self.lookahead.as_ref().and_then(|token| token.doc.clone())
+    }
 
+    /// Builds an error for a lookahead token that doesn't
+    /// match any of the expected alternatives
+    fn no_viable_alternative(&self, expected: &[TokenType]) -> ParseError {
+        ParseError::NoViableAlternative {
+            expected: expected.to_vec(),
+            actual: self.lookahead_type().unwrap_or(TokenType::Eof),
+            span: self.lookahead_span(),
+        }
+    }
+
+    /// Records the error then bails out of the current parse context by
+    /// throwing out tokens until the next synchronization point. Returns a
+    /// placeholder covering everything that was thrown out.
+    fn handle_error_and_recover(
+        &mut self,
+        e: ParseError,
+        start: Span,
+        sync: &[TokenType],
+        terminal: Option<TokenType>,
+    ) -> ErrorStatement {
         self.errors.push(e);
 
-        if fixed_with_single_char_insertion {
-            return;
+        let mut end = start.clone();
+        // Make sure we always make progress when the offending
+        // token is the one the failed element started with
+        if self.lookahead_span() == start && self.lookahead_type() != terminal {
+            end = consume_unchecked!(self);
         }
 
-        while self.lookahead.is_some() {
-            if recovery_set.contains(&self.lookahead.as_ref().unwrap().kind) {
+        if let Some(span) = self.synchronize(sync, terminal) {
+            end = span;
+        }
+        ErrorStatement {
+            id: NodeId::DUMMY,
+            span: start + end,
+        }
+    }
+
+    /// Throws out tokens until the lookahead is one of the synchronization
+    /// tokens, the terminal token or a semicolon which is consumed. Braces
+    /// are skipped in balanced pairs so that an error in a declaration
+    /// signature doesn't resume parsing inside its body. Returns the span
+    /// of the last token that was thrown out.
+    fn synchronize(&mut self, sync: &[TokenType], terminal: Option<TokenType>) -> Option<Span> {
+        let mut end = None;
+        let mut depth = 0;
+        while let Some(kind) = self.lookahead_type() {
+            if kind == TokenType::Eof {
                 break;
             }
-            self.lookahead = self.tokenizer.next_token();
+
+            if depth == 0 {
+                if Some(kind) == terminal || sync.contains(&kind) {
+                    break;
+                }
+                if kind == TokenType::Semi {
+                    end = Some(consume_unchecked!(self));
+                    break;
+                }
+            }
+
+            match kind {
+                TokenType::LeftBrace => depth += 1,
+                TokenType::RightBrace if depth == 0 => break,
+                TokenType::RightBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(consume_unchecked!(self));
+                        break;
+                    }
+                }
+                _ => {}
+            }
+            end = Some(consume_unchecked!(self));
         }
+        end
     }
 
     /// Get a reference to the tokenizer's errors.
@@ -972,12 +810,19 @@ impl<'a> Parser<'a> {
     pub fn parse_errors(&self) -> Vec<ParseError> {
         self.errors.clone()
     }
+
+    /// Empty spans at the end of the statements a semicolon was
+    /// inserted after. These are linted rather than reported as errors.
+    pub fn inserted_semicolons(&self) -> Vec<Span> {
+        self.inserted_semicolons.clone()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use jswt_assert::assert_debug_snapshot;
+    use jswt_assert::{assert_debug_snapshot, assert_snapshot};
+    use jswt_common::Atom;
 
     #[test]
     fn test_parse_empty_program() {
@@ -1229,6 +1074,16 @@ mod test {
         assert_eq!(parser.errors.len(), 0);
     }
 
+    #[test]
+    fn parse_variable_without_initializer() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("parse_variable_without_initializer", "let x: i32;");
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
     #[test]
     fn parse_postfix_unary_expression() {
         let mut tokenizer = Tokenizer::default();
@@ -1278,6 +1133,29 @@ mod test {
         assert_eq!(parser.errors.len(), 0);
     }
 
+    #[test]
+    fn parse_cast_expression() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("parse_cast_expression", "let x = 1 + y as f32 as i32;");
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn parse_cast_expression_binds_tighter_than_relational() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_cast_expression_binds_tighter_than_relational",
+            "x as f32 < 1.5;",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
     #[test]
     fn parse_member_dot_is_left_associative() {
         let mut tokenizer = Tokenizer::default();
@@ -1290,4 +1168,170 @@ mod test {
         assert_debug_snapshot!(actual);
         assert_eq!(parser.errors.len(), 0);
     }
+
+    #[test]
+    fn parse_recovers_at_next_statement() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_recovers_at_next_statement",
+            "function test() { let x = ) 1; let y = 2; }",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 1);
+    }
+
+    #[test]
+    fn parse_recovers_after_declaration_with_broken_signature() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_recovers_after_declaration_with_broken_signature",
+            "function a(x i32) { let y = ; } class A { b(: i32 { } c: i32; } function d() {}",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(parser.errors);
+        let elements = &actual.program.files[0].source_elements.source_elements;
+        assert_eq!(elements.len(), 3);
+        assert!(matches!(
+            elements[0],
+            SourceElement::Statement(StatementElement::Error(_))
+        ));
+        assert!(
+            matches!(&elements[1], SourceElement::ClassDeclaration(class) if class.body.class_elements.len() == 1)
+        );
+        assert!(matches!(elements[2], SourceElement::FunctionDeclaration(_)));
+    }
+
+    #[test]
+    fn parse_keeps_statement_with_missing_semicolon() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_keeps_statement_with_missing_semicolon",
+            "let x = 1 let y = x;",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_eq!(parser.errors.len(), 1);
+        let elements = &actual.program.files[0].source_elements.source_elements;
+        assert_eq!(elements.len(), 2);
+        assert!(elements.iter().all(|element| matches!(
+            element,
+            SourceElement::Statement(StatementElement::Variable(_))
+        )));
+    }
+
+    #[test]
+    fn parse_error_reports_expected_alternatives() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("parse_error_reports_expected_alternatives", "let x 5;");
+        let mut parser = Parser::new(&mut tokenizer);
+        parser.parse();
+        assert_debug_snapshot!(parser.errors);
+    }
+
+    #[test]
+    fn parse_inserts_semicolons_at_line_breaks() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_inserts_semicolons_at_line_breaks",
+            "let x = 1\nlet y = x\n    + 2\nfoo(y)",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn parse_restricted_productions_end_at_line_breaks() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_restricted_productions_end_at_line_breaks",
+            "function test() { while (x) { break\ncontinue\n} let y = x\ny++\nreturn\ny }",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        let actual = parser.parse();
+        assert_debug_snapshot!(actual);
+        assert_eq!(parser.errors.len(), 0);
+    }
+
+    #[test]
+    fn parse_error_on_missing_semicolon_without_line_break() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_error_on_missing_semicolon_without_line_break",
+            "let x = 1 let y = 2;",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        parser.parse();
+        assert_debug_snapshot!(parser.errors);
+    }
+
+    #[test]
+    fn parse_error_on_jump_outside_loop() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_error_on_jump_outside_loop",
+            "function test() { break; while (x) { continue; } continue; }",
+        );
+        let mut parser = Parser::new(&mut tokenizer);
+        parser.parse();
+        assert_debug_snapshot!(parser.errors);
+    }
+
+    #[test]
+    fn parse_records_inserted_semicolons() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str("parse_records_inserted_semicolons", "let x = 1\nlet y = 2;");
+        let mut parser = Parser::new(&mut tokenizer);
+        parser.parse();
+        assert!(parser.errors.is_empty());
+        assert_debug_snapshot!(parser.inserted_semicolons);
+    }
+
+    #[test]
+    fn parse_walks_every_node_of_the_tree() {
+        use jswt_ast::visit::Visitor;
+
+        #[derive(Default)]
+        struct Collector {
+            params: Vec<Atom>,
+            identifiers: Vec<Atom>,
+        }
+
+        impl Visitor for Collector {
+            fn visit_formal_parameter(&mut self, node: &FormalParameterArg) {
+                self.params.push(node.ident.value.clone());
+            }
+
+            fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
+                self.identifiers.push(node.ident.value.clone());
+            }
+        }
+
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "parse_walks_every_node_of_the_tree",
+            "function test(a: i32, b: i32) { let c = [a, b]; }",
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut collector = Collector::default();
+        collector.visit_program(&ast.program);
+
+        assert_eq!(collector.params, vec!["a", "b"]);
+        assert_eq!(collector.identifiers, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_parse_serializes_to_json() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_parse_serializes_to_json",
+            "function f(x: i32): i32 { return -x as i32 + 1; }",
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        assert_snapshot!(serde_json::to_string_pretty(&ast).unwrap());
+    }
 }
@@ -0,0 +1,298 @@
+use crate::items::{element_range, item_name};
+use crate::{normalize, Database};
+use jswt_ast::{Ast, SourceElement};
+use jswt_codegen::CodeGenerator;
+use jswt_common::{Atom, Span};
+use jswt_hir_lowering::HirLoweringContext;
+use jswt_mir_lowering::MirLoweringContext;
+use jswt_parser::{ParseError, Parser};
+use jswt_semantics::{
+    FileUsage, GlobalSemanticResolver, LintConfig, Linter, LocalSemanticResolver, SemanticError,
+    TypeChecker,
+};
+use jswt_symbols::{BindingsTable, ResolutionTable, ScopedSymbolTable};
+use jswt_tokenizer::{Import, Tokenizer, TokenizerError};
+use jswt_types::TypeTable;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Sources checked together as a single program
+#[derive(Debug)]
+pub struct Analysis {
+    pub ast: Ast,
+    pub bindings: BindingsTable,
+    pub symbols: ScopedSymbolTable,
+    pub types: TypeTable,
+    /// Declarations that identifiers resolve to
+    pub resolutions: ResolutionTable,
+    pub tokenizer_errors: Vec<TokenizerError>,
+    pub parse_errors: Vec<ParseError>,
+    /// Semicolons the parser inserted, reported by the linter
+    pub inserted_semicolons: Vec<Span>,
+    /// Import directives with normalized paths, also reported by the linter
+    pub imports: Vec<Import>,
+    pub errors: Vec<SemanticError>,
+}
+
+impl Analysis {
+    pub fn has_errors(&self) -> bool {
+        !self.tokenizer_errors.is_empty()
+            || !self.parse_errors.is_empty()
+            || !self.errors.is_empty()
+    }
+
+    /// Drops the errors reported on other sources
+    fn retain_errors(&mut self, source_name: &str) {
+        self.tokenizer_errors.retain(|error| match error {
+            TokenizerError::UnreconizedToken { file, .. } => file == source_name,
+            TokenizerError::UnexpectedEof => true,
+        });
+        self.parse_errors
+            .retain(|error| error.span().file == source_name);
+        self.errors.retain(|error| error.span().file == source_name);
+    }
+}
+
+/// Declarations of every file in the program parsed once. Items
+/// are checked against a copy of it instead of parsing the rest of
+/// the program again.
+#[derive(Debug)]
+pub struct Environment {
+    pub ast: Ast,
+}
+
+/// Lints of an item or of the statements of a file, along
+/// with what they use from the rest of the program
+#[derive(Debug, Default, PartialEq)]
+pub struct Lints {
+    pub errors: Vec<SemanticError>,
+    pub usage: FileUsage,
+}
+
+/// Name the source of an item is parsed under
+pub fn item_source_name(path: &Path, name: Atom) -> String {
+    format!("{}#{}", path.display(), name)
+}
+
+/// Name the statements of a file are parsed under. Items are
+/// named after identifiers so the name can't be taken by one.
+pub fn statements_source_name(path: &Path) -> String {
+    format!("{}#<statements>", path.display())
+}
+
+/// Name the declarations of a file are parsed under
+fn declarations_source_name(path: &Path) -> String {
+    format!("{}/declarations", path.display())
+}
+
+/// File a source checked on its own was taken from
+pub(crate) fn source_path(source_name: &str) -> PathBuf {
+    let path = match source_name.strip_suffix("/declarations") {
+        Some(path) => path,
+        None => source_name
+            .rsplit_once('#')
+            .map_or(source_name, |(path, _)| path),
+    };
+    PathBuf::from(path)
+}
+
+/// Parses the sources in order as more files of the program
+/// and runs semantic analysis over it
+fn check(mut ast: Ast, sources: Vec<(String, Arc<String>)>) -> Analysis {
+    let mut tokenizer = Tokenizer::default();
+    tokenizer.set_follow_imports(false);
+    for (name, source) in sources {
+        tokenizer.enqueue_source_str(&name, source.as_str());
+    }
+
+    let mut parser = Parser::new(&mut tokenizer);
+    parser.parse_into(&mut ast);
+    let tokenizer_errors = parser.tokenizer_errors();
+    let parse_errors = parser.parse_errors();
+    let inserted_semicolons = parser.inserted_semicolons();
+    let imports = tokenizer
+        .imports()
+        .iter()
+        .cloned()
+        .map(|mut import| {
+            import.path = normalize(&import.path);
+            import
+        })
+        .collect();
+
+    let mut bindings = BindingsTable::default();
+    let mut symbols = ScopedSymbolTable::default();
+    let mut errors = vec![];
+
+    let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+    global.resolve(&ast);
+    errors.append(global.errors());
+
+    let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+    local.resolve(&ast);
+    errors.append(local.errors());
+    let resolutions = local.resolutions;
+
+    let mut checker = TypeChecker::new(&mut bindings, &mut symbols, &resolutions);
+    checker.resolve(&ast);
+    errors.append(checker.errors());
+    let types = checker.types;
+
+    Analysis {
+        ast,
+        bindings,
+        symbols,
+        types,
+        resolutions,
+        tokenizer_errors,
+        parse_errors,
+        inserted_semicolons,
+        imports,
+        errors,
+    }
+}
+
+/// Parses the declarations of every file, one file of declarations per file
+pub(crate) fn environment(db: &mut Database) -> Environment {
+    let mut tokenizer = Tokenizer::default();
+    tokenizer.set_follow_imports(false);
+    for file in db.files().iter() {
+        let mut declarations = String::new();
+        for declaration in db.declarations(file).iter() {
+            declarations += &declaration.source;
+            declarations += "\n";
+        }
+        tokenizer.enqueue_source_str(&declarations_source_name(file), declarations);
+    }
+
+    let ast = Parser::new(&mut tokenizer).parse();
+    Environment { ast }
+}
+
+/// Checks an item against the declarations of the rest of the program.
+/// Only the errors reported on the item are kept.
+pub(crate) fn analyze_item(db: &mut Database, path: &Path, name: Atom) -> Option<Analysis> {
+    let source = db.item_source(path, name.clone())?;
+
+    // The item is declared by its own source
+    let mut ast = db.environment().ast.clone();
+    let declarations = declarations_source_name(path);
+    for file in ast.program.files.iter_mut() {
+        if file.span.file == declarations.as_str() {
+            file.source_elements
+                .source_elements
+                .retain(|element| item_name(element).as_ref() != Some(&name));
+        }
+    }
+
+    let source_name = item_source_name(path, name);
+    let mut analysis = check(ast, vec![(source_name.clone(), source)]);
+    analysis.retain_errors(&source_name);
+    Some(analysis)
+}
+
+/// Checks the statements of a file outside of its items against the
+/// declarations of the program. Items are blanked out of the source
+/// so the offsets of the statements are the same as in the file.
+pub(crate) fn analyze_statements(db: &mut Database, path: &Path) -> Analysis {
+    let text = db.source_text(path);
+    let parsed = db.parse(path);
+    let mut items = vec![];
+    for element in parsed.file.source_elements.source_elements.iter() {
+        if item_name(element).is_some() {
+            items.push(element_range(element));
+        }
+    }
+
+    let mut statements = String::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        if c == '\n' || !items.iter().any(|item| item.contains(&offset)) {
+            statements.push(c);
+        } else {
+            statements.extend(std::iter::repeat(' ').take(c.len_utf8()));
+        }
+    }
+
+    // The statements are declared by their own source
+    let mut ast = db.environment().ast.clone();
+    let declarations = declarations_source_name(path);
+    for file in ast.program.files.iter_mut() {
+        if file.span.file == declarations.as_str() {
+            file.source_elements
+                .source_elements
+                .retain(|element| item_name(element).is_some());
+        }
+    }
+
+    let source_name = statements_source_name(path);
+    let mut analysis = check(ast, vec![(source_name.clone(), Arc::new(statements))]);
+    analysis.retain_errors(&source_name);
+    analysis
+}
+
+/// Lints the item or statements an analysis was made for
+pub(crate) fn lint(analysis: &Analysis) -> Lints {
+    let mut linter = Linter::new(
+        LintConfig::default(),
+        &analysis.symbols,
+        &analysis.resolutions,
+    );
+    linter.set_inserted_semicolons(analysis.inserted_semicolons.clone());
+    let usage = linter.lint_file(&analysis.ast);
+    Lints {
+        errors: linter.errors().clone(),
+        usage,
+    }
+}
+
+/// Checks and lints every file of the program
+pub(crate) fn analyze_program(db: &mut Database) -> Analysis {
+    let files = db.files();
+    let sources = files
+        .iter()
+        .map(|file| (file.to_str().unwrap().to_owned(), db.source_text(file)))
+        .collect();
+    let mut analysis = check(Ast::default(), sources);
+
+    let mut linter = Linter::new(
+        LintConfig::default(),
+        &analysis.symbols,
+        &analysis.resolutions,
+    );
+    linter.set_inserted_semicolons(analysis.inserted_semicolons.clone());
+    linter.set_imports(analysis.imports.clone());
+    linter.lint(&analysis.ast);
+    analysis.errors.append(linter.errors());
+    analysis
+}
+
+/// Generates the functions an item is lowered into
+pub(crate) fn generate(analysis: &Analysis) -> String {
+    let ast = Ast::new(analysis.ast.program.clone());
+    let mut hir = HirLoweringContext::new(&analysis.bindings, &analysis.symbols, &analysis.types);
+    let ast = hir.lower(ast);
+    let mut mir = MirLoweringContext::new(&analysis.bindings, &analysis.symbols, &analysis.types);
+    let ast = mir.lower(ast);
+
+    // The item is the last file in the program
+    let item = ast.program.files.last().unwrap();
+    let names: Vec<Atom> = item
+        .source_elements
+        .source_elements
+        .iter()
+        .filter_map(|element| match element {
+            SourceElement::FunctionDeclaration(function) => Some(function.ident.value.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut generator = CodeGenerator::new(analysis.types.clone(), analysis.resolutions.clone());
+    let module = generator.generate_module(&ast);
+    module
+        .functions
+        .iter()
+        .filter(|function| names.iter().any(|name| *name == function.name.as_ref()))
+        .map(|function| module.function_as_wat(function, false))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,279 @@
+use crate::Analysis;
+use jswt_ast::{visit::*, *};
+use jswt_common::{Atom, Identifiable, NodeId, Span, Spannable};
+use jswt_types::{FunctionType, ObjectType, Type};
+use std::collections::HashMap;
+
+/// Something in the program that can be declared and referred to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// Functions, variables and parameters by the id of their declaration
+    Def(NodeId),
+    Class(Atom),
+    /// Field or method of a class
+    Member(Atom, Atom),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub name: Atom,
+    /// Span of the name where it's declared
+    pub span: Span,
+    /// Declaration as it would be written in source
+    pub detail: String,
+    pub ty: Type,
+}
+
+/// A declaration of or a reference to a target
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub span: Span,
+    pub target: Target,
+}
+
+/// Where everything in the program is declared and referred to
+#[derive(Debug, Default)]
+pub struct Index {
+    pub definitions: HashMap<Target, Definition>,
+    pub occurrences: Vec<Occurrence>,
+}
+
+impl Index {
+    pub fn new(analysis: &Analysis) -> Self {
+        let mut builder = IndexBuilder {
+            analysis,
+            index: Index::default(),
+            class: None,
+        };
+        builder.visit_program(&analysis.ast.program);
+        builder.index
+    }
+
+    /// The innermost occurrence in the file at the given offset
+    pub fn occurrence_at(&self, file: &str, offset: usize) -> Option<&Occurrence> {
+        self.occurrences
+            .iter()
+            .filter(|occurrence| {
+                let span = &occurrence.span;
+                span.file == file && span.start <= offset && offset <= span.end
+            })
+            .min_by_key(|occurrence| occurrence.span.end - occurrence.span.start)
+    }
+
+    pub fn references<'a>(&'a self, target: &'a Target) -> impl Iterator<Item = &'a Span> {
+        self.occurrences
+            .iter()
+            .filter(move |occurrence| occurrence.target == *target)
+            .map(|occurrence| &occurrence.span)
+    }
+
+    /// Fields and methods of a class
+    pub fn members(&self, class: Atom) -> Vec<&Definition> {
+        let mut members: Vec<_> = self
+            .definitions
+            .iter()
+            .filter_map(|(target, definition)| match target {
+                Target::Member(owner, _) if *owner == class => Some(definition),
+                _ => None,
+            })
+            .collect();
+        members.sort_by_key(|definition| definition.span.start);
+        members
+    }
+}
+
+struct IndexBuilder<'a> {
+    analysis: &'a Analysis,
+    index: Index,
+    /// Class whose members are being visited
+    class: Option<Atom>,
+}
+
+impl<'a> IndexBuilder<'a> {
+    fn define(&mut self, target: Target, ident: &Identifier, detail: String, ty: Type) {
+        self.index.occurrences.push(Occurrence {
+            span: ident.span(),
+            target: target.clone(),
+        });
+        let definition = Definition {
+            name: ident.value.clone(),
+            span: ident.span(),
+            detail,
+            ty,
+        };
+        self.index.definitions.entry(target).or_insert(definition);
+    }
+
+    fn refer(&mut self, span: Span, target: Target) {
+        self.index.occurrences.push(Occurrence { span, target });
+    }
+
+    /// Class names in type annotations refer to the class
+    fn type_annotation(&mut self, annotation: &TypeAnnotation) {
+        let mut ty = &annotation.ty;
+        while let Type::Object(ObjectType::Array(inner)) = ty {
+            ty = inner;
+        }
+        if let Type::Object(ObjectType::Reference(name)) = ty {
+            let span = &annotation.span;
+            let span = Span::new(
+                span.file.clone(),
+                span.module.clone(),
+                span.start,
+                span.start + name.len(),
+            );
+            self.refer(span, Target::Class(name.as_ref().into()));
+        }
+    }
+
+    fn function_type(params: &FormalParameterList, returns: &Option<TypeAnnotation>) -> Type {
+        Type::Function(FunctionType {
+            params: params
+                .parameters
+                .iter()
+                .map(|param| param.type_annotation.ty.clone())
+                .collect(),
+            returns: Box::new(
+                returns
+                    .as_ref()
+                    .map_or(Type::Void, |returns| returns.ty.clone()),
+            ),
+        })
+    }
+}
+
+/// Parameters and return type of a function as they're written in source
+pub fn signature(params: &FormalParameterList, returns: &Option<TypeAnnotation>) -> String {
+    let params: Vec<_> = params
+        .parameters
+        .iter()
+        .map(|param| format!("{}: {}", param.ident.value, param.type_annotation.ty))
+        .collect();
+    match returns {
+        Some(returns) => format!("({}): {}", params.join(", "), returns.ty),
+        None => format!("({})", params.join(", ")),
+    }
+}
+
+impl<'a> Visitor for IndexBuilder<'a> {
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        let detail = format!(
+            "function {}{}",
+            node.ident.value,
+            signature(&node.params, &node.returns)
+        );
+        let ty = Self::function_type(&node.params, &node.returns);
+        self.define(Target::Def(node.id), &node.ident, detail, ty);
+        if let Some(returns) = &node.returns {
+            self.type_annotation(returns);
+        }
+        walk_function_declaration(self, node);
+    }
+
+    fn visit_formal_parameter(&mut self, node: &FormalParameterArg) {
+        let ty = node.type_annotation.ty.clone();
+        let detail = format!("{}: {}", node.ident.value, ty);
+        self.define(Target::Def(node.id), &node.ident, detail, ty);
+        self.type_annotation(&node.type_annotation);
+    }
+
+    fn visit_variable_statement(&mut self, node: &VariableStatement) {
+        let AssignableElement::Identifier(ident) = &node.target;
+        let ty = match (&node.type_annotation, &node.expression) {
+            (Some(annotation), _) => annotation.ty.clone(),
+            (None, Some(expression)) => self.analysis.types.ty(expression.id()),
+            (None, None) => Type::Unknown,
+        };
+        let modifier = match node.modifier {
+            VariableModifier::Let(_) => "let",
+            VariableModifier::Const(_) => "const",
+        };
+        let detail = format!("{} {}: {}", modifier, ident.value, ty);
+        self.define(Target::Def(node.id), ident, detail, ty);
+        if let Some(annotation) = &node.type_annotation {
+            self.type_annotation(annotation);
+        }
+        if let Some(expression) = &node.expression {
+            self.visit_single_expression(expression);
+        }
+    }
+
+    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
+        let name = &node.ident.value;
+        let detail = format!("class {}", name);
+        let ty = Type::from_name(name.clone());
+        self.define(Target::Class(name.clone()), &node.ident, detail, ty);
+        self.class = Some(name.clone());
+        walk_class_declaration(self, node);
+        self.class = None;
+    }
+
+    fn visit_class_field_declaration(&mut self, node: &ClassFieldElement) {
+        if let Some(class) = &self.class {
+            let ty = node.type_annotation.ty.clone();
+            let readonly = if node.readonly { "readonly " } else { "" };
+            let detail = format!("{}{}: {}", readonly, node.ident.value, ty);
+            self.define(
+                Target::Member(class.clone(), node.ident.value.clone()),
+                &node.ident,
+                detail,
+                ty,
+            );
+        }
+        self.type_annotation(&node.type_annotation);
+    }
+
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        if let Some(class) = &self.class {
+            let detail = format!(
+                "{}{}",
+                node.ident.value,
+                signature(&node.params, &node.returns)
+            );
+            let ty = Self::function_type(&node.params, &node.returns);
+            self.define(
+                Target::Member(class.clone(), node.ident.value.clone()),
+                &node.ident,
+                detail,
+                ty,
+            );
+        }
+        if let Some(returns) = &node.returns {
+            self.type_annotation(returns);
+        }
+        walk_class_method_declaration(self, node);
+    }
+
+    fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
+        if let Some(def) = self.analysis.resolutions.get(node.id) {
+            self.refer(node.ident.span(), Target::Def(def.0));
+        }
+    }
+
+    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
+        self.visit_single_expression(&node.target);
+        let class = self.analysis.types.ty(node.target.id()).binding_name();
+        if let (Some(class), Some(member)) = (class, node.expression.as_identifier()) {
+            let target = Target::Member(class.as_ref().into(), member.ident.value.clone());
+            self.refer(member.ident.span(), target);
+        }
+    }
+
+    fn visit_new(&mut self, node: &NewExpression) {
+        match node.expression.as_ref() {
+            SingleExpression::Arguments(call) => match call.ident.as_identifier() {
+                Some(class) => {
+                    self.refer(class.ident.span(), Target::Class(class.ident.value.clone()));
+                    call.arguments.walk(self);
+                }
+                None => walk_new(self, node),
+            },
+            _ => walk_new(self, node),
+        }
+    }
+
+    fn visit_cast_expression(&mut self, node: &CastExpression) {
+        self.type_annotation(&node.target);
+        walk_cast_expression(self, node);
+    }
+}
@@ -0,0 +1,79 @@
+use jswt_ast::{Annotation, ClassElement, SourceElement};
+use jswt_common::{Atom, Span, Spannable};
+use std::ops::Range;
+
+/// Source of a top level element as far as the rest of the
+/// program is concerned. Function and method bodies are left out
+/// so editing one doesn't change the declarations of the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    /// Name of the item, statements don't have one
+    pub name: Option<Atom>,
+    pub source: String,
+}
+
+/// Functions and classes are items, they are checked
+/// and generated separately from the rest of the file
+pub(crate) fn item_name(element: &SourceElement) -> Option<Atom> {
+    match element {
+        SourceElement::FunctionDeclaration(function) => Some(function.ident.value.clone()),
+        SourceElement::ClassDeclaration(class) => Some(class.ident.value.clone()),
+        SourceElement::Statement(_) => None,
+    }
+}
+
+/// Offsets of the source of an element including its annotations
+pub(crate) fn element_range(element: &SourceElement) -> Range<usize> {
+    let span = element.span();
+    let start = match element {
+        SourceElement::FunctionDeclaration(function) => {
+            start(&function.decorators.annotations, &span)
+        }
+        SourceElement::ClassDeclaration(class) => start(&class.annotations, &span),
+        SourceElement::Statement(_) => span.start,
+    };
+    start..span.end
+}
+
+/// Builds the declaration of an element by replacing the bodies
+/// of its functions, constructors and methods with empty blocks
+pub(crate) fn declaration(source: &str, element: &SourceElement) -> Declaration {
+    let bodies: Vec<Span> = match element {
+        SourceElement::FunctionDeclaration(function) => vec![function.body.span()],
+        SourceElement::ClassDeclaration(class) => class
+            .body
+            .class_elements
+            .iter()
+            .filter_map(|element| match element {
+                ClassElement::Constructor(constructor) => Some(constructor.body.span()),
+                ClassElement::Method(method) => Some(method.body.span()),
+                ClassElement::Field(_) => None,
+            })
+            .collect(),
+        SourceElement::Statement(_) => vec![],
+    };
+
+    let range = element_range(element);
+    let mut stub = String::new();
+    let mut offset = range.start;
+    for body in bodies {
+        stub += &source[offset..body.start];
+        stub += "{}";
+        offset = body.end;
+    }
+    stub += &source[offset..range.end];
+
+    Declaration {
+        name: item_name(element),
+        source: stub,
+    }
+}
+
+fn start(annotations: &[Annotation], span: &Span) -> usize {
+    annotations
+        .iter()
+        .map(|annotation| annotation.span.start)
+        .chain([span.start])
+        .min()
+        .unwrap()
+}
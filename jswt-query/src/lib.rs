@@ -0,0 +1,749 @@
+//! Demand driven compilation. Results are computed when they're asked for
+//! and memoized along with the queries they read. When an input changes only
+//! the results that read something that actually changed are recomputed,
+//! so editing the body of a function only checks and generates that function.
+
+mod analysis;
+mod index;
+mod items;
+mod memo;
+
+pub use analysis::{item_source_name, statements_source_name, Analysis, Environment, Lints};
+pub use index::{signature, Definition, Index, Occurrence, Target};
+pub use items::Declaration;
+pub use memo::{QueryKey, Revision};
+
+use items::{element_range, item_name};
+use jswt_ast::File;
+use jswt_common::Atom;
+use jswt_parser::{ParseError, Parser};
+use jswt_semantics::{unused_imports, LintConfig, SemanticError};
+use jswt_tokenizer::{Import, Tokenizer, TokenizerError};
+use memo::{Input, Memo};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// A source file parsed on its own without following its imports
+#[derive(Debug)]
+pub struct ParsedFile {
+    pub file: File,
+    /// Import directives of the source with normalized paths
+    pub imports: Vec<Import>,
+    pub tokenizer_errors: Vec<TokenizerError>,
+    pub parse_errors: Vec<ParseError>,
+}
+
+/// Memoized results of a query keyed by its arguments
+struct Query<K, V> {
+    memos: HashMap<K, Memo<V>>,
+    compute: fn(&mut Database, &K) -> V,
+    /// Recomputed values equal to the old ones don't count as a change
+    eq: fn(&V, &V) -> bool,
+}
+
+impl<K, V> Query<K, V> {
+    fn new(compute: fn(&mut Database, &K) -> V, eq: fn(&V, &V) -> bool) -> Self {
+        Self {
+            memos: HashMap::new(),
+            compute,
+            eq,
+        }
+    }
+}
+
+type ItemKey = (PathBuf, Atom);
+
+pub struct Database {
+    revision: Revision,
+    roots: Input<Arc<Vec<PathBuf>>>,
+    sources: HashMap<PathBuf, Input<Arc<String>>>,
+    parse: Query<PathBuf, Arc<ParsedFile>>,
+    files: Query<(), Arc<Vec<PathBuf>>>,
+    items: Query<PathBuf, Arc<Vec<Atom>>>,
+    item_source: Query<ItemKey, Option<Arc<String>>>,
+    declarations: Query<PathBuf, Arc<Vec<Declaration>>>,
+    environment: Query<(), Arc<Environment>>,
+    analysis: Query<ItemKey, Option<Arc<Analysis>>>,
+    check_item: Query<ItemKey, Arc<Vec<SemanticError>>>,
+    lint_item: Query<ItemKey, Arc<Lints>>,
+    codegen_item: Query<ItemKey, Option<Arc<String>>>,
+    statements: Query<PathBuf, Arc<Analysis>>,
+    check_statements: Query<PathBuf, Arc<Vec<SemanticError>>>,
+    lint_statements: Query<PathBuf, Arc<Lints>>,
+    lint_file: Query<PathBuf, Arc<Vec<SemanticError>>>,
+    program: Query<(), Arc<Analysis>>,
+    index: Query<(), Arc<Index>>,
+    /// Queries read by each of the queries being computed
+    stack: Vec<Vec<QueryKey>>,
+    /// Queries that were computed rather than reused
+    executed: Vec<QueryKey>,
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self {
+            revision: 0,
+            roots: Input {
+                value: Arc::new(vec![]),
+                changed_at: 0,
+            },
+            sources: HashMap::new(),
+            parse: Query::new(|db, path| Arc::new(db.compute_parse(path)), Arc::ptr_eq),
+            files: Query::new(|db, _| Arc::new(db.compute_files()), PartialEq::eq),
+            items: Query::new(|db, path| Arc::new(db.compute_items(path)), PartialEq::eq),
+            item_source: Query::new(
+                |db, (path, name)| db.compute_item_source(path, name.clone()).map(Arc::new),
+                PartialEq::eq,
+            ),
+            declarations: Query::new(
+                |db, path| Arc::new(db.compute_declarations(path)),
+                PartialEq::eq,
+            ),
+            environment: Query::new(|db, _| Arc::new(analysis::environment(db)), |_, _| false),
+            analysis: Query::new(
+                |db, (path, name)| analysis::analyze_item(db, path, name.clone()).map(Arc::new),
+                // Analyses aren't compared, queries reading them compare their own results
+                |_, _| false,
+            ),
+            check_item: Query::new(
+                |db, (path, name)| {
+                    let errors = db
+                        .analysis(path, name.clone())
+                        .map(|analysis| analysis.errors.clone());
+                    Arc::new(errors.unwrap_or_default())
+                },
+                PartialEq::eq,
+            ),
+            lint_item: Query::new(
+                |db, (path, name)| {
+                    let lints = db
+                        .analysis(path, name.clone())
+                        .map(|analysis| analysis::lint(&analysis));
+                    Arc::new(lints.unwrap_or_default())
+                },
+                PartialEq::eq,
+            ),
+            codegen_item: Query::new(
+                |db, (path, name)| {
+                    let analysis = db.analysis(path, name.clone())?;
+                    if analysis.has_errors() {
+                        return None;
+                    }
+                    Some(Arc::new(analysis::generate(&analysis)))
+                },
+                PartialEq::eq,
+            ),
+            statements: Query::new(
+                |db, path| Arc::new(analysis::analyze_statements(db, path)),
+                |_, _| false,
+            ),
+            check_statements: Query::new(
+                |db, path| Arc::new(db.statements(path).errors.clone()),
+                PartialEq::eq,
+            ),
+            lint_statements: Query::new(
+                |db, path| Arc::new(analysis::lint(&db.statements(path))),
+                PartialEq::eq,
+            ),
+            lint_file: Query::new(
+                |db, path| Arc::new(db.compute_lint_file(path)),
+                PartialEq::eq,
+            ),
+            program: Query::new(
+                |db, _| Arc::new(analysis::analyze_program(db)),
+                |_, _| false,
+            ),
+            index: Query::new(|db, _| Arc::new(Index::new(&db.program())), |_, _| false),
+            stack: vec![],
+            executed: vec![],
+        }
+    }
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn revision(&self) -> Revision {
+        self.revision
+    }
+
+    /// Sets the files compilation starts from. Files they import
+    /// are part of the program as well.
+    pub fn set_roots(&mut self, roots: Vec<PathBuf>) {
+        let roots: Vec<_> = roots.iter().map(|root| normalize(root)).collect();
+        if *self.roots.value == roots {
+            return;
+        }
+        self.revision += 1;
+        self.roots = Input {
+            value: Arc::new(roots),
+            changed_at: self.revision,
+        };
+    }
+
+    /// Sets the text of a source file. Files that are never set
+    /// are read from disk the first time they're needed.
+    pub fn set_source_text(&mut self, path: &Path, text: String) {
+        let path = normalize(path);
+        if let Some(source) = self.sources.get(&path) {
+            if *source.value == text {
+                return;
+            }
+        }
+        self.revision += 1;
+        let source = Input {
+            value: Arc::new(text),
+            changed_at: self.revision,
+        };
+        self.sources.insert(path, source);
+    }
+
+    /// Queries that were computed since the last call, in the order they finished
+    pub fn take_executed(&mut self) -> Vec<QueryKey> {
+        std::mem::take(&mut self.executed)
+    }
+
+    pub fn roots(&mut self) -> Arc<Vec<PathBuf>> {
+        self.read(QueryKey::Roots);
+        self.roots.value.clone()
+    }
+
+    pub fn source_text(&mut self, path: &Path) -> Arc<String> {
+        let path = normalize(path);
+        self.read(QueryKey::SourceText(path.clone()));
+        self.load_source(&path);
+        self.sources[&path].value.clone()
+    }
+
+    pub fn parse(&mut self, path: &Path) -> Arc<ParsedFile> {
+        let path = normalize(path);
+        self.get(QueryKey::Parse(path.clone()), path, |db| &mut db.parse)
+    }
+
+    /// Every file in the program, the roots followed by the files they import
+    pub fn files(&mut self) -> Arc<Vec<PathBuf>> {
+        self.get(QueryKey::Files, (), |db| &mut db.files)
+    }
+
+    /// Names of the functions and classes declared in a file
+    pub fn items(&mut self, path: &Path) -> Arc<Vec<Atom>> {
+        let path = normalize(path);
+        self.get(QueryKey::Items(path.clone()), path, |db| &mut db.items)
+    }
+
+    /// Source of the item, unchanged as long as the item itself is
+    /// even if the code around it moves it
+    pub fn item_source(&mut self, path: &Path, name: Atom) -> Option<Arc<String>> {
+        let path = normalize(path);
+        let key = QueryKey::ItemSource(path.clone(), name.clone());
+        self.get(key, (path, name), |db| &mut db.item_source)
+    }
+
+    /// Where the source of the item is in its file. Offsets of the
+    /// errors of an item are relative to its start.
+    pub fn item_range(&mut self, path: &Path, name: &Atom) -> Option<Range<usize>> {
+        let parsed = self.parse(path);
+        let elements = &parsed.file.source_elements.source_elements;
+        let element = elements
+            .iter()
+            .find(|element| item_name(element).as_ref() == Some(name))?;
+        Some(element_range(element))
+    }
+
+    /// What the file declares to the rest of the program
+    pub fn declarations(&mut self, path: &Path) -> Arc<Vec<Declaration>> {
+        let path = normalize(path);
+        self.get(QueryKey::Declarations(path.clone()), path, |db| {
+            &mut db.declarations
+        })
+    }
+
+    /// Declarations of the whole program, parsed once for every item
+    pub fn environment(&mut self) -> Arc<Environment> {
+        self.get(QueryKey::Environment, (), |db| &mut db.environment)
+    }
+
+    pub fn analysis(&mut self, path: &Path, name: Atom) -> Option<Arc<Analysis>> {
+        let path = normalize(path);
+        let key = QueryKey::Analysis(path.clone(), name.clone());
+        self.get(key, (path, name), |db| &mut db.analysis)
+    }
+
+    /// Semantic errors of an item. Their spans are offsets into
+    /// the source of the item rather than the file.
+    pub fn check_item(&mut self, path: &Path, name: Atom) -> Arc<Vec<SemanticError>> {
+        let path = normalize(path);
+        let key = QueryKey::CheckItem(path.clone(), name.clone());
+        self.get(key, (path, name), |db| &mut db.check_item)
+    }
+
+    /// Lints of an item that only depend on the item itself, along with
+    /// what it uses. Their spans are offsets into the source of the item.
+    pub fn lint_item(&mut self, path: &Path, name: Atom) -> Arc<Lints> {
+        let path = normalize(path);
+        let key = QueryKey::LintItem(path.clone(), name.clone());
+        self.get(key, (path, name), |db| &mut db.lint_item)
+    }
+
+    /// WAST of the functions an item is lowered into. Items
+    /// with errors don't generate any code.
+    pub fn codegen_item(&mut self, path: &Path, name: Atom) -> Option<Arc<String>> {
+        let path = normalize(path);
+        let key = QueryKey::CodegenItem(path.clone(), name.clone());
+        self.get(key, (path, name), |db| &mut db.codegen_item)
+    }
+
+    /// Statements of a file outside of its items, checked on their own.
+    /// Offsets in the statements are the same as in the file.
+    pub fn statements(&mut self, path: &Path) -> Arc<Analysis> {
+        let path = normalize(path);
+        self.get(QueryKey::Statements(path.clone()), path, |db| {
+            &mut db.statements
+        })
+    }
+
+    /// Semantic errors of the statements of a file
+    pub fn check_statements(&mut self, path: &Path) -> Arc<Vec<SemanticError>> {
+        let path = normalize(path);
+        self.get(QueryKey::CheckStatements(path.clone()), path, |db| {
+            &mut db.check_statements
+        })
+    }
+
+    /// Lints of the statements of a file, along with what they use
+    pub fn lint_statements(&mut self, path: &Path) -> Arc<Lints> {
+        let path = normalize(path);
+        self.get(QueryKey::LintStatements(path.clone()), path, |db| {
+            &mut db.lint_statements
+        })
+    }
+
+    /// Lints of a file that depend on the rest of the program, functions
+    /// and imports nothing uses. Functions are reported in the source of
+    /// their item and imports in the file.
+    pub fn lint_file(&mut self, path: &Path) -> Arc<Vec<SemanticError>> {
+        let path = normalize(path);
+        self.get(QueryKey::LintFile(path.clone()), path, |db| {
+            &mut db.lint_file
+        })
+    }
+
+    /// Every file of the program checked and linted together,
+    /// for tools that need to look at the program as a whole
+    pub fn program(&mut self) -> Arc<Analysis> {
+        self.get(QueryKey::Program, (), |db| &mut db.program)
+    }
+
+    /// Where everything in the program is declared and referred to
+    pub fn index(&mut self) -> Arc<Index> {
+        self.get(QueryKey::Index, (), |db| &mut db.index)
+    }
+
+    fn compute_parse(&mut self, path: &Path) -> ParsedFile {
+        let text = self.source_text(path);
+        let mut tokenizer = Tokenizer::default();
+        // Imports are separate files with queries of their own
+        tokenizer.set_follow_imports(false);
+        tokenizer.enqueue_source_str(path.to_str().unwrap(), text.as_str());
+
+        let mut parser = Parser::new(&mut tokenizer);
+        let mut ast = parser.parse();
+        let tokenizer_errors = parser.tokenizer_errors();
+        let parse_errors = parser.parse_errors();
+        let imports = tokenizer
+            .imports()
+            .iter()
+            .cloned()
+            .map(|mut import| {
+                import.path = normalize(&import.path);
+                import
+            })
+            .collect();
+
+        ParsedFile {
+            file: ast.program.files.remove(0),
+            imports,
+            tokenizer_errors,
+            parse_errors,
+        }
+    }
+
+    fn compute_files(&mut self) -> Vec<PathBuf> {
+        let mut files = vec![];
+        let mut seen = HashSet::new();
+        let mut queue: Vec<_> = self.roots().iter().cloned().collect();
+        queue.reverse();
+        while let Some(path) = queue.pop() {
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            let parsed = self.parse(&path);
+            queue.extend(
+                parsed
+                    .imports
+                    .iter()
+                    .rev()
+                    .map(|import| import.path.clone()),
+            );
+            files.push(path);
+        }
+        files
+    }
+
+    fn compute_items(&mut self, path: &Path) -> Vec<Atom> {
+        let parsed = self.parse(path);
+        let elements = &parsed.file.source_elements.source_elements;
+        elements.iter().filter_map(item_name).collect()
+    }
+
+    fn compute_item_source(&mut self, path: &Path, name: Atom) -> Option<String> {
+        let parsed = self.parse(path);
+        let text = self.source_text(path);
+        let elements = &parsed.file.source_elements.source_elements;
+        let element = elements
+            .iter()
+            .find(|element| item_name(element) == Some(name.clone()))?;
+        Some(text[element_range(element)].to_owned())
+    }
+
+    fn compute_declarations(&mut self, path: &Path) -> Vec<Declaration> {
+        let parsed = self.parse(path);
+        let text = self.source_text(path);
+        let elements = &parsed.file.source_elements.source_elements;
+        elements
+            .iter()
+            .map(|element| items::declaration(&text, element))
+            .collect()
+    }
+
+    fn compute_lint_file(&mut self, path: &Path) -> Vec<SemanticError> {
+        // What the items and statements of every file use. Only the lints
+        // of what changed are recomputed, this just puts them together.
+        let mut references = BTreeSet::new();
+        let mut used_files: BTreeMap<Atom, BTreeSet<Atom>> = BTreeMap::new();
+        let mut imports = vec![];
+        for file in self.files().iter() {
+            let mut lints: Vec<_> = self
+                .items(file)
+                .iter()
+                .map(|name| self.lint_item(file, name.clone()))
+                .collect();
+            lints.push(self.lint_statements(file));
+
+            let key = Atom::from(file.to_str().unwrap());
+            for usage in lints.iter().map(|lints| &lints.usage) {
+                for (source, name) in usage.references.iter() {
+                    references.insert((analysis::source_path(source), name.clone()));
+                }
+                let used = used_files.entry(key.clone()).or_default();
+                for source in usage.used_files.iter() {
+                    let used_file = analysis::source_path(source);
+                    if used_file != *file {
+                        used.insert(Atom::from(used_file.to_str().unwrap()));
+                    }
+                }
+            }
+            imports.extend(self.parse(file).imports.iter().cloned());
+        }
+
+        let mut errors = vec![];
+        for name in self.items(path).iter() {
+            let lints = self.lint_item(path, name.clone());
+            for (function, error) in lints.usage.unused_functions.iter() {
+                if !references.contains(&(path.to_path_buf(), function.clone())) {
+                    errors.push(error.clone());
+                }
+            }
+        }
+        let file = path.to_str().unwrap();
+        let unused = unused_imports(&LintConfig::default(), &imports, &used_files);
+        errors.extend(unused.into_iter().filter(|error| error.span().file == file));
+        errors
+    }
+
+    /// Sources that haven't been set are inputs as of the revision they're
+    /// first read in. Missing files read as empty.
+    fn load_source(&mut self, path: &Path) {
+        if self.sources.contains_key(path) {
+            return;
+        }
+        let source = Input {
+            value: Arc::new(std::fs::read_to_string(path).unwrap_or_default()),
+            changed_at: self.revision,
+        };
+        self.sources.insert(path.to_path_buf(), source);
+    }
+
+    /// Records that the query being computed read the given query
+    fn read(&mut self, key: QueryKey) {
+        if let Some(deps) = self.stack.last_mut() {
+            deps.push(key);
+        }
+    }
+
+    fn get<K, V>(&mut self, key: QueryKey, arg: K, query: fn(&mut Self) -> &mut Query<K, V>) -> V
+    where
+        K: Clone + Eq + Hash,
+        V: Clone,
+    {
+        self.update(&key, &arg, query);
+        self.read(key);
+        query(self).memos[&arg].value.clone()
+    }
+
+    /// Brings the query up to date without recording a read
+    /// and returns the revision it last changed in
+    fn changed_at(&mut self, key: &QueryKey) -> Revision {
+        match key {
+            QueryKey::Roots => self.roots.changed_at,
+            QueryKey::SourceText(path) => {
+                self.load_source(path);
+                self.sources[path].changed_at
+            }
+            QueryKey::Parse(path) => self.update(key, path, |db| &mut db.parse),
+            QueryKey::Files => self.update(key, &(), |db| &mut db.files),
+            QueryKey::Items(path) => self.update(key, path, |db| &mut db.items),
+            QueryKey::ItemSource(path, name) => {
+                self.update(key, &(path.clone(), name.clone()), |db| &mut db.item_source)
+            }
+            QueryKey::Declarations(path) => self.update(key, path, |db| &mut db.declarations),
+            QueryKey::Environment => self.update(key, &(), |db| &mut db.environment),
+            QueryKey::Analysis(path, name) => {
+                self.update(key, &(path.clone(), name.clone()), |db| &mut db.analysis)
+            }
+            QueryKey::CheckItem(path, name) => {
+                self.update(key, &(path.clone(), name.clone()), |db| &mut db.check_item)
+            }
+            QueryKey::LintItem(path, name) => {
+                self.update(key, &(path.clone(), name.clone()), |db| &mut db.lint_item)
+            }
+            QueryKey::CodegenItem(path, name) => {
+                self.update(key, &(path.clone(), name.clone()), |db| {
+                    &mut db.codegen_item
+                })
+            }
+            QueryKey::Statements(path) => self.update(key, path, |db| &mut db.statements),
+            QueryKey::CheckStatements(path) => {
+                self.update(key, path, |db| &mut db.check_statements)
+            }
+            QueryKey::LintStatements(path) => self.update(key, path, |db| &mut db.lint_statements),
+            QueryKey::LintFile(path) => self.update(key, path, |db| &mut db.lint_file),
+            QueryKey::Program => self.update(key, &(), |db| &mut db.program),
+            QueryKey::Index => self.update(key, &(), |db| &mut db.index),
+        }
+    }
+
+    fn update<K, V>(
+        &mut self,
+        key: &QueryKey,
+        arg: &K,
+        query: fn(&mut Self) -> &mut Query<K, V>,
+    ) -> Revision
+    where
+        K: Clone + Eq + Hash,
+    {
+        let revision = self.revision;
+        if let Some(memo) = query(self).memos.get(arg) {
+            if memo.verified_at == revision {
+                return memo.changed_at;
+            }
+
+            // The memo is still valid if none of the queries it read have
+            // changed since. Checking stops at the first one that has, the
+            // queries after it might not even be read anymore.
+            let verified_at = memo.verified_at;
+            let deps = memo.deps.clone();
+            if deps.iter().all(|dep| self.changed_at(dep) <= verified_at) {
+                let memo = query(self).memos.get_mut(arg).unwrap();
+                memo.verified_at = revision;
+                return memo.changed_at;
+            }
+        }
+
+        self.stack.push(vec![]);
+        let compute = query(self).compute;
+        let value = compute(self, arg);
+        let deps = self.stack.pop().unwrap();
+        self.executed.push(key.clone());
+
+        let query = query(self);
+        let changed_at = match query.memos.get(arg) {
+            Some(old) if (query.eq)(&old.value, &value) => old.changed_at,
+            _ => revision,
+        };
+        let memo = Memo {
+            value,
+            verified_at: revision,
+            changed_at,
+            deps,
+        };
+        query.memos.insert(arg.clone(), memo);
+        changed_at
+    }
+}
+
+/// Resolves `.` and `..` in a path without touching the file system
+/// so files that don't exist on disk can be imported
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jswt_assert::{assert_debug_snapshot, assert_snapshot};
+
+    const SOURCE: &str = r#"import "../runtime/rt.jswt";
+
+function add(a: i32, b: i32): i32 {
+    return a + b;
+}
+
+function sub(a: i32, b: i32): i32 {
+    return a - b;
+}
+
+export function main(): i32 {
+    return add(1, sub(3, 2));
+}
+"#;
+
+    /// Database with a program whose main file lives next to the runtime
+    fn database(name: &str, source: &str) -> (Database, PathBuf) {
+        let root = normalize(&Path::new(env!("CARGO_MANIFEST_DIR")).join(".."));
+        let path = root.join(name).join("main.jswt");
+        let mut db = Database::new();
+        db.set_roots(vec![path.clone()]);
+        db.set_source_text(&path, source.to_owned());
+        (db, path)
+    }
+
+    /// Checks and generates every item in the file
+    fn compile(db: &mut Database, path: &Path) {
+        for name in db.items(path).iter() {
+            db.check_item(path, name.clone());
+            db.codegen_item(path, name.clone());
+        }
+    }
+
+    /// Item queries that were recomputed
+    fn executed_items(db: &mut Database) -> Vec<String> {
+        db.take_executed()
+            .into_iter()
+            .filter_map(|key| match key {
+                QueryKey::Analysis(_, name) => Some(format!("analysis({})", name)),
+                QueryKey::CheckItem(_, name) => Some(format!("check_item({})", name)),
+                QueryKey::CodegenItem(_, name) => Some(format!("codegen_item({})", name)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_files_follow_imports() {
+        let (mut db, path) = database("test_files_follow_imports", SOURCE);
+        let files = db.files();
+        assert_eq!(files[0], path);
+        assert!(files
+            .iter()
+            .any(|file| file.ends_with("runtime/malloc.jswt")));
+    }
+
+    #[test]
+    fn test_check_item_reports_errors_on_the_item() {
+        let source = SOURCE.replace("return a - b;", "return a - c;");
+        let (mut db, path) = database("test_check_item_reports_errors_on_the_item", &source);
+        assert!(db.check_item(&path, "add".into()).is_empty());
+
+        let errors = db.check_item(&path, "sub".into());
+        assert_eq!(errors.len(), 1);
+        assert!(
+            matches!(&errors[0], SemanticError::VariableNotDefined { name, .. } if name == "c")
+        );
+        // Spans are offsets into the item
+        assert_eq!(errors[0].span().start, 51);
+        assert_eq!(errors[0].span().lexme(), "c");
+        assert_eq!(db.codegen_item(&path, "sub".into()), None);
+    }
+
+    #[test]
+    fn test_codegen_item() {
+        let (mut db, path) = database("test_codegen_item", SOURCE);
+        assert_snapshot!(db.codegen_item(&path, "add".into()).unwrap().as_str());
+    }
+
+    #[test]
+    fn test_queries_are_reused_without_changes() {
+        let (mut db, path) = database("test_queries_are_reused_without_changes", SOURCE);
+        compile(&mut db, &path);
+        db.take_executed();
+
+        db.set_source_text(&path, SOURCE.to_owned());
+        compile(&mut db, &path);
+        assert!(db.take_executed().is_empty());
+    }
+
+    #[test]
+    fn test_editing_a_body_only_recomputes_its_item() {
+        let (mut db, path) = database("test_editing_a_body_only_recomputes_its_item", SOURCE);
+        compile(&mut db, &path);
+        db.take_executed();
+
+        // The edit moves every item after add
+        let source = SOURCE.replace("return a + b;", "let c = a + b;\n    return c;");
+        db.set_source_text(&path, source);
+        compile(&mut db, &path);
+        assert_eq!(
+            executed_items(&mut db),
+            vec!["analysis(add)", "check_item(add)", "codegen_item(add)"]
+        );
+    }
+
+    #[test]
+    fn test_declarations_are_parsed_once_for_every_item() {
+        let (mut db, path) = database("test_declarations_are_parsed_once_for_every_item", SOURCE);
+        compile(&mut db, &path);
+        let parsed = |executed: Vec<QueryKey>| {
+            executed
+                .iter()
+                .filter(|key| **key == QueryKey::Environment)
+                .count()
+        };
+        assert_eq!(parsed(db.take_executed()), 1);
+
+        let source = SOURCE.replace("return a + b;", "return b + a;");
+        db.set_source_text(&path, source);
+        compile(&mut db, &path);
+        assert_eq!(parsed(db.take_executed()), 0);
+    }
+
+    #[test]
+    fn test_editing_a_signature_recomputes_every_item() {
+        let (mut db, path) = database("test_editing_a_signature_recomputes_every_item", SOURCE);
+        compile(&mut db, &path);
+        db.take_executed();
+
+        let source = SOURCE.replace(
+            "function sub(a: i32, b: i32)",
+            "function sub(b: i32, a: i32)",
+        );
+        db.set_source_text(&path, source);
+        compile(&mut db, &path);
+        assert_debug_snapshot!(executed_items(&mut db));
+    }
+}
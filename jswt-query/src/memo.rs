@@ -0,0 +1,51 @@
+use jswt_common::Atom;
+use std::path::PathBuf;
+
+/// Every change to an input starts a new revision
+pub type Revision = u64;
+
+/// Identifies a query along with the arguments it was called with.
+/// Memos record the queries they read so they can be verified
+/// without recomputing them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QueryKey {
+    Roots,
+    SourceText(PathBuf),
+    Parse(PathBuf),
+    Files,
+    Items(PathBuf),
+    ItemSource(PathBuf, Atom),
+    Declarations(PathBuf),
+    Environment,
+    Analysis(PathBuf, Atom),
+    CheckItem(PathBuf, Atom),
+    LintItem(PathBuf, Atom),
+    CodegenItem(PathBuf, Atom),
+    Statements(PathBuf),
+    CheckStatements(PathBuf),
+    LintStatements(PathBuf),
+    LintFile(PathBuf),
+    Program,
+    Index,
+}
+
+/// Value an input was set to and the revision it was set in
+#[derive(Debug)]
+pub(crate) struct Input<V> {
+    pub(crate) value: V,
+    pub(crate) changed_at: Revision,
+}
+
+/// Result of a query as of the revision it was last verified in
+#[derive(Debug)]
+pub(crate) struct Memo<V> {
+    pub(crate) value: V,
+    /// Last revision the value was known to be up to date
+    pub(crate) verified_at: Revision,
+    /// Revision the value last changed in. Recomputing a query that
+    /// produces an equal value keeps the old revision so queries that
+    /// read it don't have to be recomputed
+    pub(crate) changed_at: Revision,
+    /// Queries read while computing the value, in the order they were read
+    pub(crate) deps: Vec<QueryKey>,
+}
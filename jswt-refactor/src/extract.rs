@@ -0,0 +1,295 @@
+use crate::{file_name, is_identifier, verify, RefactorError, TextEdit};
+use jswt_ast::{visit::*, *};
+use jswt_common::{NodeId, Span, Spannable};
+use jswt_query::{Analysis, Database, Index, Target};
+use jswt_types::Type;
+use std::ops::Range;
+use std::path::Path;
+
+/// Moves the statements in the range into a new function declared after
+/// the function or class they're in and calls it in their place. Variables
+/// the statements read from the enclosing function become parameters.
+pub fn extract_function(
+    db: &mut Database,
+    path: &Path,
+    range: Range<usize>,
+    name: &str,
+) -> Result<Vec<TextEdit>, RefactorError> {
+    if !is_identifier(name) {
+        return Err(RefactorError::InvalidName {
+            name: name.to_owned(),
+        });
+    }
+
+    let analysis = db.program();
+    let index = db.index();
+    let source = db.source_text(path);
+    let file = file_name(path);
+
+    let (item, block) = analysis
+        .ast
+        .program
+        .files
+        .iter()
+        .flat_map(|file| &file.source_elements.source_elements)
+        .filter(|element| element.span().file == file.as_str())
+        .find_map(|element| enclosing_block(element, &range))
+        .ok_or(RefactorError::InvalidSelection)?;
+
+    let statements: Vec<&StatementElement> = block
+        .statements
+        .statements
+        .iter()
+        .filter(|statement| {
+            let span = statement.span();
+            span.start < range.end && range.start < span.end
+        })
+        .collect();
+    let (first, last) = match (statements.first(), statements.last()) {
+        (Some(first), Some(last)) => (first.span(), last.span()),
+        _ => return Err(RefactorError::InvalidSelection),
+    };
+    let whole = statements.iter().all(|statement| {
+        let span = statement.span();
+        range.start <= span.start && span.end <= range.end
+    });
+    let outside = source[range.start..first.start].trim().is_empty()
+        && source[last.end..range.end].trim().is_empty();
+    if !whole || !outside {
+        return Err(RefactorError::InvalidSelection);
+    }
+
+    let mut selection = Selection {
+        analysis: &analysis,
+        index: &index,
+        item: item.span(),
+        statements: first.start..last.end,
+        params: vec![],
+        declared: vec![],
+        loops: 0,
+        error: None,
+    };
+    for statement in &statements {
+        selection.visit_statement_element(statement);
+    }
+    if let Some(error) = selection.error {
+        return Err(error);
+    }
+
+    for def in &selection.declared {
+        let target = Target::Def(*def);
+        let used_after = index
+            .references(&target)
+            .any(|span| span.file == file.as_str() && span.start >= last.end);
+        if used_after {
+            let name = index.definitions[&target].name.to_string();
+            return Err(RefactorError::SelectionDeclares { name });
+        }
+    }
+
+    let mut params = vec![];
+    let mut args = vec![];
+    for def in &selection.params {
+        let definition = &index.definitions[&Target::Def(*def)];
+        if definition.ty == Type::Unknown {
+            return Err(RefactorError::UnknownType {
+                name: definition.name.to_string(),
+            });
+        }
+        params.push(format!("{}: {}", definition.name, definition.ty));
+        args.push(definition.name.to_string());
+    }
+
+    let function = format!(
+        "\n\nfunction {}({}) {{\n{}\n}}",
+        name,
+        params.join(", "),
+        body(&source, &first, &last)
+    );
+    let call = format!("{}({});", name, args.join(", "));
+
+    let edits = vec![
+        TextEdit {
+            path: path.to_path_buf(),
+            range: first.start..last.end,
+            text: call,
+        },
+        TextEdit {
+            path: path.to_path_buf(),
+            range: item.span().end..item.span().end,
+            text: function,
+        },
+    ];
+    verify(db, &edits)?;
+    Ok(edits)
+}
+
+/// Source of the statements reindented to be the body of a top level function
+fn body(source: &str, first: &Span, last: &Span) -> String {
+    let line_start = source[..first.start].rfind('\n').map_or(0, |it| it + 1);
+    let indent = &source[line_start..first.start];
+    source[first.start..last.end]
+        .lines()
+        .map(|line| line.strip_prefix(indent).unwrap_or(line))
+        .map(|line| {
+            if line.trim().is_empty() {
+                String::new()
+            } else {
+                format!("    {}", line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Finds the innermost block of a function, constructor or
+/// method that has the range between its braces
+fn enclosing_block<'a>(
+    element: &'a SourceElement,
+    range: &Range<usize>,
+) -> Option<(&'a SourceElement, &'a BlockStatement)> {
+    let bodies: Vec<&BlockStatement> = match element {
+        SourceElement::FunctionDeclaration(function) => vec![&function.body],
+        SourceElement::ClassDeclaration(class) => class
+            .body
+            .class_elements
+            .iter()
+            .filter_map(|element| match element {
+                ClassElement::Constructor(constructor) => Some(&constructor.body),
+                ClassElement::Method(method) => Some(&method.body),
+                ClassElement::Field(_) => None,
+            })
+            .collect(),
+        SourceElement::Statement(_) => vec![],
+    };
+    bodies
+        .into_iter()
+        .find_map(|body| innermost_block(body, range))
+        .map(|block| (element, block))
+}
+
+fn innermost_block<'a>(
+    block: &'a BlockStatement,
+    range: &Range<usize>,
+) -> Option<&'a BlockStatement> {
+    if range.start <= block.span.start || block.span.end <= range.end {
+        return None;
+    }
+    let inner = block
+        .statements
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            StatementElement::Block(block) => innermost_block(block, range),
+            StatementElement::If(statement) => std::iter::once(&statement.consequence)
+                .chain(&statement.alternative)
+                .find_map(|statement| match statement.as_ref() {
+                    StatementElement::Block(block) => innermost_block(block, range),
+                    _ => None,
+                }),
+            StatementElement::Iteration(IterationStatement::While(iteration)) => {
+                innermost_block(&iteration.block, range)
+            }
+            _ => None,
+        });
+    inner.or(Some(block))
+}
+
+/// Finds what the selected statements need from the function they're in
+struct Selection<'a> {
+    analysis: &'a Analysis,
+    index: &'a Index,
+    /// Function or class the statements are in
+    item: Span,
+    statements: Range<usize>,
+    /// Variables declared before the statements that they read
+    params: Vec<NodeId>,
+    /// Variables the statements declare
+    declared: Vec<NodeId>,
+    /// Loops in the selection the statements are in
+    loops: usize,
+    error: Option<RefactorError>,
+}
+
+impl<'a> Selection<'a> {
+    /// Variables declared in the enclosing function but outside of the selection
+    fn outer_local(&self, ident: &IdentifierExpression) -> Option<NodeId> {
+        let def = self.analysis.resolutions.get(ident.id)?.0;
+        let span = &self.index.definitions.get(&Target::Def(def))?.span;
+        let local = span.file == self.item.file
+            && self.item.start <= span.start
+            && span.end <= self.item.end;
+        let selected = self.statements.start <= span.start && span.end <= self.statements.end;
+        if local && !selected {
+            Some(def)
+        } else {
+            None
+        }
+    }
+
+    fn fail(&mut self, error: RefactorError) {
+        self.error.get_or_insert(error);
+    }
+
+    fn assigns(&mut self, expression: &SingleExpression) {
+        if let Some(ident) = expression.as_identifier() {
+            if self.outer_local(ident).is_some() {
+                let name = ident.ident.value.to_string();
+                self.fail(RefactorError::SelectionAssigns { name });
+            }
+        }
+    }
+}
+
+impl<'a> Visitor for Selection<'a> {
+    fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
+        if let Some(def) = self.outer_local(node) {
+            if !self.params.contains(&def) {
+                self.params.push(def);
+            }
+        }
+    }
+
+    fn visit_variable_statement(&mut self, node: &VariableStatement) {
+        self.declared.push(node.id);
+        walk_variable_statement(self, node);
+    }
+
+    fn visit_assignment_expression(&mut self, node: &BinaryExpression) {
+        self.assigns(&node.left);
+        walk_assignment_expression(self, node);
+    }
+
+    fn visit_unary_expression(&mut self, node: &UnaryExpression) {
+        if let UnaryOperator::PostIncrement(_) | UnaryOperator::PostDecrement(_) = node.op {
+            self.assigns(&node.expr);
+        }
+        walk_unary_expression(self, node);
+    }
+
+    fn visit_this_expression(&mut self, _: &ThisExpression) {
+        self.fail(RefactorError::SelectionUsesThis);
+    }
+
+    fn visit_return_statement(&mut self, _: &ReturnStatement) {
+        self.fail(RefactorError::SelectionReturns);
+    }
+
+    fn visit_iteration_statement(&mut self, node: &IterationStatement) {
+        self.loops += 1;
+        walk_iteration_statement(self, node);
+        self.loops -= 1;
+    }
+
+    fn visit_break_statement(&mut self, _: &BreakStatement) {
+        if self.loops == 0 {
+            self.fail(RefactorError::SelectionJumps);
+        }
+    }
+
+    fn visit_continue_statement(&mut self, _: &ContinueStatement) {
+        if self.loops == 0 {
+            self.fail(RefactorError::SelectionJumps);
+        }
+    }
+}
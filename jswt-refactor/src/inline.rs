@@ -0,0 +1,255 @@
+use crate::{file_name, verify, RefactorError, TextEdit};
+use jswt_ast::{visit::*, *};
+use jswt_common::{NodeId, Span, Spannable};
+use jswt_query::{Analysis, Database, Target};
+use std::path::{Path, PathBuf};
+
+/// Replaces every reference to the variable at the offset with
+/// its initializer and removes the declaration
+pub fn inline_variable(
+    db: &mut Database,
+    path: &Path,
+    offset: usize,
+) -> Result<Vec<TextEdit>, RefactorError> {
+    let index = db.index();
+    let analysis = db.program();
+    let occurrence = index
+        .occurrence_at(&file_name(path), offset)
+        .ok_or(RefactorError::NoSymbolAtPosition)?;
+    let definition = index
+        .definitions
+        .get(&occurrence.target)
+        .ok_or(RefactorError::NoSymbolAtPosition)?;
+    let name = definition.name.to_string();
+
+    let def = match occurrence.target {
+        Target::Def(def) => def,
+        _ => return Err(RefactorError::NotAVariable { name }),
+    };
+    let variable = find_variable(&analysis.ast.program, def)
+        .ok_or_else(|| RefactorError::NotAVariable { name: name.clone() })?;
+    let mut finder = VariableFinder {
+        analysis: &analysis,
+        def,
+        writes: 0,
+        operands: vec![],
+    };
+    finder.visit_program(&analysis.ast.program);
+    let initializer = variable
+        .expression
+        .as_ref()
+        .ok_or_else(|| RefactorError::NoInitializer { name: name.clone() })?;
+    if finder.writes > 0 {
+        return Err(RefactorError::VariableReassigned { name });
+    }
+
+    let references: Vec<&Span> = index
+        .references(&occurrence.target)
+        .filter(|span| **span != definition.span)
+        .collect();
+    if references.len() != 1 && has_side_effects(initializer) {
+        return Err(RefactorError::InitializerHasSideEffects { name });
+    }
+
+    let declaration = variable.span();
+    let file = PathBuf::from(declaration.file.as_str());
+    let source = db.source_text(&file);
+    let text = &source[initializer.span().start..initializer.span().end];
+    let parenthesized = format!("({})", text);
+
+    let mut edits: Vec<_> = references
+        .iter()
+        .map(|span| {
+            let text = if is_primary(initializer) || !finder.operands.contains(*span) {
+                text.to_owned()
+            } else {
+                parenthesized.clone()
+            };
+            TextEdit {
+                path: PathBuf::from(span.file.as_str()),
+                range: span.start..span.end,
+                text,
+            }
+        })
+        .collect();
+    edits.push(TextEdit {
+        path: file,
+        range: statement_lines(&source, &declaration),
+        text: String::new(),
+    });
+    verify(db, &edits)?;
+    Ok(edits)
+}
+
+/// Lines of the statement if nothing else is on them, otherwise the statement
+fn statement_lines(source: &str, statement: &Span) -> std::ops::Range<usize> {
+    let line_start = source[..statement.start].rfind('\n').map_or(0, |it| it + 1);
+    let line_end = source[statement.end..]
+        .find('\n')
+        .map_or(source.len(), |it| statement.end + it + 1);
+    let before = &source[line_start..statement.start];
+    let after = &source[statement.end..line_end];
+    if before.trim().is_empty() && after.trim().is_empty() {
+        line_start..line_end
+    } else {
+        statement.start..statement.end
+    }
+}
+
+/// Expressions that never need parentheses to be used as an operand
+fn is_primary(expression: &SingleExpression) -> bool {
+    matches!(
+        expression,
+        SingleExpression::Identifier(_)
+            | SingleExpression::Literal(_)
+            | SingleExpression::MemberDot(_)
+            | SingleExpression::MemberIndex(_)
+            | SingleExpression::Arguments(_)
+            | SingleExpression::This(_)
+    )
+}
+
+fn has_side_effects(expression: &SingleExpression) -> bool {
+    let mut finder = SideEffectFinder { found: false };
+    finder.visit_single_expression(expression);
+    finder.found
+}
+
+/// Finds how a variable is used
+struct VariableFinder<'a> {
+    analysis: &'a Analysis,
+    def: NodeId,
+    /// Assignments and increments of the variable
+    writes: usize,
+    /// References that are operands of other expressions
+    operands: Vec<Span>,
+}
+
+impl<'a> VariableFinder<'a> {
+    fn is_reference(&self, expression: &SingleExpression) -> bool {
+        match expression.as_identifier() {
+            Some(ident) => {
+                self.analysis.resolutions.get(ident.id).map(|def| def.0) == Some(self.def)
+            }
+            None => false,
+        }
+    }
+
+    fn operand(&mut self, expression: &SingleExpression) {
+        if self.is_reference(expression) {
+            self.operands.push(expression.span());
+        }
+    }
+}
+
+impl<'a> Visitor for VariableFinder<'a> {
+    fn visit_assignment_expression(&mut self, node: &BinaryExpression) {
+        if self.is_reference(&node.left) {
+            self.writes += 1;
+        }
+        walk_assignment_expression(self, node);
+    }
+
+    fn visit_unary_expression(&mut self, node: &UnaryExpression) {
+        let increment = matches!(
+            node.op,
+            UnaryOperator::PostIncrement(_) | UnaryOperator::PostDecrement(_)
+        );
+        if increment && self.is_reference(&node.expr) {
+            self.writes += 1;
+        }
+        self.operand(&node.expr);
+        walk_unary_expression(self, node);
+    }
+
+    fn visit_binary_expression(&mut self, node: &BinaryExpression) {
+        self.operand(&node.left);
+        self.operand(&node.right);
+        walk_binary_expression(self, node);
+    }
+
+    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
+        self.operand(&node.target);
+        walk_member_dot(self, node);
+    }
+
+    fn visit_member_index(&mut self, node: &MemberIndexExpression) {
+        self.operand(&node.target);
+        walk_member_index(self, node);
+    }
+
+    fn visit_cast_expression(&mut self, node: &CastExpression) {
+        self.operand(&node.expression);
+        walk_cast_expression(self, node);
+    }
+}
+
+fn find_variable(program: &Program, def: NodeId) -> Option<&VariableStatement> {
+    program
+        .files
+        .iter()
+        .flat_map(|file| &file.source_elements.source_elements)
+        .find_map(|element| match element {
+            SourceElement::FunctionDeclaration(function) => find_in_block(&function.body, def),
+            SourceElement::ClassDeclaration(class) => {
+                class
+                    .body
+                    .class_elements
+                    .iter()
+                    .find_map(|element| match element {
+                        ClassElement::Constructor(constructor) => {
+                            find_in_block(&constructor.body, def)
+                        }
+                        ClassElement::Method(method) => find_in_block(&method.body, def),
+                        ClassElement::Field(_) => None,
+                    })
+            }
+            SourceElement::Statement(statement) => find_in_statement(statement, def),
+        })
+}
+
+fn find_in_block(block: &BlockStatement, def: NodeId) -> Option<&VariableStatement> {
+    block
+        .statements
+        .statements
+        .iter()
+        .find_map(|statement| find_in_statement(statement, def))
+}
+
+fn find_in_statement(statement: &StatementElement, def: NodeId) -> Option<&VariableStatement> {
+    match statement {
+        StatementElement::Variable(variable) if variable.id == def => Some(variable),
+        StatementElement::Block(block) => find_in_block(block, def),
+        StatementElement::If(statement) => {
+            find_in_statement(&statement.consequence, def).or_else(|| {
+                statement
+                    .alternative
+                    .as_ref()
+                    .and_then(|alternative| find_in_statement(alternative, def))
+            })
+        }
+        StatementElement::Iteration(IterationStatement::While(iteration)) => {
+            find_in_block(&iteration.block, def)
+        }
+        _ => None,
+    }
+}
+
+struct SideEffectFinder {
+    found: bool,
+}
+
+impl Visitor for SideEffectFinder {
+    fn visit_single_expression(&mut self, node: &SingleExpression) {
+        match node {
+            SingleExpression::Arguments(_)
+            | SingleExpression::New(_)
+            | SingleExpression::Assignment(_) => self.found = true,
+            SingleExpression::Unary(UnaryExpression {
+                op: UnaryOperator::PostIncrement(_) | UnaryOperator::PostDecrement(_),
+                ..
+            }) => self.found = true,
+            _ => walk_single_expression(self, node),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! Refactorings that rewrite source text. Edits only replace the spans
+//! of the code being changed so the formatting and comments around them
+//! are kept. Each refactoring checks the program with its edits applied
+//! and is refused if that introduces errors or changes what names refer to.
+
+mod extract;
+mod inline;
+mod rename;
+
+pub use extract::extract_function;
+pub use inline::inline_variable;
+pub use rename::rename;
+
+use jswt_query::{normalize, Analysis, Database};
+use jswt_tokenizer::{TokenType, Tokenizer};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Replaces a range of a source file with new text
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub path: PathBuf,
+    /// Byte offsets into the source
+    pub range: Range<usize>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RefactorError {
+    NoSymbolAtPosition,
+    InvalidName {
+        name: String,
+    },
+    /// Another declaration already uses the name
+    NameConflict {
+        name: String,
+    },
+    NotAVariable {
+        name: String,
+    },
+    NoInitializer {
+        name: String,
+    },
+    VariableReassigned {
+        name: String,
+    },
+    /// The initializer would be evaluated more than once or not at all
+    InitializerHasSideEffects {
+        name: String,
+    },
+    /// The selection isn't a sequence of whole statements in a function
+    InvalidSelection,
+    SelectionReturns,
+    SelectionUsesThis,
+    /// Break or continue in the selection jumps out of it
+    SelectionJumps,
+    /// The selection assigns a variable declared outside of it
+    SelectionAssigns {
+        name: String,
+    },
+    /// A variable declared in the selection is used after it
+    SelectionDeclares {
+        name: String,
+    },
+    UnknownType {
+        name: String,
+    },
+    /// The edits introduce errors or change what names refer to
+    ChangesMeaning,
+}
+
+impl Display for RefactorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RefactorError::NoSymbolAtPosition => f.write_str("There is no symbol at the position"),
+            RefactorError::InvalidName { name } => {
+                write!(f, "'{}' is not a valid identifier", name)
+            }
+            RefactorError::NameConflict { name } => {
+                write!(f, "'{}' is already declared", name)
+            }
+            RefactorError::NotAVariable { name } => write!(f, "'{}' is not a variable", name),
+            RefactorError::NoInitializer { name } => {
+                write!(
+                    f,
+                    "Variable '{}' isn't initialized where it's declared",
+                    name
+                )
+            }
+            RefactorError::VariableReassigned { name } => {
+                write!(f, "Variable '{}' is assigned after it's declared", name)
+            }
+            RefactorError::InitializerHasSideEffects { name } => write!(
+                f,
+                "The initializer of '{}' has side effects and isn't used exactly once",
+                name
+            ),
+            RefactorError::InvalidSelection => {
+                f.write_str("The selection isn't a sequence of statements in a function")
+            }
+            RefactorError::SelectionReturns => f.write_str("The selection returns"),
+            RefactorError::SelectionUsesThis => f.write_str("The selection uses 'this'"),
+            RefactorError::SelectionJumps => {
+                f.write_str("The selection breaks or continues a loop outside of it")
+            }
+            RefactorError::SelectionAssigns { name } => write!(
+                f,
+                "The selection assigns '{}' which is declared outside of it",
+                name
+            ),
+            RefactorError::SelectionDeclares { name } => write!(
+                f,
+                "The selection declares '{}' which is used after it",
+                name
+            ),
+            RefactorError::UnknownType { name } => {
+                write!(f, "The type of '{}' is unknown", name)
+            }
+            RefactorError::ChangesMeaning => {
+                f.write_str("The refactoring would introduce errors or change the program")
+            }
+        }
+    }
+}
+
+/// Applies edits to the source they were made for
+pub fn apply_edits(source: &str, edits: &[&TextEdit]) -> String {
+    let mut edits = edits.to_vec();
+    edits.sort_by_key(|edit| edit.range.start);
+
+    let mut text = String::new();
+    let mut offset = 0;
+    for edit in edits {
+        text += &source[offset..edit.range.start];
+        text += &edit.text;
+        offset = edit.range.end;
+    }
+    text += &source[offset..];
+    text
+}
+
+/// Sources of the files the edits change with the edits applied
+pub fn edited_sources(db: &mut Database, edits: &[TextEdit]) -> Vec<(PathBuf, String)> {
+    let mut files: BTreeMap<&Path, Vec<&TextEdit>> = BTreeMap::new();
+    for edit in edits {
+        files.entry(&edit.path).or_default().push(edit);
+    }
+    files
+        .into_iter()
+        .map(|(path, edits)| {
+            let source = db.source_text(path);
+            (path.to_path_buf(), apply_edits(&source, &edits))
+        })
+        .collect()
+}
+
+/// Checks the program with the edits applied. The sources
+/// are restored before returning.
+fn check_edits<T>(
+    db: &mut Database,
+    edits: &[TextEdit],
+    check: impl FnOnce(&mut Database) -> T,
+) -> T {
+    let mut originals = vec![];
+    for (path, text) in edited_sources(db, edits) {
+        originals.push((path.clone(), db.source_text(&path)));
+        db.set_source_text(&path, text);
+    }
+    let result = check(db);
+    for (path, text) in originals {
+        db.set_source_text(&path, text.to_string());
+    }
+    result
+}
+
+/// Refuses edits that make the program report more errors
+fn verify(db: &mut Database, edits: &[TextEdit]) -> Result<(), RefactorError> {
+    let before = error_count(&db.program());
+    let after = check_edits(db, edits, |db| error_count(&db.program()));
+    if after > before {
+        return Err(RefactorError::ChangesMeaning);
+    }
+    Ok(())
+}
+
+fn error_count(analysis: &Analysis) -> usize {
+    analysis.tokenizer_errors.len() + analysis.parse_errors.len() + analysis.errors.len()
+}
+
+/// Name of the file as it's referred to by spans
+fn file_name(path: &Path) -> String {
+    normalize(path).to_str().unwrap().to_owned()
+}
+
+/// True if the name tokenizes as a single identifier
+fn is_identifier(name: &str) -> bool {
+    let mut tokenizer = Tokenizer::default();
+    tokenizer.set_follow_imports(false);
+    tokenizer.enqueue_source_str("refactor/identifier", name);
+    let tokens: Vec<_> = tokenizer
+        .tokenize()
+        .into_iter()
+        .filter(|token| token.kind != TokenType::Eof)
+        .collect();
+    tokenizer.errors().is_empty()
+        && tokens.len() == 1
+        && tokens[0].kind == TokenType::Identifier
+        && tokens[0].span.end - tokens[0].span.start == name.len()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use jswt_assert::assert_snapshot;
+    use std::fs;
+
+    /// Marks the position a refactoring is applied at in test sources
+    const CURSOR: &str = "/*|*/";
+    /// Mark the start and end of the selection in test sources
+    const SELECTION_START: &str = "/*[*/";
+    const SELECTION_END: &str = "/*]*/";
+
+    /// Applies the refactoring named on the first line of `main.jswt`
+    /// in each directory under `test` and snapshots the changed files
+    #[test]
+    fn test_refactorings() {
+        let root = normalize(&Path::new(env!("CARGO_MANIFEST_DIR")).join(".."));
+        let runtime = root.join("runtime").join("rt.jswt");
+        let tests = root.join("jswt-refactor").join("test");
+
+        let mut cases: Vec<_> = fs::read_dir(&tests)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        cases.sort();
+        assert!(!cases.is_empty());
+
+        for case in cases {
+            let path = case.join("main.jswt");
+            let source = fs::read_to_string(&path).unwrap();
+            let command = source.lines().next().unwrap().trim_start_matches("// ");
+
+            let mut markers = vec![];
+            let mut text = source.clone();
+            for marker in [CURSOR, SELECTION_START, SELECTION_END] {
+                markers.push(text.find(marker));
+                text = text.replacen(marker, "", 1);
+            }
+            // Each marker is found after the ones before it are removed
+            let cursor = markers[0];
+            let selection = markers[1].zip(markers[2]).map(|(start, end)| start..end);
+
+            let mut db = Database::new();
+            db.set_roots(vec![path.clone(), runtime.clone()]);
+            db.set_source_text(&path, text);
+
+            let mut words = command.split_whitespace();
+            let result = match (words.next().unwrap(), words.next()) {
+                ("rename", Some(name)) => rename(&mut db, &path, cursor.unwrap(), name),
+                ("inline-variable", None) => inline_variable(&mut db, &path, cursor.unwrap()),
+                ("extract-function", Some(name)) => {
+                    extract_function(&mut db, &path, selection.unwrap(), name)
+                }
+                _ => panic!("Unknown refactoring '{}'", command),
+            };
+
+            let output = match result {
+                Ok(edits) => edited_sources(&mut db, &edits)
+                    .into_iter()
+                    .map(|(file, text)| {
+                        let name = file.strip_prefix(&case).unwrap().display();
+                        format!("// {}\n{}", name, text)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Err(error) => format!("error: {}", error),
+            };
+            let name = case.file_name().unwrap().to_str().unwrap();
+            assert_snapshot!(name, output);
+        }
+    }
+
+    #[test]
+    fn test_apply_edits_in_any_order() {
+        let edit = |range: Range<usize>, text: &str| TextEdit {
+            path: PathBuf::from("test"),
+            range,
+            text: text.to_owned(),
+        };
+        let edits = [edit(8..9, "b"), edit(0..3, "const")];
+        let edits: Vec<_> = edits.iter().collect();
+        assert_eq!(apply_edits("let a = a;", &edits), "const a = b;");
+    }
+
+    #[test]
+    fn test_is_identifier() {
+        assert!(is_identifier("total"));
+        assert!(!is_identifier("let"));
+        assert!(!is_identifier("a b"));
+        assert!(!is_identifier("1a"));
+        assert!(!is_identifier(""));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{check_edits, file_name, is_identifier, verify, RefactorError, TextEdit};
+use jswt_common::Span;
+use jswt_query::{Database, Target};
+use std::path::{Path, PathBuf};
+
+/// Renames the symbol at the offset and every reference to it
+/// throughout the program, including the files it's imported by
+pub fn rename(
+    db: &mut Database,
+    path: &Path,
+    offset: usize,
+    name: &str,
+) -> Result<Vec<TextEdit>, RefactorError> {
+    if !is_identifier(name) {
+        return Err(RefactorError::InvalidName {
+            name: name.to_owned(),
+        });
+    }
+
+    let index = db.index();
+    let target = index
+        .occurrence_at(&file_name(path), offset)
+        .map(|occurrence| occurrence.target.clone())
+        .ok_or(RefactorError::NoSymbolAtPosition)?;
+    let definition = index
+        .definitions
+        .get(&target)
+        .ok_or(RefactorError::NoSymbolAtPosition)?;
+    if definition.name == name {
+        return Ok(vec![]);
+    }
+
+    // Duplicate members aren't errors so they're looked for here
+    if let Target::Member(class, _) = &target {
+        if index
+            .definitions
+            .contains_key(&Target::Member(class.clone(), name.into()))
+        {
+            return Err(RefactorError::NameConflict {
+                name: name.to_owned(),
+            });
+        }
+    }
+
+    let mut spans: Vec<&Span> = index.references(&target).collect();
+    spans.sort_by_key(|span| (span.file.clone(), span.start));
+    spans.dedup();
+    let edits: Vec<_> = spans
+        .iter()
+        .map(|span| TextEdit {
+            path: PathBuf::from(span.file.as_str()),
+            range: span.start..span.end,
+            text: name.to_owned(),
+        })
+        .collect();
+    verify(db, &edits)?;
+
+    // A name that's already in scope wouldn't cause an error but references
+    // would resolve to a different declaration than they used to
+    let declaration = &definition.span;
+    let before = spans_before(&spans, declaration);
+    let start = declaration.start - before * definition.name.len() + before * name.len();
+    let references = index.references(&target).count();
+    let renamed = check_edits(db, &edits, |db| {
+        let index = db.index();
+        index
+            .occurrence_at(declaration.file.as_str(), start)
+            .map(|occurrence| index.references(&occurrence.target).count())
+    });
+    if renamed != Some(references) {
+        return Err(RefactorError::NameConflict {
+            name: name.to_owned(),
+        });
+    }
+
+    Ok(edits)
+}
+
+/// Number of spans in the file of the declaration that come before it
+fn spans_before(spans: &[&Span], declaration: &Span) -> usize {
+    spans
+        .iter()
+        .filter(|span| span.file == declaration.file && span.start < declaration.start)
+        .count()
+}
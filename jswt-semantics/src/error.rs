@@ -1,6 +1,10 @@
 use std::borrow::Cow;
 
+use jswt_ast::{AnnotationTarget, ArgumentKind};
 use jswt_common::Span;
+use jswt_types::Type;
+
+use crate::{Lint, LintLevel};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum SemanticError {
@@ -52,4 +56,158 @@ pub enum SemanticError {
         ident: Cow<'static, str>,
         span: Span,
     },
+    TypeNotDefined {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    InvalidCast {
+        from: Type,
+        to: Type,
+        span: Span,
+    },
+    InvalidAssignmentTarget {
+        span: Span,
+    },
+    IntegerOutOfRange {
+        ty: Type,
+        span: Span,
+    },
+    UnknownAnnotation {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    AnnotationNotApplicable {
+        name: Cow<'static, str>,
+        target: AnnotationTarget,
+        span: Span,
+    },
+    UnexpectedAnnotationArgument {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    MissingAnnotationArgument {
+        name: Cow<'static, str>,
+        argument: &'static str,
+        span: Span,
+    },
+    DuplicateAnnotationArgument {
+        name: Cow<'static, str>,
+        argument: &'static str,
+        span: Span,
+    },
+    InvalidAnnotationArgument {
+        name: Cow<'static, str>,
+        argument: &'static str,
+        expected: ArgumentKind,
+        span: Span,
+    },
+    ArgumentCountMismatch {
+        expected: usize,
+        found: usize,
+        span: Span,
+        declaration: Span,
+    },
+    ArgumentTypeMismatch {
+        expected: Type,
+        found: Type,
+        span: Span,
+        declaration: Span,
+    },
+    MissingReturn {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    AssignmentToConstant {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    AssignmentToReadonly {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    ConstantWithoutInitializer {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    UseBeforeAssignment {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    UninitializedField {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    Lint {
+        lint: Lint,
+        level: LintLevel,
+        name: Option<Cow<'static, str>>,
+        span: Span,
+    },
+    UnknownLint {
+        name: Cow<'static, str>,
+        span: Span,
+    },
+    TypeMismatch {
+        expected: Type,
+        found: Type,
+        span: Span,
+    },
+    InvalidOperand {
+        operator: &'static str,
+        found: Type,
+        span: Span,
+    },
+}
+
+impl SemanticError {
+    /// Warnings are reported but don't prevent compilation
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            SemanticError::Lint {
+                level: LintLevel::Warn,
+                ..
+            }
+        )
+    }
+
+    /// Span of the code the error is reported on
+    pub fn span(&self) -> &Span {
+        match self {
+            SemanticError::VariableNotDefined { span, .. }
+            | SemanticError::VariableAlreadyDefined { span, .. }
+            | SemanticError::FunctionAlreadyDefined { span, .. }
+            | SemanticError::ClassAlreadyDefined { span, .. }
+            | SemanticError::FunctionNotDefined { span, .. }
+            | SemanticError::NotAFunctionError { span, .. }
+            | SemanticError::TypeError { span, .. }
+            | SemanticError::ThisOutsideClass { span, .. }
+            | SemanticError::PropertyNotDefined { span, .. }
+            | SemanticError::FieldAlreadyDefined { span, .. }
+            | SemanticError::MethodAlreadyDefined { span, .. }
+            | SemanticError::ClassNotDefined { span, .. }
+            | SemanticError::TypeNotDefined { span, .. }
+            | SemanticError::InvalidCast { span, .. }
+            | SemanticError::InvalidAssignmentTarget { span }
+            | SemanticError::IntegerOutOfRange { span, .. }
+            | SemanticError::UnknownAnnotation { span, .. }
+            | SemanticError::AnnotationNotApplicable { span, .. }
+            | SemanticError::UnexpectedAnnotationArgument { span, .. }
+            | SemanticError::MissingAnnotationArgument { span, .. }
+            | SemanticError::DuplicateAnnotationArgument { span, .. }
+            | SemanticError::InvalidAnnotationArgument { span, .. }
+            | SemanticError::ArgumentCountMismatch { span, .. }
+            | SemanticError::ArgumentTypeMismatch { span, .. }
+            | SemanticError::MissingReturn { span, .. }
+            | SemanticError::AssignmentToConstant { span, .. }
+            | SemanticError::AssignmentToReadonly { span, .. }
+            | SemanticError::ConstantWithoutInitializer { span, .. }
+            | SemanticError::UseBeforeAssignment { span, .. }
+            | SemanticError::UninitializedField { span, .. }
+            | SemanticError::Lint { span, .. }
+            | SemanticError::UnknownLint { span, .. }
+            | SemanticError::TypeMismatch { span, .. }
+            | SemanticError::InvalidOperand { span, .. } => span,
+        }
+    }
 }
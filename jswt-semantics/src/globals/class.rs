@@ -4,8 +4,10 @@ use jswt_ast::{
     visit::{self, Visitor},
     ClassDeclarationElement,
 };
-use jswt_common::Type;
-use jswt_symbols::{BindingsTable, ClassBinding, FunctionSignature, Method, ScopedSymbolTable};
+use jswt_symbols::{
+    BindingsTable, ClassBinding, DefId, FunctionSignature, Method, ScopedSymbolTable,
+};
+use jswt_types::Type;
 
 pub struct ClassDeclarationGlobalContext<'a> {
     class_binding: ClassBinding,
@@ -20,7 +22,7 @@ impl<'a> ClassDeclarationGlobalContext<'a> {
     pub fn new(resolver: &'a mut GlobalSemanticResolver, node: &ClassDeclarationElement) -> Self {
         Self {
             class_binding: ClassBinding {
-                name: node.ident.value.clone(),
+                name: node.ident.value.clone().into(),
                 fields: vec![],
                 methods: vec![],
             },
@@ -33,61 +35,61 @@ impl<'a> ClassDeclarationGlobalContext<'a> {
 
 impl<'a> Visitor for ClassDeclarationGlobalContext<'a> {
     fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
-        let class_name = node.ident.value.clone();
+        let class_name = &node.ident.value;
         // Walk the rest of the class tree
         // Resolve fields, and methods
         visit::walk_class_declaration(self, &node);
 
         // Add the class binding to the bindings table
         self.bindings
-            .insert(class_name.clone(), self.class_binding.to_owned());
+            .insert(class_name.into(), self.class_binding.to_owned());
 
         // Add the class to the symbol table
         self.symbols
-            .define(&class_name, jswt_symbols::Symbol::Class);
+            .define(class_name, jswt_symbols::Symbol::Class, DefId(node.id));
     }
 
     fn visit_class_field_declaration(&mut self, node: &jswt_ast::ClassFieldElement) {
-        let field_name = node.ident.value.clone();
+        let field_name = &node.ident.value;
 
         // Check if the field already exists
         if self
             .class_binding
             .fields
             .iter()
-            .any(|f| f.name == field_name)
+            .any(|f| f.name == *field_name)
         {
             let error = SemanticError::FieldAlreadyDefined {
-                name: field_name.clone(),
+                name: field_name.into(),
                 span: node.ident.span.to_owned(),
             };
             self.errors.push(error);
         }
 
         self.class_binding.fields.push(jswt_symbols::Field {
-            name: field_name,
+            name: field_name.into(),
             // Fields are aligned in the order they are declared
             index: self.class_binding.fields.len(),
-            // We need to get the type of the field
-            // from the symbol table to compute the size
-            size: 4, // TODO compute type size
+            offset: self.class_binding.size(),
+            size: node.type_annotation.ty.size(),
             ty: node.type_annotation.ty.clone(),
+            readonly: node.readonly,
         });
         visit::walk_class_field_declaration(self, node);
     }
 
     fn visit_class_method_declaration(&mut self, node: &jswt_ast::ClassMethodElement) {
-        let method_name = node.ident.value.clone();
+        let method_name = &node.ident.value;
 
         // Check if the method already exists
         if self
             .class_binding
             .methods
             .iter()
-            .any(|m| m.name == method_name)
+            .any(|m| m.name == *method_name)
         {
             let error = SemanticError::MethodAlreadyDefined {
-                name: method_name.clone(),
+                name: method_name.into(),
                 span: node.ident.span.to_owned(),
             };
             self.errors.push(error);
@@ -104,12 +106,16 @@ impl<'a> Visitor for ClassDeclarationGlobalContext<'a> {
             .returns
             .as_ref()
             .map(|ty| ty.ty.clone())
-            .unwrap_or(Type::Binding("void".into()));
+            .unwrap_or(Type::Void);
 
         // Add the method to the class binding
         self.class_binding.methods.push(Method {
-            name: method_name,
-            signature: FunctionSignature { params, returns },
+            name: method_name.into(),
+            signature: FunctionSignature {
+                params,
+                returns,
+                span: node.ident.span.to_owned(),
+            },
         });
     }
 }
@@ -1,8 +1,8 @@
 use super::GlobalSemanticResolver;
 use crate::SemanticError;
 use jswt_ast::{visit::Visitor, FunctionDeclarationElement};
-use jswt_common::Type;
-use jswt_symbols::{ScopedSymbolTable, Symbol};
+use jswt_symbols::{DefId, ScopedSymbolTable, Symbol};
+use jswt_types::Type;
 
 pub struct FunctionDeclarationGlobalContext<'a> {
     symbols: &'a mut ScopedSymbolTable,
@@ -25,7 +25,7 @@ impl<'a> Visitor for FunctionDeclarationGlobalContext<'a> {
 
         if self.symbols.lookup_current(function_name).is_some() {
             let error = SemanticError::FunctionAlreadyDefined {
-                name: function_name.clone(),
+                name: function_name.into(),
                 span: ident.span.to_owned(),
             };
             self.errors.push(error);
@@ -36,7 +36,7 @@ impl<'a> Visitor for FunctionDeclarationGlobalContext<'a> {
             .returns
             .as_ref()
             .map(|it| it.ty.clone())
-            .unwrap_or(Type::Binding("void".into()));
+            .unwrap_or(Type::Void);
 
         // Determine the types of the function parameters
         let params = node
@@ -49,8 +49,11 @@ impl<'a> Visitor for FunctionDeclarationGlobalContext<'a> {
         // TODO - we're eventually going to want to qualify the full function
         // name here scoped to the current module, but for now we'll just
         // use the function name
-        self.symbols
-            .define(function_name, Symbol::function(params, returns));
+        self.symbols.define(
+            function_name,
+            Symbol::function(params, returns, ident.span.to_owned()),
+            DefId(node.id),
+        );
     }
 }
 
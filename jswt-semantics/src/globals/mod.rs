@@ -30,7 +30,7 @@ impl<'a> GlobalSemanticResolver<'a> {
 
     /// Run the global semantic analysis pass
     pub fn resolve(&mut self, ast: &Ast) {
-        self.symbols.push_global_scope();
+        self.symbols.push_scope(ast.program.id);
         self.visit_program(&ast.program);
         // We should have only the global scope left
         // at the end of the pass
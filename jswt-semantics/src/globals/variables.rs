@@ -1,7 +1,7 @@
 use crate::SemanticError;
-use jswt_ast::{visit::Visitor, AssignableElement};
-use jswt_common::Typeable;
-use jswt_symbols::{ScopedSymbolTable, Symbol, TypeSignature};
+use jswt_ast::{visit::Visitor, AssignableElement, SingleExpression};
+use jswt_symbols::{DefId, ScopedSymbolTable, Symbol};
+use jswt_types::Type;
 
 use super::GlobalSemanticResolver;
 
@@ -28,20 +28,22 @@ impl<'a> Visitor for VariableDeclarationGlobalContext<'a> {
                 // in the current scope then we have a duplicate variable error
                 if self.symbols.lookup_current(name).is_some() {
                     let error = SemanticError::VariableAlreadyDefined {
-                        name: name.clone(),
+                        name: name.into(),
                         span: ident.span.to_owned(),
                     };
                     self.errors.push(error);
                 }
 
                 // Add the variable to the symbol table
-                // The type of the symbol is the type of the rhs expression
-                self.symbols.define(
-                    name,
-                    Symbol::Type(TypeSignature {
-                        ty: node.expression.ty(),
-                    }),
-                );
+                // The type of the symbol is the declared type if there is one.
+                // Other initializers are inferred later by the type checker
+                let ty = match (&node.type_annotation, &node.expression) {
+                    (Some(annotation), _) => annotation.ty.clone(),
+                    (None, Some(SingleExpression::Cast(cast))) => cast.target.ty.clone(),
+                    (None, _) => Type::Unknown,
+                };
+                self.symbols
+                    .define(name, Symbol::variable(&node.modifier, ty), DefId(node.id));
             }
         }
     }
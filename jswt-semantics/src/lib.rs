@@ -1,13 +1,11 @@
 mod error;
 mod globals;
+mod lints;
 mod locals;
 mod types;
 
 pub use error::SemanticError;
 pub use globals::GlobalSemanticResolver;
+pub use lints::{unused_imports, FileUsage, Lint, LintConfig, LintLevel, Linter};
 pub use locals::LocalSemanticResolver;
-
-use jswt_symbols::Symbol;
-use std::borrow::Cow;
-
-type SymbolTable = jswt_symbols::SimpleSymbolTable<Cow<'static, str>, Symbol>;
+pub use types::TypeChecker;
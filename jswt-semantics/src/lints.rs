@@ -0,0 +1,1046 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::Path,
+    str::FromStr,
+};
+
+use crate::{locals::terminates, SemanticError};
+use jswt_ast::{
+    visit::{self, Visitor},
+    Annotation, Ast, BinaryExpression, BlockStatement, CastExpression, ClassConstructorElement,
+    ClassDeclarationElement, ClassFieldElement, ClassMethodElement, File, FormalParameterList,
+    FunctionDeclarationElement, IdentifierExpression, IfStatement, Literal, MemberDotExpression,
+    Program, SingleExpression, SourceElement, StatementElement, TypeAnnotation, VariableStatement,
+    WhileIterationElement,
+};
+use jswt_common::{Atom, NodeId, Span, Spannable};
+use jswt_symbols::{DefId, ResolutionTable, ScopedSymbolTable};
+use jswt_tokenizer::Import;
+use jswt_types::{ObjectType, Type};
+
+/// Warnings reported by the lint pass. Every lint has a name that is
+/// used to allow or deny it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Lint {
+    UnusedVariable,
+    UnusedParameter,
+    UnusedNative,
+    UnusedImport,
+    UnusedFunction,
+    Shadowing,
+    ConstantCondition,
+    SelfAssignment,
+    UnreachableCode,
+    MissingSemicolon,
+}
+
+impl Lint {
+    pub const ALL: &'static [Lint] = &[
+        Lint::UnusedVariable,
+        Lint::UnusedParameter,
+        Lint::UnusedNative,
+        Lint::UnusedImport,
+        Lint::UnusedFunction,
+        Lint::Shadowing,
+        Lint::ConstantCondition,
+        Lint::SelfAssignment,
+        Lint::UnreachableCode,
+        Lint::MissingSemicolon,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused-variable",
+            Lint::UnusedParameter => "unused-parameter",
+            Lint::UnusedNative => "unused-native",
+            Lint::UnusedImport => "unused-import",
+            Lint::UnusedFunction => "unused-function",
+            Lint::Shadowing => "shadowing",
+            Lint::ConstantCondition => "constant-condition",
+            Lint::SelfAssignment => "self-assignment",
+            Lint::UnreachableCode => "unreachable-code",
+            Lint::MissingSemicolon => "missing-semicolon",
+        }
+    }
+
+    /// Level the lint is reported at unless it's configured. Semicolons
+    /// are inserted following javascript's rules so relying on them is
+    /// only reported when asked for.
+    pub fn default_level(&self) -> LintLevel {
+        match self {
+            Lint::MissingSemicolon => LintLevel::Allow,
+            _ => LintLevel::Warn,
+        }
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Lint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Lint::ALL
+            .iter()
+            .find(|lint| lint.name() == s)
+            .copied()
+            .ok_or_else(|| format!("Unknown lint '{}'", s))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+/// Levels lints are reported at. Lints that aren't configured are
+/// reported at their default level.
+#[derive(Debug, Clone, Default)]
+pub struct LintConfig {
+    levels: BTreeMap<Lint, LintLevel>,
+    ignored_modules: Vec<Cow<'static, str>>,
+}
+
+impl LintConfig {
+    pub fn set(&mut self, lint: Lint, level: LintLevel) {
+        self.levels.insert(lint, level);
+    }
+
+    pub fn level(&self, lint: Lint) -> LintLevel {
+        self.levels
+            .get(&lint)
+            .copied()
+            .unwrap_or_else(|| lint.default_level())
+    }
+
+    /// Sources of the module named `module` aren't linted. This is
+    /// used to keep warnings in the runtime out of user builds.
+    pub fn ignore_module<T: Into<Cow<'static, str>>>(&mut self, module: T) {
+        self.ignored_modules.push(module.into());
+    }
+
+    fn is_ignored(&self, module: &str) -> bool {
+        self.ignored_modules.iter().any(|ignored| ignored == module)
+    }
+}
+
+#[derive(Debug)]
+struct Binding {
+    name: Cow<'static, str>,
+    def: DefId,
+    span: Span,
+    used: bool,
+    // Lint and level to report if the binding is never used
+    lint: Lint,
+    level: LintLevel,
+}
+
+#[derive(Debug)]
+struct FunctionDefinition {
+    name: Cow<'static, str>,
+    def: DefId,
+    span: Span,
+    lint: Lint,
+    level: LintLevel,
+}
+
+/// What a file linted on its own uses from the rest of the program,
+/// and the lints that depend on whether the rest of the program uses it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FileUsage {
+    /// Global declarations the file refers to by file and name
+    pub references: BTreeSet<(Atom, Atom)>,
+    /// Files of the declarations the file uses
+    pub used_files: BTreeSet<Atom>,
+    /// Lints for the functions the file declares by their name,
+    /// reported if nothing else in the program refers to them
+    pub unused_functions: Vec<(Atom, SemanticError)>,
+}
+
+/// Reports lints over the resolved AST. This runs after semantic
+/// analysis so the program is assumed to be well formed.
+#[derive(Debug)]
+pub struct Linter<'a> {
+    config: LintConfig,
+    symbols: &'a ScopedSymbolTable,
+    resolutions: &'a ResolutionTable,
+    errors: Vec<SemanticError>,
+    // Lints allowed with `@allow` by the enclosing declarations
+    allowed: Vec<Vec<Lint>>,
+    // Keys of the symbol table scopes enclosing the current node
+    scopes: Vec<NodeId>,
+    // Local bindings declared by the enclosing blocks
+    bindings: Vec<Vec<Binding>>,
+    function: Option<DefId>,
+    // Private functions and the global declarations referenced anywhere
+    functions: Vec<FunctionDefinition>,
+    references: BTreeSet<DefId>,
+    // Semicolons inserted by the parser
+    inserted_semicolons: Vec<Span>,
+    imports: Vec<Import>,
+    // Files and names of the global declarations, classes are
+    // also looked up by name since types refer to them by name
+    declared_in: BTreeMap<DefId, (Atom, Atom)>,
+    classes: BTreeMap<Atom, Atom>,
+    // Files each file uses declarations of
+    used_files: BTreeMap<Atom, BTreeSet<Atom>>,
+    file: Option<Atom>,
+}
+
+impl<'a> Linter<'a> {
+    pub fn new(
+        config: LintConfig,
+        symbols: &'a ScopedSymbolTable,
+        resolutions: &'a ResolutionTable,
+    ) -> Self {
+        Self {
+            config,
+            symbols,
+            resolutions,
+            errors: vec![],
+            allowed: vec![],
+            scopes: vec![],
+            bindings: vec![],
+            function: None,
+            functions: vec![],
+            references: BTreeSet::new(),
+            inserted_semicolons: vec![],
+            imports: vec![],
+            declared_in: BTreeMap::new(),
+            classes: BTreeMap::new(),
+            used_files: BTreeMap::new(),
+            file: None,
+        }
+    }
+
+    /// Semicolons the parser inserted after statements of the program
+    pub fn set_inserted_semicolons(&mut self, spans: Vec<Span>) {
+        self.inserted_semicolons = spans;
+    }
+
+    /// Import directives of the program. Their paths have to match
+    /// the files of the spans of the imported sources.
+    pub fn set_imports(&mut self, imports: Vec<Import>) {
+        self.imports = imports;
+    }
+
+    pub fn lint(&mut self, ast: &Ast) {
+        self.declare_globals(&ast.program);
+        self.visit_program(&ast.program);
+        self.check_imports();
+
+        let functions = std::mem::take(&mut self.functions);
+        for function in functions {
+            if !self.references.contains(&function.def) {
+                self.report_at(
+                    function.lint,
+                    function.level,
+                    Some(function.name),
+                    function.span,
+                );
+            }
+        }
+    }
+
+    /// Lints the last file of the program on its own, the other files
+    /// only declare what it refers to. Whether its functions and imports
+    /// are used depends on the rest of the program, so that's left to
+    /// the caller along with what the file uses.
+    pub fn lint_file(&mut self, ast: &Ast) -> FileUsage {
+        let program = &ast.program;
+        let file = match program.files.last() {
+            Some(file) => file,
+            None => return FileUsage::default(),
+        };
+        self.declare_globals(program);
+        self.scopes.push(program.id);
+        self.visit_file(file);
+        self.scopes.pop();
+
+        let references = self
+            .references
+            .iter()
+            .filter_map(|def| self.declared_in.get(def).cloned())
+            .collect();
+        let used_files = self.used_files.remove(&file.span.file).unwrap_or_default();
+        let unused_functions = std::mem::take(&mut self.functions)
+            .into_iter()
+            .filter(|function| function.level != LintLevel::Allow)
+            .map(|function| {
+                let error = SemanticError::Lint {
+                    lint: function.lint,
+                    level: function.level,
+                    name: Some(function.name.clone()),
+                    span: function.span,
+                };
+                (function.name.into(), error)
+            })
+            .collect();
+        FileUsage {
+            references,
+            used_files,
+            unused_functions,
+        }
+    }
+
+    pub fn errors(&mut self) -> &mut Vec<SemanticError> {
+        &mut self.errors
+    }
+
+    fn level(&self, lint: Lint) -> LintLevel {
+        if self
+            .allowed
+            .iter()
+            .flatten()
+            .any(|allowed| *allowed == lint)
+        {
+            return LintLevel::Allow;
+        }
+        self.config.level(lint)
+    }
+
+    fn report(&mut self, lint: Lint, name: Option<Cow<'static, str>>, span: Span) {
+        self.report_at(lint, self.level(lint), name, span);
+    }
+
+    fn report_at(
+        &mut self,
+        lint: Lint,
+        level: LintLevel,
+        name: Option<Cow<'static, str>>,
+        span: Span,
+    ) {
+        if level != LintLevel::Allow {
+            self.errors.push(SemanticError::Lint {
+                lint,
+                level,
+                name,
+                span,
+            });
+        }
+    }
+
+    /// Collects the lints allowed by `@allow("...")` annotations
+    fn allows(&mut self, annotations: &[Annotation]) -> Vec<Lint> {
+        let mut allows = vec![];
+        for annotation in annotations.iter().filter(|a| a.name.value == "allow") {
+            let name = match annotation.string_argument("lint") {
+                Some(name) => name,
+                // Missing arguments are reported by annotation validation
+                None => continue,
+            };
+            match name.parse() {
+                Ok(lint) => allows.push(lint),
+                Err(_) => self.errors.push(SemanticError::UnknownLint {
+                    name: name.to_owned().into(),
+                    span: annotation.span(),
+                }),
+            }
+        }
+        allows
+    }
+
+    /// Notes the files global declarations are in, including the files
+    /// that aren't linted since they can still be imported
+    fn declare_globals(&mut self, program: &Program) {
+        for file in program.files.iter() {
+            for element in file.source_elements.source_elements.iter() {
+                let (def, name) = match element {
+                    SourceElement::FunctionDeclaration(function) => {
+                        (DefId(function.id), function.ident.value.clone())
+                    }
+                    SourceElement::ClassDeclaration(class) => {
+                        self.classes
+                            .insert(class.ident.value.clone(), file.span.file.clone());
+                        (DefId(class.id), class.ident.value.clone())
+                    }
+                    SourceElement::Statement(StatementElement::Variable(variable)) => {
+                        let name = match &variable.target {
+                            jswt_ast::AssignableElement::Identifier(ident) => ident.value.clone(),
+                        };
+                        (DefId(variable.id), name)
+                    }
+                    _ => continue,
+                };
+                self.declared_in.insert(def, (file.span.file.clone(), name));
+            }
+        }
+    }
+
+    /// Notes that the current file uses a declaration of `file`
+    fn use_file(&mut self, file: Atom) {
+        if let Some(current) = &self.file {
+            if *current != file {
+                self.used_files
+                    .entry(current.clone())
+                    .or_default()
+                    .insert(file);
+            }
+        }
+    }
+
+    /// Notes the files of the classes the type refers to
+    fn use_type(&mut self, ty: &Type) {
+        match ty {
+            Type::Object(ObjectType::Reference(name)) => {
+                if let Some(file) = self.classes.get(&Atom::from(name.as_ref())).cloned() {
+                    self.use_file(file);
+                }
+            }
+            Type::Object(ObjectType::Array(ty)) => self.use_type(ty),
+            _ => {}
+        }
+    }
+
+    fn use_annotation(&mut self, annotation: &Option<TypeAnnotation>) {
+        if let Some(annotation) = annotation {
+            self.use_type(&annotation.ty);
+        }
+    }
+
+    fn check_imports(&mut self) {
+        let imports = std::mem::take(&mut self.imports);
+        let errors = unused_imports(&self.config, &imports, &self.used_files);
+        self.errors.extend(errors);
+    }
+
+    /// Returns true if a scope enclosing `scope` declares `name` before `def`.
+    /// Globals are visible before they're declared.
+    fn shadows(&self, name: &str, def: DefId, scope: NodeId) -> bool {
+        let global = self.scopes.first().copied();
+        self.scopes.iter().filter(|key| **key != scope).any(|key| {
+            let outer = self
+                .symbols
+                .get_scope(*key)
+                .and_then(|scope| scope.defs.get(name));
+            match outer {
+                Some(outer) => Some(*key) == global || *outer < def,
+                None => false,
+            }
+        })
+    }
+
+    /// Declares a local binding defined by `def` in the symbol table scope `scope`
+    fn declare(
+        &mut self,
+        name: Cow<'static, str>,
+        def: DefId,
+        scope: NodeId,
+        span: Span,
+        lint: Lint,
+        level: LintLevel,
+    ) {
+        if self.shadows(&name, def, scope) {
+            self.report(Lint::Shadowing, Some(name.clone()), span.clone());
+        }
+
+        if let Some(bindings) = self.bindings.last_mut() {
+            bindings.push(Binding {
+                name,
+                def,
+                span,
+                used: false,
+                lint,
+                level,
+            });
+        }
+    }
+
+    fn pop_scope(&mut self) {
+        let bindings = self.bindings.pop().unwrap_or_default();
+        for binding in bindings {
+            // Bindings prefixed with an underscore are unused on purpose
+            if !binding.used && !binding.name.starts_with('_') {
+                self.report_at(
+                    binding.lint,
+                    binding.level,
+                    Some(binding.name),
+                    binding.span,
+                );
+            }
+        }
+    }
+
+    /// Reports a semicolon inserted after the statement ending at `span`
+    fn check_semicolon(&mut self, span: &Span) {
+        let inserted = self
+            .inserted_semicolons
+            .iter()
+            .find(|semi| semi.file == span.file && semi.start == span.end)
+            .cloned();
+        if let Some(semi) = inserted {
+            self.report(Lint::MissingSemicolon, None, semi);
+        }
+    }
+
+    /// Lints a function body with its parameters in scope. Parameters of
+    /// functions implemented in WAST or by the host are never unused.
+    fn visit_body(&mut self, params: &FormalParameterList, body: &BlockStatement, exempt: bool) {
+        self.bindings.push(vec![]);
+        for param in params.parameters.iter() {
+            self.use_type(&param.type_annotation.ty);
+            let allows = self.allows(&param.annotations);
+            let level = if exempt || allows.contains(&Lint::UnusedParameter) {
+                LintLevel::Allow
+            } else {
+                self.level(Lint::UnusedParameter)
+            };
+            // Parameters are scoped to the function body
+            self.declare(
+                param.ident.value.clone().into(),
+                DefId(param.id),
+                body.id,
+                param.ident.span(),
+                Lint::UnusedParameter,
+                level,
+            );
+        }
+        self.visit_block_statement(body);
+        self.pop_scope();
+    }
+}
+
+/// Reports imports nothing is used from. Sources see everything their
+/// imports import in turn, so the import is used if the importing file
+/// or a file importing it uses a file reachable through the import.
+/// `used_files` are the files each file uses declarations of.
+pub fn unused_imports(
+    config: &LintConfig,
+    imports: &[Import],
+    used_files: &BTreeMap<Atom, BTreeSet<Atom>>,
+) -> Vec<SemanticError> {
+    let mut errors = vec![];
+    for import in imports.iter() {
+        let level = config.level(Lint::UnusedImport);
+        if level == LintLevel::Allow || config.is_ignored(&import.span.module) {
+            continue;
+        }
+
+        let imported = reachable(import.path.as_path(), |path| {
+            imports
+                .iter()
+                .filter(|next| Path::new(next.span.file.as_str()) == path)
+                .map(|next| next.path.as_path())
+                .collect()
+        });
+        let importers = reachable(Path::new(import.span.file.as_str()), |path| {
+            imports
+                .iter()
+                .filter(|next| next.path == path)
+                .map(|next| Path::new(next.span.file.as_str()))
+                .collect()
+        });
+
+        let used = importers.iter().any(|importer| {
+            used_files
+                .get(&Atom::from(importer.to_str().unwrap()))
+                .into_iter()
+                .flatten()
+                .any(|file| imported.contains(&Path::new(file.as_str())))
+        });
+        if !used {
+            errors.push(SemanticError::Lint {
+                lint: Lint::UnusedImport,
+                level,
+                name: Some(import.name.clone().into()),
+                span: import.span.clone(),
+            });
+        }
+    }
+    errors
+}
+
+/// Every path reachable from `start` through `next`, including `start`
+fn reachable<'p>(start: &'p Path, next: impl Fn(&Path) -> Vec<&'p Path>) -> Vec<&'p Path> {
+    let mut paths = vec![start];
+    let mut i = 0;
+    while i < paths.len() {
+        for path in next(paths[i]) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        i += 1;
+    }
+    paths
+}
+
+/// Returns true if the expression only contains literals
+fn is_constant(node: &SingleExpression) -> bool {
+    match node {
+        SingleExpression::Literal(_) => true,
+        SingleExpression::Unary(exp) => is_constant(&exp.expr),
+        SingleExpression::Multiplicative(exp)
+        | SingleExpression::Bitwise(exp)
+        | SingleExpression::Additive(exp)
+        | SingleExpression::Equality(exp)
+        | SingleExpression::Relational(exp) => is_constant(&exp.left) && is_constant(&exp.right),
+        _ => false,
+    }
+}
+
+/// Returns true if both expressions refer to the same variable or field
+fn is_same_place(a: &SingleExpression, b: &SingleExpression) -> bool {
+    match (a, b) {
+        (SingleExpression::Identifier(a), SingleExpression::Identifier(b)) => {
+            a.ident.value == b.ident.value
+        }
+        (SingleExpression::This(_), SingleExpression::This(_)) => true,
+        (SingleExpression::MemberDot(a), SingleExpression::MemberDot(b)) => {
+            is_same_place(&a.target, &b.target) && is_same_place(&a.expression, &b.expression)
+        }
+        _ => false,
+    }
+}
+
+impl<'a> Visitor for Linter<'a> {
+    fn visit_program(&mut self, node: &Program) {
+        self.scopes.push(node.id);
+        visit::walk_program(self, node);
+        self.scopes.pop();
+    }
+
+    fn visit_file(&mut self, node: &File) {
+        if !self.config.is_ignored(&node.span.module) {
+            self.file = Some(node.span.file.clone());
+            visit::walk_file(self, node);
+            self.file = None;
+        }
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        let allows = self.allows(&node.decorators.annotations);
+        self.allowed.push(allows);
+
+        let is_native = node
+            .decorators
+            .annotations
+            .iter()
+            .any(|a| a.name.value == "native");
+        let is_exempt = is_native
+            || node
+                .decorators
+                .annotations
+                .iter()
+                .any(|a| a.name.value == "wast");
+
+        // Exported functions are used by the host
+        if !node.decorators.export {
+            let lint = if is_native {
+                Lint::UnusedNative
+            } else {
+                Lint::UnusedFunction
+            };
+            self.functions.push(FunctionDefinition {
+                name: node.ident.value.clone().into(),
+                def: DefId(node.id),
+                span: node.ident.span(),
+                lint,
+                level: self.level(lint),
+            });
+        }
+
+        self.use_annotation(&node.returns);
+        self.function = Some(DefId(node.id));
+        self.visit_body(&node.params, &node.body, is_exempt);
+        self.function = None;
+        self.allowed.pop();
+    }
+
+    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
+        let allows = self.allows(&node.annotations);
+        self.allowed.push(allows);
+        visit::walk_class_declaration(self, node);
+        self.allowed.pop();
+    }
+
+    fn visit_class_constructor_declaration(&mut self, node: &ClassConstructorElement) {
+        let allows = self.allows(&node.annotations);
+        self.allowed.push(allows);
+        self.visit_body(&node.params, &node.body, false);
+        self.allowed.pop();
+    }
+
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        let allows = self.allows(&node.annotations);
+        self.allowed.push(allows);
+        let is_exempt = node.annotations.iter().any(|a| a.name.value == "wast");
+        self.use_annotation(&node.returns);
+        self.visit_body(&node.params, &node.body, is_exempt);
+        self.allowed.pop();
+    }
+
+    fn visit_statement_element(&mut self, node: &StatementElement) {
+        match node {
+            StatementElement::Variable(_)
+            | StatementElement::Expression(_)
+            | StatementElement::Return(_)
+            | StatementElement::Break(_)
+            | StatementElement::Continue(_) => self.check_semicolon(&node.span()),
+            _ => {}
+        }
+        visit::walk_statement_element(self, node);
+    }
+
+    fn visit_class_field_declaration(&mut self, node: &ClassFieldElement) {
+        let allows = self.allows(&node.annotations);
+        self.allowed.push(allows);
+        self.check_semicolon(&node.type_annotation.span);
+        self.use_type(&node.type_annotation.ty);
+        visit::walk_class_field_declaration(self, node);
+        self.allowed.pop();
+    }
+
+    fn visit_block_statement(&mut self, node: &BlockStatement) {
+        let mut statements = node.statements.statements.iter();
+        if statements.by_ref().any(terminates) {
+            // Only the first unreachable statement is reported
+            let unreachable = statements.find(|s| !matches!(s, StatementElement::Empty(_)));
+            if let Some(statement) = unreachable {
+                self.report(Lint::UnreachableCode, None, statement.span());
+            }
+        }
+
+        self.scopes.push(node.id);
+        self.bindings.push(vec![]);
+        visit::walk_block_statement(self, node);
+        self.pop_scope();
+        self.scopes.pop();
+    }
+
+    fn visit_variable_statement(&mut self, node: &VariableStatement) {
+        self.use_annotation(&node.type_annotation);
+        // The initializer can't refer to the variable being declared
+        if let Some(expression) = &node.expression {
+            self.visit_single_expression(expression);
+        }
+        // Globals are declared outside of any block and aren't linted
+        if !self.bindings.is_empty() {
+            let scope = *self.scopes.last().unwrap();
+            let name = match &node.target {
+                jswt_ast::AssignableElement::Identifier(ident) => &ident.value,
+            };
+            let level = self.level(Lint::UnusedVariable);
+            self.declare(
+                name.into(),
+                DefId(node.id),
+                scope,
+                node.target.span(),
+                Lint::UnusedVariable,
+                level,
+            );
+        }
+    }
+
+    fn visit_if_statement(&mut self, node: &IfStatement) {
+        if is_constant(&node.condition) {
+            self.report(Lint::ConstantCondition, None, node.condition.span());
+        }
+        visit::walk_if_statement(self, node);
+    }
+
+    fn visit_while_iteration_element(&mut self, node: &WhileIterationElement) {
+        // `while (true)` is how infinite loops are written
+        let is_infinite = matches!(
+            &node.expression,
+            SingleExpression::Literal(Literal::Boolean(lit)) if lit.value
+        );
+        if is_constant(&node.expression) && !is_infinite {
+            self.report(Lint::ConstantCondition, None, node.expression.span());
+        }
+        visit::walk_while_iteration_element(self, node);
+    }
+
+    fn visit_assignment_expression(&mut self, node: &BinaryExpression) {
+        if is_same_place(&node.left, &node.right) {
+            self.report(Lint::SelfAssignment, None, node.span());
+        }
+
+        self.visit_single_expression(&node.right);
+        // Writing to a variable doesn't count as using it
+        match &*node.left {
+            SingleExpression::Identifier(_) => {}
+            SingleExpression::MemberDot(member) => self.visit_single_expression(&member.target),
+            target => self.visit_single_expression(target),
+        }
+    }
+
+    fn visit_cast_expression(&mut self, node: &CastExpression) {
+        self.use_type(&node.target.ty);
+        visit::walk_cast_expression(self, node);
+    }
+
+    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
+        // Members aren't variables
+        self.visit_single_expression(&node.target);
+    }
+
+    fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
+        // Undefined names are reported by the local semantic pass
+        let def = match self.resolutions.get(node.id) {
+            Some(def) => def,
+            None => return,
+        };
+        if let Some((file, _)) = self.declared_in.get(&def).cloned() {
+            self.use_file(file);
+        }
+        let binding = self
+            .bindings
+            .iter_mut()
+            .flatten()
+            .find(|binding| binding.def == def);
+
+        match binding {
+            Some(binding) => binding.used = true,
+            // Recursive calls don't count as using a function
+            None if self.function != Some(def) => {
+                self.references.insert(def);
+            }
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{GlobalSemanticResolver, LocalSemanticResolver};
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_parser::Parser;
+    use jswt_symbols::BindingsTable;
+    use jswt_tokenizer::Tokenizer;
+
+    fn lint(name: &str, source: &'static str, config: LintConfig) -> Vec<SemanticError> {
+        lint_sources(&[(name, source)], config)
+    }
+
+    /// Lints the sources as one program. Imports aren't followed
+    /// so every imported source has to be given.
+    fn lint_sources(sources: &[(&str, &'static str)], config: LintConfig) -> Vec<SemanticError> {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.set_follow_imports(false);
+        for (name, source) in sources {
+            tokenizer.enqueue_source_str(name, source);
+        }
+        let mut parser = Parser::new(&mut tokenizer);
+        let ast = parser.parse();
+        assert!(parser.parse_errors().is_empty());
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+        let resolutions = local.resolutions;
+
+        let mut linter = Linter::new(config, &symbols, &resolutions);
+        linter.set_inserted_semicolons(parser.inserted_semicolons());
+        linter.set_imports(tokenizer.imports().to_vec());
+        linter.lint(&ast);
+        linter.errors
+    }
+
+    #[test]
+    fn test_lints_report_warnings() {
+        let errors = lint(
+            "test_lints_report_warnings",
+            r#"
+        @native(module = "env")
+        function println(value: i32) {}
+
+        @native(module = "env")
+        function exit(code: i32) {}
+
+        function unused(a: i32, _b: i32): i32 {
+            let c = 1;
+            let d = 2;
+            d = 3;
+            if (a > 0) {
+                let a = 2;
+                return a;
+            }
+            if (1 > 2) {
+                a = a;
+            }
+            return a;
+            c = 4;
+        }
+
+        function helper(): i32 {
+            return helper();
+        }
+
+        export function main(): i32 {
+            println(helper());
+            return 0;
+        }
+        "#,
+            LintConfig::default(),
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_lints_can_be_allowed_and_denied() {
+        let mut config = LintConfig::default();
+        config.set(Lint::UnusedVariable, LintLevel::Deny);
+        config.set(Lint::UnusedFunction, LintLevel::Allow);
+
+        let errors = lint(
+            "test_lints_can_be_allowed_and_denied",
+            r#"
+        function unused(@allow("unused-parameter") a: i32) {
+            let b = 1;
+        }
+
+        @allow("unused-variable")
+        function allowed() {
+            let c = 1;
+        }
+
+        @allow("not-a-lint")
+        export function main(d: i32) {}
+        "#,
+            config,
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_lints_report_unused_imports() {
+        let errors = lint_sources(
+            &[
+                (
+                    "/lints/main.jswt",
+                    r#"
+        import "./point.jswt";
+        import "./math.jswt";
+        import "./unused.jswt";
+
+        export function main(_p: Point): i32 {
+            return square(2);
+        }
+        "#,
+                ),
+                ("/lints/./point.jswt", "class Point { x: i32; }"),
+                (
+                    "/lints/./math.jswt",
+                    r#"import "./square.jswt"; import "./cube.jswt";"#,
+                ),
+                (
+                    "/lints/././square.jswt",
+                    "export function square(a: i32): i32 { return a * a; }",
+                ),
+                (
+                    "/lints/././cube.jswt",
+                    "export function cube(a: i32): i32 { return a * a * a; }",
+                ),
+                ("/lints/./unused.jswt", "export function unused() {}"),
+            ],
+            LintConfig::default(),
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_lints_report_unreachable_code() {
+        let errors = lint(
+            "test_lints_report_unreachable_code",
+            r"
+        export function test(a: i32): i32 {
+            while (a > 0) {
+                break;
+                a = a - 1;
+            }
+            return a;
+            a = 0;
+        }
+        ",
+            LintConfig::default(),
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_lints_report_shadowed_globals() {
+        let errors = lint(
+            "test_lints_report_shadowed_globals",
+            r"
+        let limit = 10;
+
+        export function scale(value: i32): i32 {
+            return value * 2;
+        }
+
+        export function test(limit: i32): i32 {
+            let scale = limit;
+            {
+                let later = scale;
+                scale = later;
+            }
+            // Declared after the block so it isn't shadowed by it
+            let later = scale;
+            return later;
+        }
+        ",
+            LintConfig::default(),
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_lints_report_missing_semicolons() {
+        let mut config = LintConfig::default();
+        config.set(Lint::MissingSemicolon, LintLevel::Deny);
+
+        let errors = lint(
+            "test_lints_report_missing_semicolons",
+            r"
+        class Point {
+            x: i32
+            y: i32;
+        }
+
+        export function test(a: i32): i32 {
+            let b = a
+            b = b + 1;
+            return b
+        }
+        ",
+            config,
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_lints_ignore_modules_by_name() {
+        let source = r"
+        function unused() {}
+        ";
+        let mut config = LintConfig::default();
+        config.ignore_module("module/test_lints_ignore");
+        config.ignore_module("module/test_lints_ignore_modules_by_name");
+        assert_eq!(
+            lint("test_lints_ignore_modules_by_name", source, config.clone()),
+            vec![]
+        );
+        assert_eq!(lint("test_lints_ignore_modules", source, config).len(), 1);
+    }
+
+    #[test]
+    fn test_lints_parse_from_names() {
+        for lint in Lint::ALL {
+            assert_eq!(lint.name().parse::<Lint>(), Ok(*lint));
+        }
+        assert!("unused".parse::<Lint>().is_err());
+    }
+}
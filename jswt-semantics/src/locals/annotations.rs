@@ -0,0 +1,225 @@
+use super::LocalSemanticResolver;
+use crate::SemanticError;
+use jswt_ast::{
+    visit::{self, Visitor},
+    Annotation, AnnotationTarget, ClassConstructorElement, ClassDeclarationElement,
+    ClassFieldElement, ClassMethodElement, FormalParameterList, FunctionDeclarationElement,
+};
+use jswt_common::Spannable;
+
+/// Validates annotations against the schemas in the annotation registry
+pub struct AnnotationsLocalContext<'a> {
+    errors: &'a mut Vec<SemanticError>,
+}
+
+impl<'a> AnnotationsLocalContext<'a> {
+    pub fn new(resolver: &'a mut LocalSemanticResolver) -> Self {
+        Self {
+            errors: &mut resolver.errors,
+        }
+    }
+
+    fn validate_annotations(&mut self, annotations: &[Annotation], target: AnnotationTarget) {
+        for annotation in annotations {
+            self.validate_annotation(annotation, target);
+        }
+    }
+
+    fn validate_parameters(&mut self, params: &FormalParameterList) {
+        for param in params.parameters.iter() {
+            self.validate_annotations(&param.annotations, AnnotationTarget::Parameter);
+        }
+    }
+
+    fn validate_annotation(&mut self, annotation: &Annotation, target: AnnotationTarget) {
+        let name = &annotation.name.value;
+        let schema = match annotation.schema() {
+            Some(schema) => schema,
+            None => {
+                self.errors.push(SemanticError::UnknownAnnotation {
+                    name: name.into(),
+                    span: annotation.name.span(),
+                });
+                return;
+            }
+        };
+
+        if !schema.targets.contains(&target) {
+            self.errors.push(SemanticError::AnnotationNotApplicable {
+                name: name.into(),
+                target,
+                span: annotation.span(),
+            });
+        }
+
+        // Bind every argument to a parameter in the schema. Positional
+        // arguments are bound in the order parameters are declared.
+        let mut bound = vec![false; schema.arguments.len()];
+        for (i, arg) in annotation.arguments.iter().enumerate() {
+            let position = match &arg.name {
+                Some(ident) => schema.arguments.iter().position(|p| p.name == ident.value),
+                None if i < schema.arguments.len() => Some(i),
+                None => None,
+            };
+
+            let position = match position {
+                Some(position) => position,
+                None => {
+                    self.errors
+                        .push(SemanticError::UnexpectedAnnotationArgument {
+                            name: name.into(),
+                            span: arg.span(),
+                        });
+                    continue;
+                }
+            };
+
+            let param = &schema.arguments[position];
+            if bound[position] {
+                self.errors
+                    .push(SemanticError::DuplicateAnnotationArgument {
+                        name: name.into(),
+                        argument: param.name,
+                        span: arg.span(),
+                    });
+                continue;
+            }
+            bound[position] = true;
+
+            if !param.kind.matches(&arg.value) {
+                self.errors.push(SemanticError::InvalidAnnotationArgument {
+                    name: name.into(),
+                    argument: param.name,
+                    expected: param.kind,
+                    span: arg.value.span(),
+                });
+            }
+        }
+
+        for (param, bound) in schema.arguments.iter().zip(bound) {
+            if param.required && !bound {
+                self.errors.push(SemanticError::MissingAnnotationArgument {
+                    name: name.into(),
+                    argument: param.name,
+                    span: annotation.span(),
+                });
+            }
+        }
+    }
+}
+
+impl<'a> Visitor for AnnotationsLocalContext<'a> {
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        self.validate_annotations(&node.decorators.annotations, AnnotationTarget::Function);
+        self.validate_parameters(&node.params);
+    }
+
+    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
+        self.validate_annotations(&node.annotations, AnnotationTarget::Class);
+        visit::walk_class_declaration(self, node);
+    }
+
+    fn visit_class_constructor_declaration(&mut self, node: &ClassConstructorElement) {
+        self.validate_annotations(&node.annotations, AnnotationTarget::Constructor);
+        self.validate_parameters(&node.params);
+    }
+
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        self.validate_annotations(&node.annotations, AnnotationTarget::Method);
+        self.validate_parameters(&node.params);
+    }
+
+    fn visit_class_field_declaration(&mut self, node: &ClassFieldElement) {
+        self.validate_annotations(&node.annotations, AnnotationTarget::Field);
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::GlobalSemanticResolver;
+
+    use super::*;
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_parser::Parser;
+    use jswt_symbols::{BindingsTable, ScopedSymbolTable};
+    use jswt_tokenizer::Tokenizer;
+
+    #[test]
+    fn test_valid_annotations() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_valid_annotations",
+            r#"
+        @native("env")
+        function print(value: i32) {}
+
+        @inline
+        @wast(code = "(local.get $a)")
+        function identity(a: i32): i32 {}
+
+        class Test {
+            @allow("missing-semicolon")
+            field: i32
+
+            @allow("unused-parameter")
+            constructor(a: i32) {}
+
+            @inline
+            @wast("(local.get $this)")
+            address(): i32 {}
+        }
+        "#,
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+
+        assert_eq!(local.errors, vec![]);
+    }
+
+    #[test]
+    fn test_error_on_invalid_annotations() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_error_on_invalid_annotations",
+            r#"
+        @unknown
+        function a() {}
+
+        @native
+        function b() {}
+
+        @wast(1)
+        function c() {}
+
+        @wast("", code = "")
+        function d() {}
+
+        @inline(true)
+        function e(@inline f: i32) {}
+
+        @native(module = "env")
+        class Test {
+            @wast(value = "")
+            field: i32;
+        }
+        "#,
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+
+        assert_debug_snapshot!(local.errors);
+    }
+}
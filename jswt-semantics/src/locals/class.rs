@@ -5,7 +5,8 @@ use jswt_ast::{
     ClassBody, ClassDeclarationElement, ClassMethodElement,
 };
 use jswt_common::Spannable;
-use jswt_symbols::{ScopedSymbolTable, Symbol};
+use jswt_symbols::{DefId, ScopedSymbolTable, Symbol};
+use jswt_types::Type;
 
 pub struct ClassLocalContext<'a> {
     symbols: &'a mut ScopedSymbolTable,
@@ -23,35 +24,45 @@ impl<'a> ClassLocalContext<'a> {
 
 impl<'a> Visitor for ClassLocalContext<'a> {
     fn visit_class_body(&mut self, node: &ClassBody) {
-        self.symbols.push_scope(node.span());
+        self.symbols.push_scope(node.id);
         visit::walk_class_body(self, node);
         self.symbols.pop_scope();
     }
 
     fn visit_class_constructor_declaration(&mut self, node: &jswt_ast::ClassConstructorElement) {
-        self.symbols.push_scope(node.body.span());
+        self.symbols
+            .push_scope_with_return(node.body.id, Some(Type::Void));
         //  Add method parameters as variables in scope
         for param in node.params.parameters.iter() {
             // Check to see if the parameter is already defined
             let param_name = &param.ident.value;
             if let Some(_) = self.symbols.lookup(param_name) {
                 let error = SemanticError::VariableAlreadyDefined {
-                    name: param_name.clone(),
+                    name: param_name.into(),
                     span: param.span(),
                 };
                 self.errors.push(error);
             }
 
             // Resolve Type from Type Annotation
-            self.symbols
-                .define(param_name, Symbol::ty(param.type_annotation.ty.clone()));
+            self.symbols.define(
+                param_name,
+                Symbol::ty(param.type_annotation.ty.clone()),
+                DefId(param.id),
+            );
         }
         self.symbols.pop_scope();
     }
 
     fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
         // The scope of the function parameters is the method body
-        self.symbols.push_scope(node.body.span());
+        let returns = node
+            .returns
+            .as_ref()
+            .map(|it| it.ty.clone())
+            .unwrap_or(Type::Void);
+        self.symbols
+            .push_scope_with_return(node.body.id, Some(returns));
 
         // Note: Redefinition errors are handled by the GlobalSemanticResolver
         // during global symbol resolution
@@ -62,15 +73,18 @@ impl<'a> Visitor for ClassLocalContext<'a> {
             let param_name = &param.ident.value;
             if let Some(_) = self.symbols.lookup(param_name) {
                 let error = SemanticError::VariableAlreadyDefined {
-                    name: param_name.clone(),
+                    name: param_name.into(),
                     span: param.span(),
                 };
                 self.errors.push(error);
             }
 
             // Resolve Type from Type Annotation
-            self.symbols
-                .define(param_name, Symbol::ty(param.type_annotation.ty.clone()));
+            self.symbols.define(
+                param_name,
+                Symbol::ty(param.type_annotation.ty.clone()),
+                DefId(param.id),
+            );
         }
         self.symbols.pop_scope();
     }
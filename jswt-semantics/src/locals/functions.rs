@@ -2,7 +2,8 @@ use super::LocalSemanticResolver;
 use crate::SemanticError;
 use jswt_ast::{visit::Visitor, FunctionDeclarationElement};
 use jswt_common::Spannable;
-use jswt_symbols::{ScopedSymbolTable, Symbol};
+use jswt_symbols::{DefId, ScopedSymbolTable, Symbol};
+use jswt_types::Type;
 
 pub struct FunctionsLocalContext<'a> {
     symbols: &'a mut ScopedSymbolTable,
@@ -20,7 +21,13 @@ impl<'a> FunctionsLocalContext<'a> {
 
 impl<'a> Visitor for FunctionsLocalContext<'a> {
     fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
-        self.symbols.push_scope(node.body.span());
+        let returns = node
+            .returns
+            .as_ref()
+            .map(|it| it.ty.clone())
+            .unwrap_or(Type::Void);
+        self.symbols
+            .push_scope_with_return(node.body.id, Some(returns));
         // Redefinition errors are handled by the GlobalSemanticResolver
         // during clobal symbol resolution
 
@@ -30,15 +37,18 @@ impl<'a> Visitor for FunctionsLocalContext<'a> {
             let param_name = &param.ident.value;
             if let Some(_) = self.symbols.lookup(param_name) {
                 let error = SemanticError::VariableAlreadyDefined {
-                    name: param_name.clone(),
+                    name: param_name.into(),
                     span: param.span(),
                 };
                 self.errors.push(error);
             }
 
             // Resolve Type from Type Annotation
-            self.symbols
-                .define(param_name, Symbol::ty(param.type_annotation.ty.clone()));
+            self.symbols.define(
+                param_name,
+                Symbol::ty(param.type_annotation.ty.clone()),
+                DefId(param.id),
+            );
         }
 
         self.symbols.pop_scope();
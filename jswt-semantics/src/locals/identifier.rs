@@ -2,10 +2,11 @@ use super::LocalSemanticResolver;
 use crate::SemanticError;
 use jswt_ast::visit::Visitor;
 use jswt_common::Spannable;
-use jswt_symbols::ScopedSymbolTable;
+use jswt_symbols::{ResolutionTable, ScopedSymbolTable};
 
 pub struct IdentifierExpressionLocalContext<'a> {
     symbols: &'a mut ScopedSymbolTable,
+    resolutions: &'a mut ResolutionTable,
     errors: &'a mut Vec<SemanticError>,
 }
 
@@ -13,6 +14,7 @@ impl<'a> IdentifierExpressionLocalContext<'a> {
     pub fn new(resolver: &'a mut LocalSemanticResolver) -> Self {
         Self {
             symbols: resolver.symbols,
+            resolutions: &mut resolver.resolutions,
             errors: &mut resolver.errors,
         }
     }
@@ -22,14 +24,16 @@ impl<'a> Visitor for IdentifierExpressionLocalContext<'a> {
     fn visit_identifier_expression(&mut self, node: &jswt_ast::IdentifierExpression) {
         let ident = &node.ident;
         let name = &ident.value;
-        let symbol = self.symbols.lookup(name);
-        if let None = symbol {
-            let error = SemanticError::VariableNotDefined {
-                name: name.clone(),
-                span: ident.span(),
-            };
-            self.errors.push(error);
-        };
+        match self.symbols.lookup_def(name) {
+            Some(def) => self.resolutions.insert(node.id, def),
+            None => {
+                let error = SemanticError::VariableNotDefined {
+                    name: name.into(),
+                    span: ident.span(),
+                };
+                self.errors.push(error);
+            }
+        }
     }
 }
 
@@ -0,0 +1,434 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use super::{returns::terminates, LocalSemanticResolver};
+use crate::SemanticError;
+use jswt_ast::{
+    visit::{self, Visitor},
+    AssignableElement, BinaryExpression, BlockStatement, ClassConstructorElement,
+    ClassDeclarationElement, ClassMethodElement, FormalParameterList, FunctionDeclarationElement,
+    IdentifierExpression, IfStatement, MemberDotExpression, SingleExpression, UnaryExpression,
+    UnaryOperator, VariableModifier, VariableStatement, WhileIterationElement,
+};
+use jswt_common::Spannable;
+use jswt_symbols::{BindingsTable, Field, ScopedSymbolTable, Symbol};
+use jswt_types::Type;
+
+#[derive(Debug, Clone)]
+struct Local {
+    ty: Type,
+    constant: bool,
+    assigned: bool,
+}
+
+/// What is known to have been assigned at a point in the program
+#[derive(Debug, Clone, Default)]
+struct State {
+    // Variables declared in each enclosing block
+    scopes: Vec<BTreeMap<Cow<'static, str>, Local>>,
+    // Fields of the class being constructed
+    fields: BTreeMap<Cow<'static, str>, bool>,
+}
+
+impl State {
+    fn new(scope: BTreeMap<Cow<'static, str>, Local>) -> Self {
+        Self {
+            scopes: vec![scope],
+            fields: BTreeMap::new(),
+        }
+    }
+
+    fn lookup(&mut self, name: &str) -> Option<&mut Local> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+    }
+
+    /// Merges the state of two branches that flow into the same point.
+    /// Something is only definitely assigned if both branches assign it.
+    fn merge(&mut self, other: State) {
+        for (scope, other) in self.scopes.iter_mut().zip(other.scopes) {
+            for (name, local) in scope.iter_mut() {
+                local.assigned &= other.get(name).map(|l| l.assigned).unwrap_or(false);
+            }
+        }
+        for (name, assigned) in self.fields.iter_mut() {
+            *assigned &= other.fields.get(name).copied().unwrap_or(false);
+        }
+    }
+}
+
+/// Enforces `const` and `readonly` and checks that variables, as well as
+/// fields inside of constructors, are definitely assigned before they are read
+pub struct InitializationLocalContext<'a> {
+    symbols: &'a mut ScopedSymbolTable,
+    bindings: &'a BindingsTable,
+    errors: &'a mut Vec<SemanticError>,
+    state: State,
+    class: Option<Cow<'static, str>>,
+    in_constructor: bool,
+}
+
+impl<'a> InitializationLocalContext<'a> {
+    pub fn new(resolver: &'a mut LocalSemanticResolver) -> Self {
+        Self {
+            symbols: resolver.symbols,
+            bindings: resolver.bindings,
+            errors: &mut resolver.errors,
+            // Top level statements are analyzed in the global scope
+            state: State::new(BTreeMap::new()),
+            class: None,
+            in_constructor: false,
+        }
+    }
+
+    /// Resolves a variable that isn't declared locally. Globals referenced from
+    /// within functions are zero initialized so they are always assigned.
+    fn lookup_global(&mut self, name: &str) -> Option<Local> {
+        match self.symbols.lookup(name) {
+            Some(Symbol::Type(signature)) => Some(Local {
+                ty: signature.ty.clone(),
+                constant: signature.constant,
+                assigned: true,
+            }),
+            _ => None,
+        }
+    }
+
+    fn field(&self, class: &str, name: &str) -> Option<&Field> {
+        self.bindings.lookup(class)?.field(name)
+    }
+
+    /// Analyzes a function body with the parameters already assigned
+    fn visit_body(&mut self, params: &FormalParameterList, body: &BlockStatement) {
+        let params = params
+            .parameters
+            .iter()
+            .map(|param| {
+                let local = Local {
+                    ty: param.type_annotation.ty.clone(),
+                    constant: false,
+                    assigned: true,
+                };
+                (param.ident.value.clone().into(), local)
+            })
+            .collect();
+
+        let fields = std::mem::take(&mut self.state.fields);
+        let outer = std::mem::replace(&mut self.state, State::new(params));
+        self.state.fields = fields;
+        self.visit_block_statement(body);
+        self.state = outer;
+    }
+
+    /// Records a write to the target of an assignment
+    fn assign(&mut self, target: &SingleExpression) {
+        match target {
+            SingleExpression::Identifier(ident) => {
+                let name = &ident.ident.value;
+                let constant = match self.state.lookup(name) {
+                    Some(local) => {
+                        local.assigned = true;
+                        local.constant
+                    }
+                    None => self.lookup_global(name).map_or(false, |l| l.constant),
+                };
+                if constant {
+                    self.errors.push(SemanticError::AssignmentToConstant {
+                        name: name.into(),
+                        span: target.span(),
+                    });
+                }
+            }
+            SingleExpression::MemberDot(member) => {
+                let name = match member.expression.as_identifier() {
+                    Some(ident) => &ident.ident.value,
+                    None => return,
+                };
+                let (class, is_this) = match &*member.target {
+                    SingleExpression::This(_) => (self.class.clone(), true),
+                    SingleExpression::Identifier(ident) => {
+                        let name = &ident.ident.value;
+                        let local = match self.state.lookup(name) {
+                            Some(local) => Some(local.clone()),
+                            None => self.lookup_global(name),
+                        };
+                        (local.and_then(|l| l.ty.binding_name()), false)
+                    }
+                    _ => return,
+                };
+
+                let readonly = match class.as_ref().and_then(|c| self.field(c, name)) {
+                    Some(field) => field.readonly,
+                    None => return,
+                };
+                if is_this && self.in_constructor {
+                    self.state.fields.insert(name.into(), true);
+                } else if readonly {
+                    self.errors.push(SemanticError::AssignmentToReadonly {
+                        name: name.into(),
+                        span: target.span(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> Visitor for InitializationLocalContext<'a> {
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        self.visit_body(&node.params, &node.body);
+    }
+
+    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
+        self.class = Some(node.ident.value.clone().into());
+        visit::walk_class_declaration(self, node);
+        self.class = None;
+    }
+
+    fn visit_class_constructor_declaration(&mut self, node: &ClassConstructorElement) {
+        // Every field starts out unassigned
+        let binding = self.class.as_ref().and_then(|c| self.bindings.lookup(c));
+        self.state.fields = binding
+            .map(|b| b.fields.iter().map(|f| (f.name.clone(), false)).collect())
+            .unwrap_or_default();
+
+        self.in_constructor = true;
+        self.visit_body(&node.params, &node.body);
+        self.in_constructor = false;
+        self.state.fields.clear();
+    }
+
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        self.visit_body(&node.params, &node.body);
+    }
+
+    fn visit_block_statement(&mut self, node: &BlockStatement) {
+        self.state.scopes.push(BTreeMap::new());
+        visit::walk_block_statement(self, node);
+        self.state.scopes.pop();
+    }
+
+    fn visit_variable_statement(&mut self, node: &VariableStatement) {
+        let name = match &node.target {
+            AssignableElement::Identifier(ident) => &ident.value,
+        };
+        let constant = matches!(node.modifier, VariableModifier::Const(_));
+        if constant && node.expression.is_none() {
+            self.errors.push(SemanticError::ConstantWithoutInitializer {
+                name: name.into(),
+                span: node.target.span(),
+            });
+        }
+
+        let local = Local {
+            ty: node
+                .type_annotation
+                .as_ref()
+                .map(|annotation| annotation.ty.clone())
+                .unwrap_or(Type::Unknown),
+            constant,
+            assigned: false,
+        };
+        // Declared ahead of the initializer so that it can't reference itself
+        if let Some(scope) = self.state.scopes.last_mut() {
+            scope.insert(name.into(), local);
+        }
+
+        if let Some(expression) = &node.expression {
+            self.visit_single_expression(expression);
+            if let Some(local) = self.state.lookup(name) {
+                local.assigned = true;
+            }
+        }
+    }
+
+    fn visit_if_statement(&mut self, node: &IfStatement) {
+        self.visit_single_expression(&node.condition);
+
+        let before = self.state.clone();
+        self.visit_statement_element(&node.consequence);
+        let consequence = std::mem::replace(&mut self.state, before);
+        if let Some(alternative) = &node.alternative {
+            self.visit_statement_element(alternative);
+        }
+
+        // Branches that don't complete don't flow into the code after the if
+        let alternative_terminates = node.alternative.as_deref().map_or(false, terminates);
+        if alternative_terminates {
+            self.state = consequence;
+        } else if !terminates(&node.consequence) {
+            self.state.merge(consequence);
+        }
+    }
+
+    fn visit_while_iteration_element(&mut self, node: &WhileIterationElement) {
+        self.visit_single_expression(&node.expression);
+        // The body might never execute
+        let before = self.state.clone();
+        self.visit_block_statement(&node.block);
+        self.state = before;
+    }
+
+    fn visit_assignment_expression(&mut self, node: &BinaryExpression) {
+        self.visit_single_expression(&node.right);
+        // The target itself is written rather than read
+        match &*node.left {
+            SingleExpression::Identifier(_) => {}
+            SingleExpression::MemberDot(member) => self.visit_single_expression(&member.target),
+            target => self.visit_single_expression(target),
+        }
+        self.assign(&node.left);
+    }
+
+    fn visit_unary_expression(&mut self, node: &UnaryExpression) {
+        self.visit_single_expression(&node.expr);
+        if let UnaryOperator::PostIncrement(_) | UnaryOperator::PostDecrement(_) = node.op {
+            self.assign(&node.expr);
+        }
+    }
+
+    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
+        let member = node.expression.as_identifier().map(|i| &i.ident.value);
+        if let (SingleExpression::This(_), Some(name)) = (&*node.target, member) {
+            if let Some(assigned) = self.state.fields.get_mut(name.as_str()) {
+                if !*assigned {
+                    // Only the first read is reported
+                    *assigned = true;
+                    self.errors.push(SemanticError::UninitializedField {
+                        name: name.into(),
+                        span: node.span(),
+                    });
+                }
+            }
+        }
+        // Members aren't variables
+        self.visit_single_expression(&node.target);
+    }
+
+    fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
+        let name = &node.ident.value;
+        if let Some(local) = self.state.lookup(name) {
+            if !local.assigned {
+                // Only the first read is reported
+                local.assigned = true;
+                self.errors.push(SemanticError::UseBeforeAssignment {
+                    name: name.into(),
+                    span: node.span(),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::GlobalSemanticResolver;
+
+    use super::*;
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_parser::Parser;
+    use jswt_tokenizer::Tokenizer;
+
+    #[test]
+    fn test_definitely_assigned_variables() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_definitely_assigned_variables",
+            r"
+        class Point {
+            readonly x: i32;
+            y: i32;
+
+            constructor(x: i32) {
+                this.x = x;
+                if (x > 0) {
+                    this.y = x;
+                } else {
+                    this.y = 0;
+                }
+                this.y = this.x + this.y;
+            }
+        }
+
+        function test(a: i32): i32 {
+            let b: i32;
+            if (a > 0) {
+                b = 1;
+            } else {
+                return 0;
+            }
+            let c = b + a;
+            c = c + 1;
+            return c;
+        }
+        ",
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+
+        assert_eq!(local.errors, vec![]);
+    }
+
+    #[test]
+    fn test_error_on_invalid_assignments() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_error_on_invalid_assignments",
+            r"
+        const LIMIT = 10;
+
+        class Point {
+            readonly x: i32;
+            y: i32;
+
+            constructor(x: i32) {
+                this.y = this.x + 1;
+                this.x = x;
+            }
+
+            move(x: i32) {
+                this.x = x;
+            }
+        }
+
+        function test(a: i32, p: Point) {
+            const b = 1;
+            b = 2;
+            const c: i32;
+            LIMIT = 5;
+            p.x = 1;
+            let d: i32;
+            if (a > 0) {
+                d = 1;
+            }
+            a = d;
+            let e = e;
+            let f: i32;
+            while (a > 0) {
+                f = a;
+            }
+            a = f;
+        }
+        ",
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+
+        assert_debug_snapshot!(local.errors);
+    }
+}
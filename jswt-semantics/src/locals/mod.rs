@@ -1,24 +1,35 @@
+mod annotations;
 mod class;
 mod functions;
 mod identifier;
+mod initialization;
 mod new;
+mod returns;
+mod type_annotations;
 mod variables;
 
+pub(crate) use self::returns::terminates;
 use self::{
-    class::ClassLocalContext, functions::FunctionsLocalContext,
-    identifier::IdentifierExpressionLocalContext, new::NewExpressionLocalContext,
+    annotations::AnnotationsLocalContext, class::ClassLocalContext,
+    functions::FunctionsLocalContext, identifier::IdentifierExpressionLocalContext,
+    initialization::InitializationLocalContext, new::NewExpressionLocalContext,
+    returns::ReturnsLocalContext, type_annotations::TypeAnnotationsLocalContext,
     variables::VariableDeclarationLocalContext,
 };
 use crate::SemanticError;
 use jswt_ast::{visit::*, *};
-use jswt_common::Spannable;
-use jswt_symbols::{BindingsTable, ScopedSymbolTable};
+use jswt_symbols::{BindingsTable, ResolutionTable, ScopedSymbolTable};
+use std::borrow::Cow;
 
 #[derive(Debug)]
 pub struct LocalSemanticResolver<'a> {
     pub symbols: &'a mut ScopedSymbolTable,
     pub bindings: &'a mut BindingsTable,
     pub errors: Vec<SemanticError>,
+    /// Declarations that identifiers resolve to
+    pub resolutions: ResolutionTable,
+    /// Name of the class whose members are being resolved
+    pub class: Option<Cow<'static, str>>,
 }
 
 impl<'a> LocalSemanticResolver<'a> {
@@ -27,6 +38,8 @@ impl<'a> LocalSemanticResolver<'a> {
             symbols,
             bindings,
             errors: vec![],
+            resolutions: ResolutionTable::default(),
+            class: None,
         }
     }
 
@@ -42,8 +55,14 @@ impl<'a> LocalSemanticResolver<'a> {
 }
 
 impl<'a> Visitor for LocalSemanticResolver<'a> {
+    fn visit_program(&mut self, node: &Program) {
+        let mut ctx = InitializationLocalContext::new(self);
+        ctx.visit_program(node);
+        walk_program(self, node);
+    }
+
     fn visit_block_statement(&mut self, node: &BlockStatement) {
-        self.symbols.push_scope(node.span());
+        self.symbols.push_scope(node.id);
         walk_block_statement(self, node);
         self.symbols.pop_scope();
     }
@@ -51,6 +70,8 @@ impl<'a> Visitor for LocalSemanticResolver<'a> {
     fn visit_variable_statement(&mut self, node: &VariableStatement) {
         let mut ctx = VariableDeclarationLocalContext::new(self);
         ctx.visit_variable_statement(node);
+        let mut ctx = TypeAnnotationsLocalContext::new(self);
+        ctx.visit_variable_statement(node);
         visit::walk_variable_statement(self, node);
     }
 
@@ -60,21 +81,46 @@ impl<'a> Visitor for LocalSemanticResolver<'a> {
         walk_identifier_expression(self, node);
     }
 
+    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
+        // Members are resolved against the target by the type checker
+        self.visit_single_expression(&node.target);
+    }
+
     fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        let mut ctx = AnnotationsLocalContext::new(self);
+        ctx.visit_function_declaration(node);
         let mut ctx = FunctionsLocalContext::new(self);
         ctx.visit_function_declaration(node);
+        let mut ctx = ReturnsLocalContext::new(self);
+        ctx.visit_function_declaration(node);
+        let mut ctx = TypeAnnotationsLocalContext::new(self);
+        ctx.visit_function_declaration(node);
         walk_function_declaration(self, node);
     }
 
     fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
+        let mut ctx = AnnotationsLocalContext::new(self);
+        ctx.visit_class_declaration(node);
         let mut ctx = ClassLocalContext::new(self, node);
         ctx.visit_class_declaration(&node);
+        let mut ctx = ReturnsLocalContext::new(self);
+        ctx.visit_class_declaration(node);
+        let mut ctx = TypeAnnotationsLocalContext::new(self);
+        ctx.visit_class_declaration(node);
+        self.class = Some(node.ident.value.clone().into());
         walk_class_declaration(self, node);
+        self.class = None;
     }
 
     fn visit_new(&mut self, node: &NewExpression) {
         let mut ctx = NewExpressionLocalContext::new(self);
         ctx.visit_new(node);
+        // The class name was checked above, only the arguments are expressions
+        if let Some(arguments) = node.expression.as_arguments() {
+            for argument in arguments.arguments.arguments.iter() {
+                self.visit_single_expression(argument);
+            }
+        }
     }
 
     // fn visit_new(&mut self, node: &NewExpression) {
@@ -83,29 +129,6 @@ impl<'a> Visitor for LocalSemanticResolver<'a> {
     //     // ctx.visit_new(node);
     // }
 
-    // fn visit_if_statement(&mut self, node: &IfStatement) {
-    //     match node.condition {
-    //         SingleExpression::Equality(_)
-    //         | SingleExpression::Relational(_)
-    //         | SingleExpression::Literal(Literal::Boolean(_)) => {
-    //             // Valid boolean expression
-    //         }
-    //         SingleExpression::Arguments(_) => {
-    //             // TODO type check the function
-    //         }
-    //         SingleExpression::Identifier(_) => {} // TODO Type check the symbol
-    //         _ => {
-    //             let error = SemanticError::TypeError {
-    //                 span: node.condition.span(),
-    //                 offending_token: node.condition.span(),
-    //                 expected: "Boolean",
-    //             };
-    //             self.errors.push(error);
-    //         }
-    //     }
-    //     walk_if_statement(self, node);
-    // }
-
     // fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
     //     let ident = &node.ident;
     //     let name = &ident.value;
@@ -144,29 +167,6 @@ impl<'a> Visitor for LocalSemanticResolver<'a> {
     //     self.in_this_expr = false;
     // }
 }
-// impl<'a> StatementVisitor<()> for Resolver<'a> {
-
-//     fn visit_if_statement(&mut self, node: &IfStatement) {
-//         match node.condition {
-//             SingleExpression::Equality(_)
-//             | SingleExpression::Relational(_)
-//             | SingleExpression::Literal(Literal::Boolean(_)) => {
-//                 // Valid boolean expression
-//             }
-//             SingleExpression::Arguments(_) => {
-//                 // TODO type check the function
-//             }
-//             SingleExpression::Identifier(_) => {} // TODO Type check the symbol
-//             _ => {
-//                 let error = SemanticError::TypeError {
-//                     span: node.condition.span(),
-//                     offending_token: node.condition.span(),
-//                     expected: "Boolean",
-//                 };
-//                 self.errors.push(error);
-//             }
-//         }
-//     }
 
 // impl<'a> ExpressionVisitor<Type> for Resolver<'a> {
 
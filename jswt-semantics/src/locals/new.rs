@@ -30,7 +30,7 @@ impl<'a> Visitor for NewExpressionLocalContext<'a> {
 
         if let None = self.bindings.lookup(ident) {
             self.errors.push(SemanticError::ClassNotDefined {
-                ident: ident.clone(),
+                ident: ident.into(),
                 span: ident_exp.span(),
             });
         }
@@ -94,4 +94,31 @@ mod test {
 
         assert_debug_snapshot!(local);
     }
+
+    #[test]
+    fn test_error_on_undefined_constructor_argument() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_error_on_undefined_constructor_argument",
+            r"
+        class Test {
+            constructor(x: i32) {}
+        }
+
+        function test() {
+            new Test(x);
+        }
+        ",
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+
+        assert_debug_snapshot!(local.errors);
+    }
 }
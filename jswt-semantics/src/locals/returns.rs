@@ -0,0 +1,204 @@
+use super::LocalSemanticResolver;
+use crate::SemanticError;
+use jswt_ast::{
+    visit::Visitor, Annotation, BlockStatement, ClassMethodElement, FunctionDeclarationElement,
+    Identifier, IterationStatement, Literal, SingleExpression, StatementElement, TypeAnnotation,
+};
+use jswt_common::Spannable;
+use jswt_types::Type;
+
+/// Checks that functions returning a value return on every code path
+pub struct ReturnsLocalContext<'a> {
+    errors: &'a mut Vec<SemanticError>,
+}
+
+impl<'a> ReturnsLocalContext<'a> {
+    pub fn new(resolver: &'a mut LocalSemanticResolver) -> Self {
+        Self {
+            errors: &mut resolver.errors,
+        }
+    }
+
+    fn check_returns(
+        &mut self,
+        annotations: &[Annotation],
+        ident: &Identifier,
+        returns: &Option<TypeAnnotation>,
+        body: &BlockStatement,
+    ) {
+        // Bodies of these functions are provided by the host or inlined
+        // as WAST so they don't contain any statements to analyze
+        let is_exempt = annotations
+            .iter()
+            .any(|a| a.name.value == "wast" || a.name.value == "native");
+        let returns_value = returns
+            .as_ref()
+            .map(|annotation| annotation.ty != Type::Void)
+            .unwrap_or(false);
+
+        if returns_value && !is_exempt && !block_returns(body) {
+            self.errors.push(SemanticError::MissingReturn {
+                name: ident.value.clone().into(),
+                span: ident.span(),
+            });
+        }
+    }
+}
+
+/// Returns true if the statement returns from the function on every path
+fn returns(node: &StatementElement) -> bool {
+    match node {
+        StatementElement::Return(_) => true,
+        StatementElement::Block(block) => block_returns(block),
+        StatementElement::If(stmt) => match &stmt.alternative {
+            Some(alternative) => returns(&stmt.consequence) && returns(alternative),
+            None => false,
+        },
+        StatementElement::Iteration(stmt) => diverges(stmt),
+        _ => false,
+    }
+}
+
+fn block_returns(node: &BlockStatement) -> bool {
+    node.statements.statements.iter().any(returns)
+}
+
+/// Returns true if control never continues past the statement
+pub(crate) fn terminates(node: &StatementElement) -> bool {
+    match node {
+        StatementElement::Return(_)
+        | StatementElement::Break(_)
+        | StatementElement::Continue(_) => true,
+        StatementElement::Block(block) => block.statements.statements.iter().any(terminates),
+        StatementElement::If(stmt) => match &stmt.alternative {
+            Some(alternative) => terminates(&stmt.consequence) && terminates(alternative),
+            None => false,
+        },
+        StatementElement::Iteration(stmt) => diverges(stmt),
+        _ => false,
+    }
+}
+
+/// Returns true for loops like `while (true) {}` that can only be left
+/// by returning from the function
+fn diverges(node: &IterationStatement) -> bool {
+    match node {
+        IterationStatement::While(stmt) => {
+            let is_infinite = matches!(
+                &stmt.expression,
+                SingleExpression::Literal(Literal::Boolean(lit)) if lit.value
+            );
+            is_infinite && !stmt.block.statements.statements.iter().any(breaks)
+        }
+    }
+}
+
+/// Returns true if the statement breaks out of the loop it's in. Breaks
+/// in nested loops only leave the nested loop.
+fn breaks(node: &StatementElement) -> bool {
+    match node {
+        StatementElement::Break(_) => true,
+        StatementElement::Block(block) => block.statements.statements.iter().any(breaks),
+        StatementElement::If(stmt) => {
+            breaks(&stmt.consequence) || stmt.alternative.as_deref().map_or(false, breaks)
+        }
+        _ => false,
+    }
+}
+
+impl<'a> Visitor for ReturnsLocalContext<'a> {
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        self.check_returns(
+            &node.decorators.annotations,
+            &node.ident,
+            &node.returns,
+            &node.body,
+        );
+    }
+
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        self.check_returns(&node.annotations, &node.ident, &node.returns, &node.body);
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::GlobalSemanticResolver;
+
+    use super::*;
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_parser::Parser;
+    use jswt_symbols::{BindingsTable, ScopedSymbolTable};
+    use jswt_tokenizer::Tokenizer;
+
+    #[test]
+    fn test_error_on_missing_returns() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_error_on_missing_returns",
+            r#"
+        function empty(): i32 {}
+
+        function partial(a: i32): i32 {
+            if (a > 0) {
+                return 1;
+            }
+        }
+
+        function complete(a: i32): i32 {
+            if (a > 0) {
+                return 1;
+            } else {
+                return 0;
+            }
+        }
+
+        function nothing() {}
+
+        @wast(code = "(i32.const 1)")
+        function inlined(): i32 {}
+
+        class Test {
+            value(): i32 {}
+        }
+
+        function forever(a: i32): i32 {
+            while (true) {
+                while (a > 0) {
+                    break;
+                }
+                if (a > 1) {
+                    return a;
+                }
+            }
+        }
+
+        function escapes(a: i32): i32 {
+            while (true) {
+                if (a > 1) {
+                    break;
+                }
+                return a;
+            }
+        }
+
+        function conditional(a: i32): i32 {
+            while (a > 0) {
+                return a;
+            }
+        }
+        "#,
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+
+        assert_debug_snapshot!(local.errors);
+    }
+}
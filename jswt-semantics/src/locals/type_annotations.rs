@@ -0,0 +1,133 @@
+use super::LocalSemanticResolver;
+use crate::SemanticError;
+use jswt_ast::{visit::*, *};
+use jswt_symbols::BindingsTable;
+use jswt_types::{ObjectType, Type};
+
+/// Checks that the types named in annotations exist. Annotations that
+/// name a class that was never declared would otherwise be treated as
+/// unknown and accepted everywhere by the type checker.
+pub struct TypeAnnotationsLocalContext<'a> {
+    bindings: &'a BindingsTable,
+    errors: &'a mut Vec<SemanticError>,
+}
+
+impl<'a> TypeAnnotationsLocalContext<'a> {
+    pub fn new(resolver: &'a mut LocalSemanticResolver) -> Self {
+        Self {
+            bindings: resolver.bindings,
+            errors: &mut resolver.errors,
+        }
+    }
+
+    fn check_annotation(&mut self, annotation: &TypeAnnotation) {
+        if let Some(name) = self.undefined_type(&annotation.ty) {
+            self.errors.push(SemanticError::TypeNotDefined {
+                name,
+                span: annotation.span.clone(),
+            });
+        }
+    }
+
+    fn check_signature(&mut self, params: &FormalParameterList, returns: Option<&TypeAnnotation>) {
+        for param in params.parameters.iter() {
+            self.check_annotation(&param.type_annotation);
+        }
+        if let Some(returns) = returns {
+            self.check_annotation(returns);
+        }
+    }
+
+    /// Finds the name of the first type referred to by `ty` that isn't defined
+    fn undefined_type(&self, ty: &Type) -> Option<std::borrow::Cow<'static, str>> {
+        match ty {
+            Type::Object(ObjectType::Reference(name)) if self.bindings.lookup(name).is_none() => {
+                Some(name.clone())
+            }
+            Type::Object(ObjectType::Array(element)) => self.undefined_type(element),
+            Type::Function(function) => function
+                .params
+                .iter()
+                .chain(std::iter::once(&*function.returns))
+                .find_map(|ty| self.undefined_type(ty)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Visitor for TypeAnnotationsLocalContext<'a> {
+    fn visit_variable_statement(&mut self, node: &VariableStatement) {
+        if let Some(annotation) = &node.type_annotation {
+            self.check_annotation(annotation);
+        }
+    }
+
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        self.check_signature(&node.params, node.returns.as_ref());
+    }
+
+    fn visit_class_constructor_declaration(&mut self, node: &ClassConstructorElement) {
+        self.check_signature(&node.params, None);
+    }
+
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        self.check_signature(&node.params, node.returns.as_ref());
+    }
+
+    fn visit_class_field_declaration(&mut self, node: &ClassFieldElement) {
+        self.check_annotation(&node.type_annotation);
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::GlobalSemanticResolver;
+
+    use super::*;
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_parser::Parser;
+    use jswt_symbols::{BindingsTable, ScopedSymbolTable};
+    use jswt_tokenizer::Tokenizer;
+
+    #[test]
+    fn test_error_on_undefined_types_in_annotations() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_error_on_undefined_types_in_annotations",
+            r"
+        class Point {
+            x: i32;
+            next: Node;
+
+            constructor(x: boolean) {
+            }
+
+            scale(factor: f32): Pointer {
+                let valid: bool = true;
+            }
+        }
+
+        function less(a: i32, b: i32): bool {
+            let points: Point[] = [];
+            let nodes: Node[] = [];
+        }
+        ",
+        );
+        let ast = Parser::new(&mut tokenizer).parse();
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+
+        let errors: Vec<_> = local
+            .errors
+            .into_iter()
+            .filter(|error| matches!(error, SemanticError::TypeNotDefined { .. }))
+            .collect();
+        assert_debug_snapshot!(errors);
+    }
+}
@@ -5,7 +5,7 @@ use jswt_ast::{
     AssignableElement, VariableStatement,
 };
 use jswt_common::Spannable;
-use jswt_symbols::{ScopedSymbolTable, Symbol};
+use jswt_symbols::{DefId, ScopedSymbolTable, Symbol};
 
 pub struct VariableDeclarationLocalContext<'a> {
     symbols: &'a mut ScopedSymbolTable,
@@ -32,7 +32,7 @@ impl<'a> Visitor for VariableDeclarationLocalContext<'a> {
             // Error if a variable name collides with an already defined variable
             if self.symbols.lookup(name).is_some() {
                 let error = SemanticError::VariableAlreadyDefined {
-                    name: name.clone(),
+                    name: name.into(),
                     span: node.target.span(),
                 };
                 self.errors.push(error);
@@ -42,10 +42,10 @@ impl<'a> Visitor for VariableDeclarationLocalContext<'a> {
             let declared_type = node
                 .type_annotation
                 .as_ref()
-                .map(|t| Symbol::ty(t.ty.clone()))
+                .map(|t| Symbol::variable(&node.modifier, t.ty.clone()))
                 .unwrap_or(Symbol::Unknown);
 
-            self.symbols.define(name, declared_type);
+            self.symbols.define(name, declared_type, DefId(node.id));
         }
     }
 }
@@ -1,185 +1,968 @@
-use crate::{SemanticError, SymbolTable};
-use jswt_ast::mut_visit::*;
+use std::borrow::Cow;
+
+use crate::SemanticError;
+use jswt_ast::visit::*;
 use jswt_ast::*;
-use jswt_common::{Type, Typeable};
-use jswt_symbols::{BindingsTable, Symbol};
+use jswt_common::{Identifiable, Span, Spannable};
+use jswt_symbols::{
+    BindingsTable, DefId, FunctionSignature, ResolutionTable, ScopedSymbolTable, Symbol,
+};
+use jswt_types::{FunctionType, ObjectType, PrimitiveType, Type, TypeTable};
 
+/// Bidirectional type checker. Expression types are inferred bottom up and
+/// checked against the type expected by their context where one is known,
+/// such as a declared variable type, a parameter or a return type.
+/// Inferred types are recorded in a [TypeTable] keyed by the id of
+/// each expression so later passes can look them up.
+///
+/// This runs after the local semantic pass and reuses its scopes.
+/// Identifiers are looked up through the declarations the local pass
+/// resolved them to.
+/// Unknown types are never reported as mismatches since whatever made
+/// them unknown has already been reported.
 pub struct TypeChecker<'a> {
-    pub symbols: &'a mut SymbolTable,
+    pub symbols: &'a mut ScopedSymbolTable,
     pub bindings: &'a mut BindingsTable,
+    pub resolutions: &'a ResolutionTable,
     pub errors: Vec<SemanticError>,
-    binding_context: Option<String>,
+    pub types: TypeTable,
+    // Name of the class whose members are being checked
+    class: Option<Cow<'static, str>>,
 }
 
 impl<'a> TypeChecker<'a> {
-    pub fn new(symbols: &'a mut SymbolTable, bindings: &'a mut BindingsTable) -> Self {
+    pub fn new(
+        bindings: &'a mut BindingsTable,
+        symbols: &'a mut ScopedSymbolTable,
+        resolutions: &'a ResolutionTable,
+    ) -> Self {
         Self {
             symbols,
             bindings,
+            resolutions,
             errors: vec![],
-            binding_context: None,
+            types: TypeTable::default(),
+            class: None,
         }
     }
 
-    pub fn resolve(&mut self, ast: &mut Ast) {
+    pub fn resolve(&mut self, ast: &Ast) {
         debug_assert!(self.symbols.depth() == 1);
-        self.visit_program(&mut ast.program);
+        self.visit_program(&ast.program);
         debug_assert!(self.symbols.depth() == 1);
     }
+
+    pub fn errors(&mut self) -> &mut Vec<SemanticError> {
+        &mut self.errors
+    }
+
+    /// Resolves a declared type. References to classes that don't
+    /// exist can't be checked against so they are treated as unknown.
+    fn resolve_type(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Object(ObjectType::Reference(name)) if self.bindings.lookup(name).is_none() => {
+                Type::Unknown
+            }
+            Type::Object(ObjectType::Array(element)) => Type::array(self.resolve_type(element)),
+            _ => ty.clone(),
+        }
+    }
+
+    /// Infers the type of an expression
+    fn infer(&mut self, node: &SingleExpression) -> Type {
+        self.visit_single_expression(node);
+        self.types.ty(node.id())
+    }
+
+    /// Checks the expression against the type expected by its context
+    fn check(&mut self, node: &SingleExpression, expected: &Type) {
+        if type_literal(&mut self.types, &mut self.errors, node, expected) {
+            return;
+        }
+
+        // Array literals take their element type from the context
+        if let (
+            SingleExpression::Literal(Literal::Array(array)),
+            Type::Object(ObjectType::Array(element)),
+        ) = (node, expected)
+        {
+            for item in array.elements.iter() {
+                self.check(item, element);
+            }
+            self.types.insert(array.id, expected.clone());
+            return;
+        }
+
+        let found = self.infer(node);
+        if !found.is_assignable_to(expected) {
+            self.errors.push(SemanticError::TypeMismatch {
+                expected: expected.clone(),
+                found,
+                span: node.span(),
+            });
+        }
+    }
+
+    /// Checks an argument against the type of the parameter it is passed to
+    fn check_argument(&mut self, node: &SingleExpression, expected: &Type, declaration: &Span) {
+        if type_literal(&mut self.types, &mut self.errors, node, expected) {
+            return;
+        }
+        // Mismatched elements are reported individually
+        if let SingleExpression::Literal(Literal::Array(_)) = node {
+            self.check(node, expected);
+            return;
+        }
+
+        let found = self.infer(node);
+        if !found.is_assignable_to(expected) {
+            self.errors.push(SemanticError::ArgumentTypeMismatch {
+                expected: expected.clone(),
+                found,
+                span: node.span(),
+                declaration: declaration.clone(),
+            });
+        }
+    }
+
+    /// Resolves where the function or method being called is declared.
+    /// Expects the callee to have already been inferred.
+    fn declaration(&mut self, callee: &SingleExpression) -> Option<Span> {
+        match callee {
+            SingleExpression::Identifier(ident) => match self.symbol(ident) {
+                Some(Symbol::Function(signature)) => Some(signature.span.clone()),
+                _ => None,
+            },
+            SingleExpression::MemberDot(member) => {
+                let class = self.types.ty(member.target.id()).binding_name()?;
+                let name = &member.expression.as_identifier()?.ident.value;
+                let method = self.bindings.lookup(&class)?.method(name)?;
+                Some(method.signature.span.clone())
+            }
+            _ => None,
+        }
+    }
+
+    fn invalid_operand(&mut self, operator: &'static str, found: Type, span: Span) {
+        if !found.is_unknown() {
+            self.errors.push(SemanticError::InvalidOperand {
+                operator,
+                found,
+                span,
+            });
+        }
+    }
+
+    /// Resolves the type of a field or method on a value of the given type
+    fn member_type(&mut self, target: &Type, member: &Identifier) -> Type {
+        let binding = match target.binding_name() {
+            Some(name) => match self.bindings.lookup(&name) {
+                Some(binding) => binding,
+                None => return Type::Unknown,
+            },
+            None => return Type::Unknown,
+        };
+
+        if let Some(field) = binding.field(&member.value) {
+            return self.resolve_type(&field.ty);
+        }
+        if let Some(method) = binding.method(&member.value) {
+            let signature = method.signature.clone();
+            return Type::Function(FunctionType {
+                params: signature.params,
+                returns: Box::new(signature.returns),
+            });
+        }
+
+        self.errors.push(SemanticError::PropertyNotDefined {
+            name: member.value.clone().into(),
+            span: member.span(),
+        });
+        Type::Unknown
+    }
+
+    /// Resolves the method overloading an operator on the class of its left operand
+    fn operator_method(&self, operand: &Type, op: &BinaryOperator) -> Option<FunctionSignature> {
+        let name = op.method_name()?;
+        let binding = self.bindings.lookup(&operand.binding_name()?)?;
+        Some(binding.method(name)?.signature.clone())
+    }
+
+    /// Looks up the symbol of the declaration an identifier resolves to.
+    /// Undefined variables are reported by the local semantic pass.
+    fn symbol(&self, node: &IdentifierExpression) -> Option<&Symbol> {
+        let def = self.resolutions.get(node.id)?;
+        self.symbols.lookup_resolved(&node.ident.value, def)
+    }
+
+    /// Defines the parameters in the current scope with their declared types
+    fn define_parameters(&mut self, params: &FormalParameterList) {
+        for param in params.parameters.iter() {
+            let ty = self.resolve_type(&param.type_annotation.ty);
+            self.symbols
+                .define(&param.ident.value, Symbol::ty(ty), DefId(param.id));
+        }
+    }
 }
 
-impl<'a> MutVisitor for TypeChecker<'a> {
-    fn visit_class_declaration(&mut self, node: &mut ClassDeclarationElement) {
-        let name = node.ident.value.clone();
-        self.binding_context = Some(name.to_string());
-        walk_class_declaration(self, node);
-        self.binding_context = None;
+fn is_numeric(ty: &Type) -> bool {
+    is_integer(ty) || is_float(ty)
+}
+
+fn is_integer(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Primitive(PrimitiveType::I32 | PrimitiveType::U32 | PrimitiveType::I64)
+    )
+}
+
+fn is_float(ty: &Type) -> bool {
+    matches!(ty, Type::Primitive(PrimitiveType::F32 | PrimitiveType::F64))
+}
+
+/// Returns true if a value of type `from` can be explicitly cast to `to`.
+/// Numeric casts convert between integers and floats of any width, casts
+/// from integers to `boolean` test whether the value is non-zero, casts
+/// between `ptr` and `f32` reinterpret the underlying bits and everything
+/// else that is an address or integer at runtime is a no-op.
+fn is_valid_cast(from: &Type, to: &Type, is_class: impl Fn(&Type) -> bool) -> bool {
+    let is_raw_address = |ty: &Type| *ty == Type::I32 || *ty == Type::PTR;
+    let is_number = |ty: &PrimitiveType| {
+        matches!(
+            ty,
+            PrimitiveType::I32
+                | PrimitiveType::U32
+                | PrimitiveType::I64
+                | PrimitiveType::F32
+                | PrimitiveType::F64
+        )
+    };
+    // Booleans convert to and from integers of any width
+    let is_integral = |ty: &PrimitiveType| {
+        matches!(
+            ty,
+            PrimitiveType::I32 | PrimitiveType::U32 | PrimitiveType::I64 | PrimitiveType::Boolean
+        )
+    };
+    match (from, to) {
+        _ if from == to => true,
+        (Type::Primitive(from), Type::Primitive(to)) if is_number(from) && is_number(to) => true,
+        (Type::Primitive(from), Type::Primitive(to)) if is_integral(from) && is_integral(to) => {
+            true
+        }
+        (Type::Primitive(from), Type::Primitive(to)) => matches!(
+            (from, to),
+            (PrimitiveType::Ptr, PrimitiveType::F32)
+                | (PrimitiveType::F32, PrimitiveType::Ptr)
+                | (PrimitiveType::I32, PrimitiveType::Ptr)
+                | (PrimitiveType::Ptr, PrimitiveType::I32)
+        ),
+        // Classes can only be converted to and from raw addresses
+        (from, to) if is_class(from) => is_raw_address(to),
+        (from, to) if is_class(to) => is_raw_address(from),
+        _ => false,
     }
+}
 
-    fn visit_function_declaration(&mut self, node: &mut FunctionDeclarationElement) {
-        // Push a new local scope for the function
-        // Scope definition should have been defined during the global pass
-        self.symbols.push_scope();
+/// Numeric literals take the width of the number type expected by
+/// their context so `let x: i64 = 1` doesn't require a cast
+fn type_literal(
+    types: &mut TypeTable,
+    errors: &mut Vec<SemanticError>,
+    node: &SingleExpression,
+    expected: &Type,
+) -> bool {
+    type_signed_literal(types, errors, node, expected, false)
+}
 
-        // Add function parameters as variables in scope
-        for param in node.params.parameters.iter() {
-            // Resolve Type from Type Annotation
-            let param_name = &param.ident.value;
-            self.symbols.define(
-                param_name.clone(),
-                Symbol::ty(param.type_annotation.ty.clone()),
-            );
+/// Types a literal that is negated if `negative` is set
+fn type_signed_literal(
+    types: &mut TypeTable,
+    errors: &mut Vec<SemanticError>,
+    node: &SingleExpression,
+    expected: &Type,
+    negative: bool,
+) -> bool {
+    match node {
+        SingleExpression::Literal(Literal::Integer(lit)) if is_integer(expected) => {
+            check_integer_range(errors, lit, negative, expected);
+            types.insert(lit.id, expected.clone());
+            true
+        }
+        SingleExpression::Literal(Literal::Float(lit)) if is_float(expected) => {
+            types.insert(lit.id, expected.clone());
+            true
         }
+        SingleExpression::Unary(unary)
+            if matches!(unary.op, UnaryOperator::Minus(_) | UnaryOperator::Plus(_)) =>
+        {
+            let negative = negative ^ matches!(unary.op, UnaryOperator::Minus(_));
+            let is_literal = type_signed_literal(types, errors, &unary.expr, expected, negative);
+            if is_literal {
+                types.insert(unary.id, expected.clone());
+            }
+            is_literal
+        }
+        _ => false,
+    }
+}
 
-        walk_function_declaration(self, node);
+/// Reports integer literals that don't fit in the type they're given.
+/// Literals themselves are never negative, `negative` is set when the
+/// literal is negated.
+fn check_integer_range(
+    errors: &mut Vec<SemanticError>,
+    lit: &IntegerLiteral,
+    negative: bool,
+    ty: &Type,
+) {
+    let value = if negative { -lit.value } else { lit.value };
+    let fits = match ty {
+        Type::Primitive(PrimitiveType::I32) => {
+            (i64::from(i32::MIN)..=i64::from(i32::MAX)).contains(&value)
+        }
+        Type::Primitive(PrimitiveType::U32) => (0..=i64::from(u32::MAX)).contains(&value),
+        _ => true,
+    };
+    if !fits {
+        errors.push(SemanticError::IntegerOutOfRange {
+            ty: ty.clone(),
+            span: lit.span.clone(),
+        });
+    }
+}
+
+fn binary_operator(op: &BinaryOperator) -> &'static str {
+    match op {
+        BinaryOperator::Plus(_) => "+",
+        BinaryOperator::Minus(_) => "-",
+        BinaryOperator::Mult(_) => "*",
+        BinaryOperator::Div(_) => "/",
+        BinaryOperator::Equal(_) => "==",
+        BinaryOperator::NotEqual(_) => "!=",
+        BinaryOperator::Greater(_) => ">",
+        BinaryOperator::GreaterEqual(_) => ">=",
+        BinaryOperator::Less(_) => "<",
+        BinaryOperator::LessEqual(_) => "<=",
+        BinaryOperator::And(_) => "&",
+        BinaryOperator::Or(_) => "|",
+        BinaryOperator::Assign(_) => "=",
+    }
+}
+
+impl<'a> Visitor for TypeChecker<'a> {
+    fn visit_class_declaration(&mut self, node: &ClassDeclarationElement) {
+        self.class = Some(node.ident.value.clone().into());
+        walk_class_declaration(self, node);
+        self.class = None;
+    }
+
+    fn visit_class_body(&mut self, node: &ClassBody) {
+        self.symbols.push_scope(node.id);
+        walk_class_body(self, node);
         self.symbols.pop_scope();
     }
 
-    fn visit_class_constructor_declaration(&mut self, node: &mut ClassConstructorElement) {
-        self.symbols.push_scope();
-        // Add function parameters as variables in scope
-        for param in node.params.parameters.iter() {
-            // Resolve Type from Type Annotation
-            let param_name = &param.ident.value;
-            self.symbols.define(
-                param_name.clone(),
-                Symbol::ty(param.type_annotation.ty.clone()),
-            );
-        }
+    fn visit_function_declaration(&mut self, node: &FunctionDeclarationElement) {
+        // Parameters are scoped to the function body
+        self.symbols.push_scope(node.body.id);
+        self.define_parameters(&node.params);
+        walk_function_declaration(self, node);
+        self.symbols.pop_scope();
+    }
 
+    fn visit_class_constructor_declaration(&mut self, node: &ClassConstructorElement) {
+        self.symbols.push_scope(node.body.id);
+        self.define_parameters(&node.params);
         walk_class_constructor_declaration(self, node);
         self.symbols.pop_scope();
     }
 
-    fn visit_class_method_declaration(&mut self, node: &mut ClassMethodElement) {
-        self.symbols.push_scope();
-        // Add function parameters as variables in scope
-        for param in node.params.parameters.iter() {
-            // Resolve Type from Type Annotation
-            let param_name = &param.ident.value;
-            self.symbols.define(
-                param_name.clone(),
-                Symbol::ty(param.type_annotation.ty.clone()),
-            );
-        }
-
+    fn visit_class_method_declaration(&mut self, node: &ClassMethodElement) {
+        self.symbols.push_scope(node.body.id);
+        self.define_parameters(&node.params);
         walk_class_method_declaration(self, node);
         self.symbols.pop_scope();
     }
 
-    fn visit_block_statement(&mut self, node: &mut BlockStatement) {
-        self.symbols.push_scope();
+    fn visit_block_statement(&mut self, node: &BlockStatement) {
+        self.symbols.push_scope(node.id);
         walk_block_statement(self, node);
         self.symbols.pop_scope();
     }
 
-    fn visit_variable_statement(&mut self, node: &mut VariableStatement) {
-        walk_variable_statement(self, node);
-        let expression_type = node.expression.ty();
+    fn visit_variable_statement(&mut self, node: &VariableStatement) {
+        let ty = match (&node.type_annotation, &node.expression) {
+            (Some(annotation), Some(expression)) => {
+                let declared = self.resolve_type(&annotation.ty);
+                self.check(expression, &declared);
+                declared
+            }
+            (Some(annotation), None) => self.resolve_type(&annotation.ty),
+            (None, Some(expression)) => self.infer(expression),
+            (None, None) => Type::Unknown,
+        };
 
-        // We are in a local scope
         let name = match &node.target {
             AssignableElement::Identifier(ident) => &ident.value,
         };
+        self.symbols
+            .define(name, Symbol::variable(&node.modifier, ty), DefId(node.id));
+    }
 
-        let declared_type = node.type_annotation.as_ref().map(|t| t.ty.clone());
-        if let Some(ty) = declared_type {
-            if ty != expression_type {
-                todo!(
-                    "error: declared {},  but found {}",
-                    ty.to_string(),
-                    expression_type.to_string()
-                );
-            }
+    fn visit_if_statement(&mut self, node: &IfStatement) {
+        self.check(&node.condition, &Type::BOOLEAN);
+        self.visit_statement_element(&node.consequence);
+        if let Some(alternative) = &node.alternative {
+            self.visit_statement_element(alternative);
         }
+    }
 
-        self.symbols
-            .define(name.clone(), Symbol::ty(expression_type));
+    fn visit_while_iteration_element(&mut self, node: &WhileIterationElement) {
+        self.check(&node.expression, &Type::BOOLEAN);
+        self.visit_block_statement(&node.block);
     }
 
-    fn visit_identifier_expression(&mut self, node: &mut IdentifierExpression) {
-        let name = &node.ident.value;
-        if let Some(sym) = self.symbols.lookup(name) {
-            match sym {
-                Symbol::Type(sig) => node.ty = sig.ty.clone(),
-                // TODO: Technically incorrect
-                Symbol::Function(sig) => node.ty = sig.returns.clone(),
-                Symbol::Unknown => todo!(),
-                Symbol::Class => node.ty = Type::Binding(name.clone()),
+    fn visit_return_statement(&mut self, node: &ReturnStatement) {
+        // Return types are scoped to function bodies by the local pass
+        let expected = match self.symbols.scope_return_type().cloned() {
+            Some(returns) => self.resolve_type(&returns),
+            None => Type::Unknown,
+        };
+        match &node.expression {
+            // Void functions can't return a value
+            Some(expression) if expected == Type::Void => {
+                let found = self.infer(expression);
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected,
+                    found,
+                    span: expression.span(),
+                });
             }
+            Some(expression) => self.check(expression, &expected),
+            None if !Type::Void.is_assignable_to(&expected) => {
+                self.errors.push(SemanticError::TypeMismatch {
+                    expected,
+                    found: Type::Void,
+                    span: node.span(),
+                });
+            }
+            None => {}
         }
     }
 
-    fn visit_assignment_expression(&mut self, node: &mut BinaryExpression) {
-        walk_assignment_expression(self, node);
-        if let SingleExpression::Identifier(ident) = &mut *node.left {
-            ident.ty = node.right.ty();
+    fn visit_member_dot(&mut self, node: &MemberDotExpression) {
+        let target = self.infer(&node.target);
+        // The member is resolved against the target rather than the scope
+        let ty = match node.expression.as_identifier() {
+            Some(member) => {
+                let ty = self.member_type(&target, &member.ident);
+                self.types.insert(member.id, ty.clone());
+                ty
+            }
+            None => Type::Unknown,
+        };
+        self.types.insert(node.id, ty);
+    }
+
+    fn visit_member_index(&mut self, node: &MemberIndexExpression) {
+        let target = self.infer(&node.target);
+        self.check(&node.index, &Type::I32);
+        let ty = match target {
+            Type::Object(ObjectType::Array(element)) => *element,
+            _ => Type::Unknown,
+        };
+        self.types.insert(node.id, ty);
+    }
+
+    fn visit_new(&mut self, node: &NewExpression) {
+        // The class being constructed is called like a function
+        let class = match &*node.expression {
+            SingleExpression::Arguments(exp) => {
+                for arg in exp.arguments.arguments.iter() {
+                    self.infer(arg);
+                }
+                let ty = match exp.ident.as_identifier() {
+                    Some(ident) => self.resolve_type(&Type::from_name(&ident.ident.value)),
+                    None => Type::Unknown,
+                };
+                self.types.insert(exp.id, ty.clone());
+                ty
+            }
+            expression => self.infer(expression),
         };
+        self.types.insert(node.id, class);
     }
 
-    fn visit_argument_expression(&mut self, node: &mut ArgumentsExpression) {
-        walk_argument_expression(self, node);
-        if let Some(target) = node.ident.as_identifier() {
-            let name = &target.ident.value;
-            if let Some(Symbol::Function(sig)) = self.symbols.lookup(name) {
-                node.ty = sig.returns.clone();
+    fn visit_cast_expression(&mut self, node: &CastExpression) {
+        let from = self.infer(&node.expression);
+        let to = &node.target.ty;
+        self.types.insert(node.id, to.clone());
+
+        let is_class = |ty: &Type| match ty {
+            Type::Object(ObjectType::Reference(name)) => self.bindings.lookup(name).is_some(),
+            _ => false,
+        };
+        match to {
+            Type::Primitive(_) => {}
+            Type::Object(ObjectType::Reference(name)) if !is_class(to) => {
+                self.errors.push(SemanticError::ClassNotDefined {
+                    ident: name.clone(),
+                    span: node.target.span(),
+                });
+                return;
             }
+            Type::Object(ObjectType::Reference(_)) => {}
+            // Array, string and void casts aren't supported
+            _ => {
+                self.errors.push(SemanticError::InvalidCast {
+                    from,
+                    to: to.clone(),
+                    span: node.span(),
+                });
+                return;
+            }
+        };
+
+        if !from.is_unknown() && !is_valid_cast(&from, to, is_class) {
+            self.errors.push(SemanticError::InvalidCast {
+                from,
+                to: to.clone(),
+                span: node.span(),
+            });
         }
     }
 
-    fn visit_binary_expression(&mut self, node: &mut BinaryExpression) {
-        walk_binary_expression(self, node);
-        // TODO - evaluate this corectly
-        // let lhs = node.left.ty();
-        // let rhs = node.right.ty();
-        node.ty = node.left.ty();
+    fn visit_identifier_expression(&mut self, node: &IdentifierExpression) {
+        let name = &node.ident.value;
+        let ty = match self.symbol(node) {
+            Some(Symbol::Type(sig)) => sig.ty.clone(),
+            Some(Symbol::Function(sig)) => Type::Function(FunctionType {
+                params: sig.params.clone(),
+                returns: Box::new(sig.returns.clone()),
+            }),
+            Some(Symbol::Class) => Type::from_name(name),
+            Some(Symbol::Unknown) | None => Type::Unknown,
+        };
+        let ty = self.resolve_type(&ty);
+        self.types.insert(node.id, ty);
+    }
+
+    fn visit_argument_expression(&mut self, node: &ArgumentsExpression) {
+        let callee = self.infer(&node.ident);
+        let function = match callee {
+            Type::Function(function) => function,
+            ty => {
+                if !ty.is_unknown() {
+                    self.errors.push(SemanticError::NotAFunctionError {
+                        span: node.span(),
+                        name_span: node.ident.span(),
+                    });
+                }
+                for arg in node.arguments.arguments.iter() {
+                    self.infer(arg);
+                }
+                self.types.insert(node.id, Type::Unknown);
+                return;
+            }
+        };
+
+        let declaration = self.declaration(&node.ident);
+        if let Some(declaration) = &declaration {
+            let expected = function.params.len();
+            let found = node.arguments.arguments.len();
+            if expected != found {
+                self.errors.push(SemanticError::ArgumentCountMismatch {
+                    expected,
+                    found,
+                    span: node.span(),
+                    declaration: declaration.clone(),
+                });
+            }
+        }
+        for (i, arg) in node.arguments.arguments.iter().enumerate() {
+            match (function.params.get(i), &declaration) {
+                (Some(param), Some(declaration)) => {
+                    let param = self.resolve_type(param);
+                    self.check_argument(arg, &param, declaration);
+                }
+                (Some(param), None) => {
+                    let param = self.resolve_type(param);
+                    self.check(arg, &param);
+                }
+                (None, _) => {
+                    self.infer(arg);
+                }
+            }
+        }
+        let returns = self.resolve_type(&function.returns);
+        self.types.insert(node.id, returns);
     }
 
-    fn visit_this_expression(&mut self, node: &mut ThisExpression) {
-        node.ty = Type::Binding("ptr".into());
+    fn visit_unary_expression(&mut self, node: &UnaryExpression) {
+        // Negated literals are checked against the range of i32 as a whole
+        if let (SingleExpression::Literal(Literal::Integer(lit)), UnaryOperator::Minus(_)) =
+            (&*node.expr, &node.op)
+        {
+            check_integer_range(&mut self.errors, lit, true, &Type::I32);
+            self.types.insert(lit.id, Type::I32);
+            self.types.insert(node.id, Type::I32);
+            return;
+        }
+
+        let ty = self.infer(&node.expr);
+        let (operator, valid) = match node.op {
+            UnaryOperator::Plus(_) => ("+", is_numeric(&ty)),
+            UnaryOperator::Minus(_) => ("-", is_numeric(&ty)),
+            UnaryOperator::Not(_) => ("~", is_integer(&ty) || ty == Type::BOOLEAN),
+            UnaryOperator::PostIncrement(_) => ("++", is_numeric(&ty)),
+            UnaryOperator::PostDecrement(_) => ("--", is_numeric(&ty)),
+        };
+        if !valid {
+            self.invalid_operand(operator, ty.clone(), node.expr.span());
+        }
+        self.types.insert(node.id, ty);
     }
 
-    fn visit_member_dot(&mut self, node: &mut MemberDotExpression) {
-        walk_member_dot(self, node);
+    fn visit_assignment_expression(&mut self, node: &BinaryExpression) {
+        // Only variables, fields and array elements can be assigned to
+        let is_assignable = matches!(
+            &*node.left,
+            SingleExpression::Identifier(_)
+                | SingleExpression::MemberDot(_)
+                | SingleExpression::MemberIndex(_)
+        );
+        if !is_assignable {
+            self.errors.push(SemanticError::InvalidAssignmentTarget {
+                span: node.left.span(),
+            });
+        }
+        let target = self.infer(&node.left);
+        self.check(&node.right, &target);
+        let ty = match target {
+            Type::Unknown => self.types.ty(node.right.id()),
+            target => target,
+        };
+        self.types.insert(node.id, ty);
+    }
 
-        if let SingleExpression::This(_) = &*node.target {
-            let binding = self.binding_context.as_ref().unwrap();
-            let binding = self.bindings.lookup(binding).unwrap();
-            if let Some(target) = node.expression.as_identifier() {
-                if let Some(field) = binding.field(&target.ident.value) {
-                    node.ty = field.ty.clone();
+    fn visit_binary_expression(&mut self, node: &BinaryExpression) {
+        let left = self.infer(&node.left);
+        if let Some(signature) = self.operator_method(&left, &node.op) {
+            match signature.params.first() {
+                Some(param) => {
+                    let param = self.resolve_type(param);
+                    self.check(&node.right, &param);
+                }
+                None => {
+                    self.infer(&node.right);
                 }
             }
+            let returns = self.resolve_type(&signature.returns);
+            self.types.insert(node.id, returns);
+            return;
+        }
+
+        let valid = match node.op {
+            BinaryOperator::Plus(_) | BinaryOperator::Minus(_) => {
+                is_numeric(&left) || left == Type::PTR
+            }
+            BinaryOperator::Mult(_)
+            | BinaryOperator::Div(_)
+            | BinaryOperator::Greater(_)
+            | BinaryOperator::GreaterEqual(_)
+            | BinaryOperator::Less(_)
+            | BinaryOperator::LessEqual(_) => is_numeric(&left),
+            BinaryOperator::And(_) | BinaryOperator::Or(_) => {
+                is_integer(&left) || left == Type::BOOLEAN
+            }
+            BinaryOperator::Equal(_) | BinaryOperator::NotEqual(_) | BinaryOperator::Assign(_) => {
+                true
+            }
+        };
+
+        if valid {
+            // Pointers are offset by an integer number of bytes
+            let operand = match left {
+                Type::Primitive(PrimitiveType::Ptr) => Type::I32,
+                _ => left.clone(),
+            };
+            self.check(&node.right, &operand);
+        } else {
+            let operator = binary_operator(&node.op);
+            self.invalid_operand(operator, left.clone(), node.left.span());
+            self.infer(&node.right);
         }
+
+        let ty = match node.op {
+            BinaryOperator::Equal(_)
+            | BinaryOperator::NotEqual(_)
+            | BinaryOperator::Greater(_)
+            | BinaryOperator::GreaterEqual(_)
+            | BinaryOperator::Less(_)
+            | BinaryOperator::LessEqual(_) => Type::BOOLEAN,
+            _ if left.is_unknown() => self.types.ty(node.right.id()),
+            _ => left,
+        };
+        self.types.insert(node.id, ty);
     }
 
-    fn visit_literal(&mut self, node: &mut Literal) {
+    fn visit_this_expression(&mut self, node: &ThisExpression) {
+        let ty = match &self.class {
+            Some(class) => Type::from_name(class.clone()),
+            None => Type::Unknown,
+        };
+        self.types.insert(node.id, ty);
+    }
+
+    fn visit_error_expression(&mut self, node: &ErrorExpression) {
+        self.types.insert(node.id, Type::Unknown);
+    }
+
+    fn visit_literal(&mut self, node: &Literal) {
         match node {
-            Literal::Array(_) => todo!(),
-            Literal::String(s) => s.ty = Type::Binding("string".into()),
-            Literal::Integer(i) => i.ty = Type::Binding("i32".into()),
-            Literal::Float(f) => f.ty = Type::Binding("f32".into()),
-            Literal::Boolean(b) => b.ty = Type::Binding("boolean".into()),
+            Literal::Array(array) => {
+                let mut elements = array.elements.iter();
+                let element = match elements.next() {
+                    Some(first) => self.infer(first),
+                    None => Type::Unknown,
+                };
+                for item in elements {
+                    self.check(item, &element);
+                }
+                self.types.insert(array.id, Type::array(element));
+            }
+            Literal::String(s) => self.types.insert(s.id, Type::STRING),
+            Literal::Integer(i) => {
+                check_integer_range(&mut self.errors, i, false, &Type::I32);
+                self.types.insert(i.id, Type::I32);
+            }
+            Literal::Float(f) => self.types.insert(f.id, Type::F32),
+            Literal::Boolean(b) => self.types.insert(b.id, Type::BOOLEAN),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{GlobalSemanticResolver, LocalSemanticResolver};
+    use jswt_assert::assert_debug_snapshot;
+    use jswt_parser::Parser;
+    use jswt_tokenizer::Tokenizer;
+
+    fn check(name: &'static str, source: &'static str) -> Vec<SemanticError> {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(name, source);
+        let mut parser = Parser::new(&mut tokenizer);
+        let ast = parser.parse();
+        assert!(parser.parse_errors().is_empty());
+        let mut symbols = ScopedSymbolTable::default();
+        let mut bindings = BindingsTable::default();
+
+        let mut global = GlobalSemanticResolver::new(&mut bindings, &mut symbols);
+        global.resolve(&ast);
+        let mut local = LocalSemanticResolver::new(&mut bindings, &mut symbols);
+        local.resolve(&ast);
+        assert_eq!(local.errors, vec![]);
+
+        let resolutions = local.resolutions;
+        let mut checker = TypeChecker::new(&mut bindings, &mut symbols, &resolutions);
+        checker.resolve(&ast);
+        checker.errors
+    }
+
+    #[test]
+    fn test_type_check_valid_program() {
+        let errors = check(
+            "test_type_check_valid_program",
+            r"
+        class Point {
+            x: i32;
+            constructor(x: i32) {
+                this.x = x;
+            }
+            add(other: Point): i32 {
+                return this.x + other.x;
+            }
+        }
+
+        function distance(a: f32, b: f32): f32 {
+            if (a > b) {
+                return a - b;
+            }
+            return b - a;
+        }
+
+        function main() {
+            let p = new Point(1);
+            let x: i32 = p.add(p) * 2;
+            let d = distance(1.5, 0.5);
+            let values: i32[] = [1, 2, 3];
+            let first = values[0] + x;
+            while (first < 10) {
+                first = first + 1;
+            }
+        }
+        ",
+        );
+        assert_eq!(errors, vec![]);
+    }
+
+    #[test]
+    fn test_type_check_numeric_literals_take_type_from_context() {
+        let errors = check(
+            "test_type_check_numeric_literals_take_type_from_context",
+            r"
+        function scale(value: f64): f64 {
+            return value * 2.5;
+        }
+
+        function main() {
+            let big: i64 = 10000000000;
+            let negative: i64 = -1;
+            let unsigned: u32 = 0xFFFFFFFF;
+            let sum = big + 1;
+            let scaled = scale(1.5);
+            let wide: f32 = 1;
+        }
+        ",
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_type_check_reports_integers_out_of_range() {
+        let errors = check(
+            "test_type_check_reports_integers_out_of_range",
+            r"
+        function test() {
+            let largest: i32 = 2147483647;
+            let smallest: i32 = -2147483648;
+            let large: i32 = 2147483648;
+            let small: i32 = -2147483649;
+            let mask: i32 = 0xFFFFFFFF;
+            let default = 3000000000;
+            let negated = -2147483648;
+            let unsigned: u32 = 4294967295;
+            let zero: u32 = -0;
+            let narrow: u32 = 4294967296;
+            let negative: u32 = -1;
+            let wide: i64 = 9223372036854775807;
+            let sum: i32 = largest + 10000000000;
+        }
+        ",
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_type_check_valid_casts() {
+        let errors = check(
+            "test_type_check_valid_casts",
+            r"
+        class Test {}
+
+        function test(a: i32, b: f32, c: ptr, d: i64, e: u32, f: f64) {
+            let x = a as f32;
+            let y = b as i32;
+            let z = b as ptr;
+            let w = c as Test;
+            let v = w as i32;
+            let u = d as f64;
+            let t = e as i64;
+            let s = f as f32;
+            let r = d as boolean;
+            let q = r as u32;
+            let p = (a + 1) as f64;
+        }
+        ",
+        );
+        assert_eq!(errors, vec![]);
+    }
+
+    #[test]
+    fn test_type_check_reports_invalid_casts() {
+        let errors = check(
+            "test_type_check_reports_invalid_casts",
+            r"
+        class Test {}
+
+        function test(a: f32) {
+            a as Test;
+            true as f32;
+            a as boolean;
+            a as Unknown;
+            (a as i32) = 1;
+        }
+        ",
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_type_check_reports_argument_count_mismatches() {
+        let errors = check(
+            "test_type_check_reports_argument_count_mismatches",
+            r"
+        function add(a: i32, b: i32): i32 {
+            return a + b;
+        }
+
+        class Counter {
+            count: i32;
+            next: Counter;
+
+            increment(by: i32) {
+                this.count = this.count + by;
+                this.increment();
+            }
+        }
+
+        function test(counter: Counter) {
+            add(1, 2);
+            add(1);
+            add(1, 2, 3);
+            counter.increment(1);
+            counter.increment(1, 2);
+            counter.next.increment(1);
+            counter.next.increment();
+            new Counter().increment(1, 2);
+        }
+        ",
+        );
+        assert_debug_snapshot!(errors);
+    }
+
+    #[test]
+    fn test_type_check_reports_mismatches() {
+        let errors = check(
+            "test_type_check_reports_mismatches",
+            r#"
+        class Point {
+            x: i32;
+        }
+
+        function half(value: f32): f32 {
+            return value / 2;
+        }
+
+        function nothing() {
+            return 1;
+        }
+
+        function main(p: Point): i32 {
+            let a: i32 = 1.5;
+            let b = half(1);
+            let c = true + 1;
+            if (1) {
+                a = "text";
+            }
+            let d = p.y;
+            return;
         }
+        "#,
+        );
+        assert_debug_snapshot!(errors);
     }
 }
@@ -1,12 +1,14 @@
 use crate::FunctionSignature;
-use jswt_common::Type;
+use jswt_types::Type;
+use serde::Serialize;
 use std::{borrow::Cow, collections::BTreeMap};
 
 // Class containing all bindings that
 // we've discovered in the global scope
 // We're keeping these definitions out of the symbol table
 // to make it easier to track all the class instances we've discovered
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize)]
+#[serde(transparent)]
 pub struct BindingsTable {
     bindings: BTreeMap<Cow<'static, str>, ClassBinding>,
 }
@@ -21,7 +23,7 @@ impl BindingsTable {
     }
 }
 
-#[derive(Debug, Default, PartialEq, Clone)]
+#[derive(Debug, Default, PartialEq, Clone, Serialize)]
 pub struct ClassBinding {
     pub name: Cow<'static, str>,
     pub fields: Vec<Field>,
@@ -45,15 +47,18 @@ impl ClassBinding {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Field {
     pub name: Cow<'static, str>,
     pub index: usize,
+    /// Offset of the field in bytes from the start of the object
+    pub offset: usize,
     pub size: usize,
     pub ty: Type,
+    pub readonly: bool,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct Method {
     pub name: Cow<'static, str>,
     pub signature: FunctionSignature,
@@ -4,23 +4,35 @@ mod tables;
 use std::borrow::Cow;
 use std::collections::BTreeMap;
 
-use jswt_common::Type;
+use jswt_ast::VariableModifier;
+use jswt_common::{NodeId, Span};
+use jswt_types::Type;
+use serde::Serialize;
 
 pub use self::bindings::*;
 pub use self::tables::*;
 
-#[derive(Debug, PartialEq, Clone)]
+/// Identifies a declaration by the id of the node that declares it,
+/// such as a variable statement, a parameter or a function
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize)]
+pub struct DefId(pub NodeId);
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct FunctionSignature {
     pub params: Vec<Type>,
     pub returns: Type,
+    /// Span of the name of the function where it is declared
+    pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize)]
 pub struct TypeSignature {
     pub ty: Type,
+    /// Constants can't be reassigned after they are declared
+    pub constant: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub enum Symbol {
     Type(TypeSignature),
     Function(FunctionSignature),
@@ -30,17 +42,38 @@ pub enum Symbol {
 
 impl Symbol {
     pub fn ty(ty: Type) -> Self {
-        Symbol::Type(TypeSignature { ty })
+        Symbol::Type(TypeSignature {
+            ty,
+            constant: false,
+        })
+    }
+
+    pub fn constant(ty: Type) -> Self {
+        Symbol::Type(TypeSignature { ty, constant: true })
+    }
+
+    /// Creates a symbol for a variable declared with the given modifier
+    pub fn variable(modifier: &VariableModifier, ty: Type) -> Self {
+        match modifier {
+            VariableModifier::Let(_) => Symbol::ty(ty),
+            VariableModifier::Const(_) => Symbol::constant(ty),
+        }
     }
 
-    pub fn function(params: Vec<Type>, returns: Type) -> Self {
-        Symbol::Function(FunctionSignature { params, returns })
+    pub fn function(params: Vec<Type>, returns: Type, span: Span) -> Self {
+        Symbol::Function(FunctionSignature {
+            params,
+            returns,
+            span,
+        })
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Scope {
     pub symbols: BTreeMap<Cow<'static, str>, Symbol>,
+    /// Declarations that define the symbols in the scope
+    pub defs: BTreeMap<Cow<'static, str>, DefId>,
     pub ret: Option<Type>,
 }
 
@@ -48,6 +81,7 @@ impl Scope {
     pub fn new(ret: Option<Type>) -> Self {
         Self {
             symbols: BTreeMap::new(),
+            defs: BTreeMap::new(),
             ret,
         }
     }
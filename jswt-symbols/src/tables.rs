@@ -1,6 +1,8 @@
-use crate::{Scope, Symbol};
+use crate::{DefId, Scope, Symbol};
 use core::hash::Hash;
-use jswt_common::{Span, Type};
+use jswt_common::NodeId;
+use jswt_types::Type;
+use serde::Serialize;
 use std::{borrow::Cow, collections::BTreeMap};
 
 #[derive(Debug)]
@@ -77,34 +79,51 @@ impl<K: Eq + Hash + Ord, V> Default for SimpleSymbolTable<K, V> {
     }
 }
 
-#[derive(Debug, Default)]
-pub struct ScopedSymbolTable {
-    scopes: Vec<Span>,
-    table: BTreeMap<Span, Scope>,
+/// Maps every identifier use to the declaration it resolves to
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ResolutionTable {
+    // BTree map to enforce order in tests
+    table: BTreeMap<NodeId, DefId>,
 }
 
-impl ScopedSymbolTable {
-    // TODO - We want to have entities within the parser have
-    // their own spans. We're inserting a synthetic one for now
-    // just to make this work
-    pub fn push_global_scope(&mut self) {
-        self.push_scope(Span::new("program".into(), "root".into(), 0, 0));
+impl ResolutionTable {
+    pub fn insert(&mut self, id: NodeId, def: DefId) {
+        self.table.insert(id, def);
     }
 
+    /// Declaration the identifier with the given id resolves to
+    pub fn get(&self, id: NodeId) -> Option<DefId> {
+        self.table.get(&id).copied()
+    }
+
+    /// Every resolved identifier along with its declaration
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, DefId)> + '_ {
+        self.table.iter().map(|(id, def)| (*id, *def))
+    }
+}
+
+/// Scopes are keyed by the id of the node that introduces them,
+/// the program for the global scope or a block for local ones
+#[derive(Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct ScopedSymbolTable {
+    // Only meaningful while a pass is walking the tree
+    #[serde(skip)]
+    scopes: Vec<NodeId>,
+    table: BTreeMap<NodeId, Scope>,
+}
+
+impl ScopedSymbolTable {
     // Pushing a scope adds it to the scope stack
     // and defines a new key in our global symbol map
-    pub fn push_scope(&mut self, key: Span) {
-        self.scopes.push(key.clone());
-        self.table
-            .entry(key)
-            .or_insert_with(|| Scope::new(None));
+    pub fn push_scope(&mut self, key: NodeId) {
+        self.scopes.push(key);
+        self.table.entry(key).or_insert_with(|| Scope::new(None));
     }
 
-    pub fn push_scope_with_return(&mut self, key: Span, returns: Option<Type>) {
-        self.scopes.push(key.clone());
-        self.table
-            .entry(key.clone())
-            .or_insert_with(|| Scope::new(returns));
+    pub fn push_scope_with_return(&mut self, key: NodeId, returns: Option<Type>) {
+        self.scopes.push(key);
+        self.table.entry(key).or_insert_with(|| Scope::new(returns));
     }
 
     // Poping a scope removes it from the scope stack
@@ -129,12 +148,19 @@ impl ScopedSymbolTable {
     //     scope.symbols.insert(Cow::Owned(name), symbol)
     // }
 
-    // Define a symbol within the current active scope using a borrowed key
-    pub fn define(&mut self, name: &Cow<'static, str>, symbol: Symbol) -> Option<Symbol> {
+    // Define a symbol declared by `def` within the current active scope using a borrowed key
+    pub fn define<T: Into<Cow<'static, str>>>(
+        &mut self,
+        name: T,
+        symbol: Symbol,
+        def: DefId,
+    ) -> Option<Symbol> {
         debug_assert!(self.scopes.len() > 0);
+        let name = name.into();
         let key = self.scopes.last().unwrap();
         let scope = self.table.get_mut(key).unwrap();
-        scope.symbols.insert(name.clone(), symbol)
+        scope.defs.insert(name.clone(), def);
+        scope.symbols.insert(name, symbol)
     }
 
     // pub fn update_type(&mut self, name: &Cow<'static, str>, ty: Type) {
@@ -162,6 +188,27 @@ impl ScopedSymbolTable {
         None
     }
 
+    /// Lookup the declaration a name resolves to from the current
+    /// active scope as well as scopes lower in the stack
+    pub fn lookup_def(&self, name: &str) -> Option<DefId> {
+        self.scopes.iter().rev().find_map(|key| {
+            let scope = self.table.get(key).unwrap();
+            scope.defs.get(name).copied()
+        })
+    }
+
+    /// Lookup the symbol defined by the declaration a name resolves to.
+    /// Symbols with the same name from other declarations are skipped
+    pub fn lookup_resolved(&self, name: &str, def: DefId) -> Option<&Symbol> {
+        self.scopes.iter().rev().find_map(|key| {
+            let scope = self.table.get(key).unwrap();
+            match scope.defs.get(name) {
+                Some(found) if *found == def => scope.symbols.get(name),
+                _ => None,
+            }
+        })
+    }
+
     /// Look for the symbol in the local scope on
     /// the top of the stack
     pub fn lookup_current(&mut self, name: &str) -> Option<&Symbol> {
@@ -182,15 +229,18 @@ impl ScopedSymbolTable {
 
     /// Returns all the symbols available in
     /// the current local scope
-    pub fn get_scope(&mut self, key: Span) -> Option<&Scope> {
+    pub fn get_scope(&self, key: NodeId) -> Option<&Scope> {
         self.table.get(&key)
     }
 
+    /// Returns the return type of the function enclosing the current scope
     pub fn scope_return_type(&mut self) -> Option<&Type> {
         debug_assert!(self.scopes.len() > 0);
-        let key = &self.scopes.last().unwrap();
-        let scope = self.table.get_mut(key).unwrap();
-        scope.ret.as_ref()
+        let table = &self.table;
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|key| table.get(key).and_then(|scope| scope.ret.as_ref()))
     }
 }
 
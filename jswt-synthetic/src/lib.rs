@@ -1,77 +1,97 @@
-use std::borrow::Cow;
-
 use jswt_ast::*;
-use jswt_common::{Span, Type};
+use jswt_common::{Atom, NodeId, Span};
+use jswt_types::Type;
 
-pub fn function_call(
-    name: Cow<'static, str>,
-    arguments: Vec<SingleExpression>,
-    ret: Type,
-) -> SingleExpression {
+pub fn function_call(name: Atom, arguments: Vec<SingleExpression>) -> SingleExpression {
     SingleExpression::Arguments(ArgumentsExpression {
+        id: NodeId::DUMMY,
         span: Span::synthetic(),
         ident: Box::new(SingleExpression::Identifier(IdentifierExpression {
+            id: NodeId::DUMMY,
             span: Span::synthetic(),
             ident: Identifier::new(name, Span::synthetic()),
-            ty: ret.clone(),
         })),
         arguments: ArgumentsList {
             span: Span::synthetic(),
             arguments,
         },
-        ty: ret,
     })
 }
 
-pub fn i32_store(target: &'static str, offset: i32, value: SingleExpression) -> SingleExpression {
-    function_call(
-        "i32Store".into(),
-        vec![
-            SingleExpression::Additive(BinaryExpression {
-                span: Span::synthetic(),
-                left: Box::new(SingleExpression::Identifier(IdentifierExpression {
-                    span: Span::synthetic(),
-                    ident: Identifier::new(target, Span::synthetic()),
-                    ty: type_i32(),
-                })),
-                op: BinaryOperator::Plus(Span::synthetic()),
-                right: Box::new(SingleExpression::Literal(Literal::Integer(
-                    IntegerLiteral {
-                        span: Span::synthetic(),
-                        value: offset,
-                        ty: type_i32(),
-                    },
-                ))),
-                ty: type_i32(),
-            }),
-            value,
-        ],
-        type_i32(),
-    )
+pub fn i32_store(
+    target: SingleExpression,
+    offset: i32,
+    value: SingleExpression,
+) -> SingleExpression {
+    function_call("i32Store".into(), vec![address(target, offset), value])
 }
 
-pub fn i32_load(target: &'static str, offset: i32) -> SingleExpression {
-    function_call(
-        "i32Load".into(),
-        vec![SingleExpression::Additive(BinaryExpression {
+pub fn i32_load(target: SingleExpression, offset: i32) -> SingleExpression {
+    function_call("i32Load".into(), vec![address(target, offset)])
+}
+
+pub fn f32_store(
+    target: SingleExpression,
+    offset: i32,
+    value: SingleExpression,
+) -> SingleExpression {
+    function_call("f32Store".into(), vec![address(target, offset), value])
+}
+
+pub fn f32_load(target: SingleExpression, offset: i32) -> SingleExpression {
+    function_call("f32Load".into(), vec![address(target, offset)])
+}
+
+pub fn i64_store(
+    target: SingleExpression,
+    offset: i32,
+    value: SingleExpression,
+) -> SingleExpression {
+    function_call("i64Store".into(), vec![address(target, offset), value])
+}
+
+pub fn i64_load(target: SingleExpression, offset: i32) -> SingleExpression {
+    function_call("i64Load".into(), vec![address(target, offset)])
+}
+
+pub fn f64_store(
+    target: SingleExpression,
+    offset: i32,
+    value: SingleExpression,
+) -> SingleExpression {
+    function_call("f64Store".into(), vec![address(target, offset), value])
+}
+
+pub fn f64_load(target: SingleExpression, offset: i32) -> SingleExpression {
+    function_call("f64Load".into(), vec![address(target, offset)])
+}
+
+/// Address `offset` bytes past the pointer `target` evaluates to. The
+/// target is cast to a pointer so the addition is pointer arithmetic
+/// rather than an operator of the target's class
+fn address(target: SingleExpression, offset: i32) -> SingleExpression {
+    let target = SingleExpression::Cast(CastExpression {
+        id: NodeId::DUMMY,
+        span: Span::synthetic(),
+        expression: Box::new(target),
+        target: TypeAnnotation {
             span: Span::synthetic(),
-            left: Box::new(SingleExpression::Identifier(IdentifierExpression {
+            ty: type_ptr(),
+        },
+    });
+    SingleExpression::Additive(BinaryExpression {
+        id: NodeId::DUMMY,
+        span: Span::synthetic(),
+        left: Box::new(target),
+        op: BinaryOperator::Plus(Span::synthetic()),
+        right: Box::new(SingleExpression::Literal(Literal::Integer(
+            IntegerLiteral {
+                id: NodeId::DUMMY,
                 span: Span::synthetic(),
-                ident: Identifier::new(target, Span::synthetic()),
-                ty: type_ptr(),
-            })),
-            op: BinaryOperator::Plus(Span::synthetic()),
-            right: Box::new(SingleExpression::Literal(Literal::Integer(
-                IntegerLiteral {
-                    span: Span::synthetic(),
-                    value: offset,
-                    ty: type_i32(),
-                },
-            ))),
-            ty: type_i32(),
-        })],
-        type_i32(),
-    )
+                value: offset as i64,
+            },
+        ))),
+    })
 }
 
 pub fn malloc(size: usize) -> SingleExpression {
@@ -79,53 +99,51 @@ pub fn malloc(size: usize) -> SingleExpression {
         "malloc".into(),
         vec![SingleExpression::Literal(Literal::Integer(
             IntegerLiteral {
+                id: NodeId::DUMMY,
                 span: Span::synthetic(),
-                value: size as i32,
-                ty: type_ptr(),
+                value: size as i64,
             },
         ))],
-        type_ptr(),
     )
 }
 
-pub fn variable_decl_stmt(
-    ident: Cow<'static, str>,
-    expression: SingleExpression,
-) -> StatementElement {
+pub fn variable_decl_stmt(ident: Atom, expression: SingleExpression) -> StatementElement {
     StatementElement::Variable(VariableStatement {
+        id: NodeId::DUMMY,
         span: Span::synthetic(),
         modifier: VariableModifier::Const(Span::synthetic()),
         target: AssignableElement::Identifier(Identifier {
             span: Span::synthetic(),
             value: ident,
         }),
-        expression,
+        expression: Some(expression),
         type_annotation: None,
     })
 }
 
 pub fn return_stmt(expression: SingleExpression) -> StatementElement {
     StatementElement::Return(ReturnStatement {
+        id: NodeId::DUMMY,
         span: Span::synthetic(),
-        expression,
+        expression: Some(expression),
     })
 }
 
-pub fn ident_exp(ident: Cow<'static, str>) -> SingleExpression {
+pub fn ident_exp(ident: Atom) -> SingleExpression {
     SingleExpression::Identifier(IdentifierExpression {
+        id: NodeId::DUMMY,
         span: Span::synthetic(),
         ident: Identifier {
             span: Span::synthetic(),
             value: ident,
         },
-        ty: type_ptr(),
     })
 }
 
 pub fn type_ptr() -> Type {
-    Type::Binding("ptr".into())
+    Type::PTR
 }
 
 pub fn type_i32() -> Type {
-    Type::Binding("i32".into())
+    Type::I32
 }
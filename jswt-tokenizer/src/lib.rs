@@ -46,6 +46,7 @@ macro_rules! directives {
 #[derive(Debug, PartialEq)]
 enum DirectiveType {
     Import,
+    DocComment,
     Skip,
 }
 
@@ -67,6 +68,9 @@ lazy_static! {
         // import "./test.jswt". Group 1 is the unquoted path
         r#"^\bimport\b\s+"((?:/)?(?:[^"]+(?:/)?)+)""# => DirectiveType::Import,
         r"^\s+" => DirectiveType::Skip,
+        // Doc comments are kept for the token that follows them.
+        // An empty /**/ is an ordinary comment
+        r"(?s)^/\*\*[^/].*?\*/" => DirectiveType::DocComment,
         // Skip comments
         //https://docs.rs/regex/latest/regex/#grouping-and-flags
         r"(?s)^/\*.*?\*/" => DirectiveType::Skip,
@@ -89,11 +93,15 @@ lazy_static! {
         r"^\breturn\b" => TokenType::Return,
         r"^\blet\b" => TokenType::Let,
         r"^\bconst\b" => TokenType::Const,
+        r"^\breadonly\b" => TokenType::Readonly,
         r"^\bwhile\b" => TokenType::While,
         r"^\bclass\b" => TokenType::Class,
         r"^\bconstructor\b" => TokenType::Constructor,
         r"^\bthis\b" => TokenType::This,
         r"^\bnew\b" => TokenType::New,
+        r"^\bas\b" => TokenType::As,
+        r"^\bbreak\b" => TokenType::Break,
+        r"^\bcontinue\b" => TokenType::Continue,
 
         // Multi character alternatives
         r"^\+\+" => TokenType::PlusPlus,
@@ -138,6 +146,16 @@ lazy_static! {
     ];
 }
 
+/// An `import "..."` directive found in a source
+#[derive(Debug, Clone, PartialEq)]
+pub struct Import {
+    pub span: Span,
+    /// Path as it's written in the directive
+    pub name: String,
+    /// Path relative to the importing file
+    pub path: PathBuf,
+}
+
 pub struct Tokenizer {
     /// We're using a vec here as a queue of sources
     /// to be tokenized. the current source being parsed should be
@@ -149,6 +167,15 @@ pub struct Tokenizer {
     // To compute a module name for the source file
     sources_root: Option<PathBuf>,
     module_prefix: Option<String>,
+    // Whether a line terminator was skipped since the last token
+    newline_before: bool,
+    // Doc comment skipped since the last token
+    doc: Option<Span>,
+    // Imported sources are tokenized after the importing source
+    // unless the caller wants to load them itself
+    follow_imports: bool,
+    // Every import found so far
+    imports: Vec<Import>,
 }
 
 impl Default for Tokenizer {
@@ -164,6 +191,10 @@ impl Tokenizer {
             errors: vec![],
             sources_root: None,
             module_prefix: None,
+            newline_before: false,
+            doc: None,
+            follow_imports: true,
+            imports: vec![],
         }
     }
 
@@ -185,6 +216,9 @@ impl Tokenizer {
         // our tokenization stack
         if !source.has_more_content() {
             self.dequeue_source();
+            // The next file always starts on a new line
+            self.newline_before = true;
+            self.doc = None;
             return Some(Token::new(
                 Span::new(source.path.clone(), source.module.clone(), offset, offset),
                 TokenType::Eof,
@@ -212,9 +246,36 @@ impl Tokenizer {
                         ));
 
                         // Push the source where we found the import to the queue
-                        self.enqueue_source_file(&relative_source_path);
+                        if self.follow_imports {
+                            self.enqueue_source_file(&relative_source_path);
+                        }
+                        self.imports.push(Import {
+                            span: Span::new(
+                                source.path.clone(),
+                                source.module.clone(),
+                                offset,
+                                offset + match_text.len(),
+                            ),
+                            name: import_path.to_owned(),
+                            path: relative_source_path,
+                        });
+                    }
+                    DirectiveType::DocComment => {
+                        if match_text.contains('\n') {
+                            self.newline_before = true;
+                        }
+                        self.doc = Some(Span::new(
+                            source.path.clone(),
+                            source.module.clone(),
+                            offset,
+                            offset + match_text.len(),
+                        ));
+                    }
+                    DirectiveType::Skip => {
+                        if match_text.contains('\n') {
+                            self.newline_before = true;
+                        }
                     }
-                    DirectiveType::Skip => {}
                 }
 
                 // Skip the tokenizer directive by advancing the cursor.
@@ -229,7 +290,7 @@ impl Tokenizer {
                 let len = res.as_str().len();
                 // Advance cursor based on match
                 source.advance_cursor(len);
-                let token = Token::new(
+                let mut token = Token::new(
                     Span::new(
                         source.path.clone(),
                         source.module.clone(),
@@ -238,6 +299,8 @@ impl Tokenizer {
                     ),
                     rule.token_type,
                 );
+                token.newline_before = std::mem::take(&mut self.newline_before);
+                token.doc = self.doc.take();
                 return Some(token);
             }
         }
@@ -321,6 +384,17 @@ impl Tokenizer {
         self.module_prefix = prefix;
     }
 
+    /// When disabled imports are only recorded, leaving it
+    /// to the caller to load the imported sources
+    pub fn set_follow_imports(&mut self, follow_imports: bool) {
+        self.follow_imports = follow_imports;
+    }
+
+    /// Imports found so far
+    pub fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+
     fn dequeue_source(&mut self) {
         self.sources.pop();
     }
@@ -581,4 +655,55 @@ mod test {
         assert!(tokenizer.errors().is_empty());
         assert_debug_snapshot!(actual);
     }
+
+    #[test]
+    fn test_tokens_track_preceding_line_terminators() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.enqueue_source_str(
+            "test_tokens_track_preceding_line_terminators",
+            "a b\nc /* \n */ d // e\nf",
+        );
+        let actual: Vec<_> = tokenizer
+            .tokenize()
+            .iter()
+            .map(|token| token.newline_before)
+            .collect();
+        assert_eq!(actual, vec![false, false, true, true, true, false]);
+    }
+
+    #[test]
+    fn test_doc_comments_are_attached_to_the_next_token() {
+        let mut tokenizer = Tokenizer::default();
+        let source = "/** a */ let /* b */ c /**/ d /**\n * e\n */ // f\n g";
+        tokenizer.enqueue_source_str("test_doc_comments_are_attached_to_the_next_token", source);
+        let actual: Vec<_> = tokenizer
+            .tokenize()
+            .iter()
+            .map(|token| token.doc.as_ref().map(|doc| &source[doc.start..doc.end]))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![Some("/** a */"), None, None, Some("/**\n * e\n */"), None]
+        );
+    }
+
+    #[test]
+    fn test_imports_are_recorded_without_following_them() {
+        let mut tokenizer = Tokenizer::default();
+        tokenizer.set_follow_imports(false);
+        tokenizer.enqueue_source_str(
+            "/test_imports_are_recorded/main.jswt",
+            "import \"./missing.jswt\"; let a = 1;",
+        );
+        let actual = tokenizer.tokenize();
+        assert!(tokenizer.errors().is_empty());
+        // Only the tokens of the importing file and its Eof
+        assert_eq!(actual.len(), 7);
+        let imports: Vec<_> = tokenizer.imports().iter().map(|i| &i.path).collect();
+        assert_eq!(
+            imports,
+            vec![&PathBuf::from("/test_imports_are_recorded/./missing.jswt")]
+        );
+        assert_eq!(tokenizer.imports()[0].name, "./missing.jswt");
+    }
 }
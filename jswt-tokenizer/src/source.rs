@@ -1,19 +1,22 @@
 use jswt_common::fs;
-use std::{borrow::Cow, cell::Cell};
+use std::{borrow::Cow, cell::Cell, sync::Arc};
 
 /// Representation of a tokenizable consumable source
 pub struct Source {
     pub path: Cow<'static, str>,
     pub module: Cow<'static, str>,
+    content: Arc<str>,
     cursor: Cell<usize>,
 }
 
 impl Source {
     /// Creates a new source with a cursor at the begining of the file
     pub fn new(path: Cow<'static, str>, module: Cow<'static, str>) -> Self {
+        let content = fs::read_to_string(&path);
         Self {
             path,
             module,
+            content,
             cursor: Cell::new(0),
         }
     }
@@ -21,15 +24,13 @@ impl Source {
     /// Checks if the cursor has reached the
     /// end of the source
     pub fn has_more_content(&self) -> bool {
-        let content = fs::read_to_string(&self.path);
-        self.cursor.get() < content.len()
+        self.cursor.get() < self.content.len()
     }
 
     /// Returns the rest of the source file starting
     /// from the cursors offset
     pub fn content_from_cursor(&self) -> &str {
-        let content = fs::read_to_string(&self.path);
-        &content[self.cursor()..]
+        &self.content[self.cursor()..]
     }
 
     /// Advances the cursor by the given amount
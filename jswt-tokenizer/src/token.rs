@@ -5,11 +5,21 @@ use std::fmt::Display;
 pub struct Token {
     pub kind: TokenType,
     pub span: Span,
+    /// True if a line terminator separates this token from the previous one.
+    /// Used for automatic semicolon insertion
+    pub newline_before: bool,
+    /// Span of the `/** */` doc comment before this token, if any
+    pub doc: Option<Span>,
 }
 
 impl Token {
     pub fn new(span: Span, kind: TokenType) -> Self {
-        Token { kind, span }
+        Token {
+            kind,
+            span,
+            newline_before: false,
+            doc: None,
+        }
     }
 }
 
@@ -65,11 +75,15 @@ pub enum TokenType {
     Return,
     Let,
     Const,
+    Readonly,
     While,
     Class,
     Constructor,
     This,
     New,
+    As,
+    Break,
+    Continue,
 
     // Other
     WhiteSpace,
@@ -92,7 +106,7 @@ impl Display for TokenType {
             TokenType::PlusPlus => f.write_str("++"),
             TokenType::Minus => f.write_str("-"),
             TokenType::MinusMinus => f.write_str("--"),
-            TokenType::Comma => f.write_str("),"),
+            TokenType::Comma => f.write_str(","),
             TokenType::Dot => f.write_str("."),
             TokenType::Semi => f.write_str(";"),
             TokenType::Colon => f.write_str(":"),
@@ -118,21 +132,25 @@ impl Display for TokenType {
             TokenType::Return => f.write_str("return"),
             TokenType::Let => f.write_str("let"),
             TokenType::Const => f.write_str("const"),
+            TokenType::Readonly => f.write_str("readonly"),
             TokenType::While => f.write_str("while"),
             TokenType::Class => f.write_str("class"),
             TokenType::Constructor => f.write_str("constructor"),
             TokenType::This => f.write_str("this"),
             TokenType::New => f.write_str("new"),
+            TokenType::As => f.write_str("as"),
+            TokenType::Break => f.write_str("break"),
+            TokenType::Continue => f.write_str("continue"),
             TokenType::Identifier => f.write_str("identifier"),
             TokenType::String => f.write_str("string"),
             TokenType::Integer => f.write_str("integer"),
             TokenType::Float => f.write_str("float"),
             TokenType::HexInteger => f.write_str("hex literal"),
+            TokenType::Eof => f.write_str("end of file"),
             // Non punctuation. We shouldn't be trying
             // to serialize these
             TokenType::WhiteSpace => todo!(),
             TokenType::Comment => todo!(),
-            TokenType::Eof => todo!(),
         }
     }
 }
@@ -1,6 +1,11 @@
+mod table;
+
+use serde::Serialize;
 use std::{borrow::Cow, fmt::Display};
 
-#[derive(Debug, Clone, PartialEq)]
+pub use table::TypeTable;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum Type {
     Primitive(PrimitiveType),
     Object(ObjectType),
@@ -9,22 +14,25 @@ pub enum Type {
     Unknown,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum ObjectType {
     Array(Box<Type>),
     String,
     Reference(Cow<'static, str>),
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum PrimitiveType {
     I32,
     U32,
+    I64,
     F32,
+    F64,
     Boolean,
+    Ptr,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct FunctionType {
     pub params: Vec<Type>,
     pub returns: Box<Type>,
@@ -35,13 +43,116 @@ impl Display for PrimitiveType {
         match self {
             PrimitiveType::I32 => f.write_str("i32"),
             PrimitiveType::U32 => f.write_str("u32"),
+            PrimitiveType::I64 => f.write_str("i64"),
             PrimitiveType::F32 => f.write_str("f32"),
+            PrimitiveType::F64 => f.write_str("f64"),
             PrimitiveType::Boolean => f.write_str("boolean"),
+            PrimitiveType::Ptr => f.write_str("ptr"),
+        }
+    }
+}
+
+impl Display for ObjectType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ObjectType::Array(inner) => write!(f, "{}[]", inner),
+            ObjectType::String => f.write_str("string"),
+            ObjectType::Reference(name) => f.write_str(name),
+        }
+    }
+}
+
+impl Display for FunctionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("(")?;
+        for (i, param) in self.params.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", param)?;
+        }
+        write!(f, ") => {}", self.returns)
+    }
+}
+
+impl Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Primitive(ty) => write!(f, "{}", ty),
+            Type::Object(ty) => write!(f, "{}", ty),
+            Type::Function(ty) => write!(f, "{}", ty),
+            Type::Void => f.write_str("void"),
+            Type::Unknown => f.write_str("Unknown"),
         }
     }
 }
 
 impl Type {
+    pub const I32: Type = Type::Primitive(PrimitiveType::I32);
+    pub const U32: Type = Type::Primitive(PrimitiveType::U32);
+    pub const I64: Type = Type::Primitive(PrimitiveType::I64);
+    pub const F32: Type = Type::Primitive(PrimitiveType::F32);
+    pub const F64: Type = Type::Primitive(PrimitiveType::F64);
+    pub const BOOLEAN: Type = Type::Primitive(PrimitiveType::Boolean);
+    pub const PTR: Type = Type::Primitive(PrimitiveType::Ptr);
+    pub const STRING: Type = Type::Object(ObjectType::String);
+
+    /// Resolves a type from the name it is referred to by in source.
+    /// Anything that isn't a builtin type is a reference to a class.
+    pub fn from_name<T: Into<Cow<'static, str>>>(name: T) -> Type {
+        let name = name.into();
+        match name.as_ref() {
+            "i32" => Type::I32,
+            "u32" => Type::U32,
+            "i64" => Type::I64,
+            "f32" => Type::F32,
+            "f64" => Type::F64,
+            "boolean" => Type::BOOLEAN,
+            "ptr" => Type::PTR,
+            "string" => Type::STRING,
+            "void" => Type::Void,
+            _ => Type::Object(ObjectType::Reference(name)),
+        }
+    }
+
+    /// Creates an array of the given element type
+    pub fn array(element: Type) -> Type {
+        Type::Object(ObjectType::Array(Box::new(element)))
+    }
+
+    /// The name of the class binding that implements this type, if any.
+    /// Primitives are backed by runtime classes of the same name.
+    pub fn binding_name(&self) -> Option<Cow<'static, str>> {
+        match self {
+            Type::Primitive(ty) => Some(ty.to_string().into()),
+            Type::Object(ObjectType::Reference(name)) => Some(name.clone()),
+            Type::Object(ObjectType::String) => Some("string".into()),
+            Type::Void => Some("void".into()),
+            _ => None,
+        }
+    }
+
+    /// Returns true if a value of this type can be used where a value of
+    /// type `target` is expected. Unknown types are unresolved and are
+    /// assumed to be compatible with everything to avoid cascading errors.
+    pub fn is_assignable_to(&self, target: &Type) -> bool {
+        match (self, target) {
+            (Type::Unknown, _) | (_, Type::Unknown) => true,
+            (Type::Object(ObjectType::Array(a)), Type::Object(ObjectType::Array(b))) => {
+                a.is_assignable_to(b)
+            }
+            (Type::Function(a), Type::Function(b)) => {
+                a.params.len() == b.params.len()
+                    && a.params
+                        .iter()
+                        .zip(&b.params)
+                        .all(|(a, b)| b.is_assignable_to(a))
+                    && a.returns.is_assignable_to(&b.returns)
+            }
+            (a, b) => a == b,
+        }
+    }
+
     /// Returns `true` if the type is [`Function`].
     ///
     /// [`Function`]: Type::Function
@@ -55,4 +166,66 @@ impl Type {
     pub fn is_object(&self) -> bool {
         matches!(self, Self::Object(..))
     }
+
+    /// Returns `true` if the type is [`Unknown`].
+    ///
+    /// [`Unknown`]: Type::Unknown
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Self::Unknown)
+    }
+
+    /// Number of bytes a value of the type takes up in linear memory.
+    /// Objects are held as pointers to their data.
+    pub fn size(&self) -> usize {
+        match self {
+            Type::Primitive(PrimitiveType::I64) | Type::Primitive(PrimitiveType::F64) => 8,
+            _ => 4,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_types_have_sizes() {
+        assert_eq!(Type::I32.size(), 4);
+        assert_eq!(Type::F32.size(), 4);
+        assert_eq!(Type::I64.size(), 8);
+        assert_eq!(Type::F64.size(), 8);
+        assert_eq!(Type::from_name("Point").size(), 4);
+    }
+
+    #[test]
+    fn test_types_resolve_from_names() {
+        assert_eq!(Type::from_name("i32"), Type::I32);
+        assert_eq!(Type::from_name("i64"), Type::I64);
+        assert_eq!(Type::from_name("f64"), Type::F64);
+        assert_eq!(Type::from_name("void"), Type::Void);
+        assert_eq!(
+            Type::from_name("Point"),
+            Type::Object(ObjectType::Reference("Point".into()))
+        );
+    }
+
+    #[test]
+    fn test_types_display_as_source_names() {
+        assert_eq!(Type::from_name("ptr").to_string(), "ptr");
+        assert_eq!(Type::array(Type::array(Type::F32)).to_string(), "f32[][]");
+        let function = Type::Function(FunctionType {
+            params: vec![Type::I32, Type::BOOLEAN],
+            returns: Box::new(Type::Void),
+        });
+        assert_eq!(function.to_string(), "(i32, boolean) => void");
+    }
+
+    #[test]
+    fn test_assignability() {
+        assert!(Type::I32.is_assignable_to(&Type::I32));
+        assert!(Type::Unknown.is_assignable_to(&Type::F32));
+        assert!(!Type::I32.is_assignable_to(&Type::F32));
+        assert!(Type::array(Type::I32).is_assignable_to(&Type::array(Type::Unknown)));
+        assert!(!Type::array(Type::I32).is_assignable_to(&Type::array(Type::F32)));
+    }
 }
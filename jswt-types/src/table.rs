@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+
+use jswt_common::NodeId;
+
+use crate::Type;
+
+/// Side table of the types inferred for expressions during type checking.
+/// Later passes look types up by the id of the node rather than reading
+/// them off the tree.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TypeTable {
+    // BTree map to enforce order in tests
+    types: BTreeMap<NodeId, Type>,
+}
+
+impl TypeTable {
+    pub fn insert(&mut self, id: NodeId, ty: Type) {
+        self.types.insert(id, ty);
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&Type> {
+        self.types.get(&id)
+    }
+
+    /// Type of the node, or unknown if it was never typed
+    pub fn ty(&self, id: NodeId) -> Type {
+        self.get(id).cloned().unwrap_or(Type::Unknown)
+    }
+}
@@ -19,12 +19,39 @@ pub enum Instruction {
     I32And(Box<Instruction>, Box<Instruction>),
     I32Or(Box<Instruction>, Box<Instruction>),
     I32Xor(Box<Instruction>, Box<Instruction>),
+    I32Eqz(Box<Instruction>),
     I32Gt(Box<Instruction>, Box<Instruction>),
     I32Ge(Box<Instruction>, Box<Instruction>),
     I32Lt(Box<Instruction>, Box<Instruction>),
     I32Le(Box<Instruction>, Box<Instruction>),
     I32Store(Box<Instruction>, Box<Instruction>),
     I32Load(Box<Instruction>),
+    I64Const(i64),
+    I64Sub(Box<Instruction>, Box<Instruction>),
+    I64Ne(Box<Instruction>, Box<Instruction>),
+    F32Const(f32),
+    F32Neg(Box<Instruction>),
+    F64Const(f64),
+    F64Neg(Box<Instruction>),
+    I32TruncF32S(Box<Instruction>),
+    I32TruncF32U(Box<Instruction>),
+    I32TruncF64S(Box<Instruction>),
+    I32TruncF64U(Box<Instruction>),
+    I32WrapI64(Box<Instruction>),
+    I64ExtendI32S(Box<Instruction>),
+    I64ExtendI32U(Box<Instruction>),
+    I64TruncF32S(Box<Instruction>),
+    I64TruncF64S(Box<Instruction>),
+    F32ConvertI32S(Box<Instruction>),
+    F32ConvertI32U(Box<Instruction>),
+    F32ConvertI64S(Box<Instruction>),
+    F32DemoteF64(Box<Instruction>),
+    F64ConvertI32S(Box<Instruction>),
+    F64ConvertI32U(Box<Instruction>),
+    F64ConvertI64S(Box<Instruction>),
+    F64PromoteF32(Box<Instruction>),
+    I32ReinterpretF32(Box<Instruction>),
+    F32ReinterpretI32(Box<Instruction>),
     Block(usize, Vec<Instruction>),
     Return(Box<Instruction>),
     If(Box<Instruction>, Vec<Instruction>, Vec<Instruction>),
@@ -78,6 +105,7 @@ impl From<&Instruction> for String {
             Instruction::I32And(lhs, rhs) => format!("(i32.and {} {})", *lhs, *rhs),
             Instruction::I32Or(lhs, rhs) => format!("(i32.or {} {})", *lhs, *rhs),
             Instruction::I32Xor(lhs, rhs) => format!("(i32.xor {} {})", *lhs, *rhs),
+            Instruction::I32Eqz(value) => format!("(i32.eqz {})", *value),
             Instruction::Return(instruction) => {
                 // Set the synthetic value and break into the function block scope
                 format!("(local.set $return {}) (br $blk0)", *instruction)
@@ -122,6 +150,32 @@ impl From<&Instruction> for String {
             Instruction::Complex(args) => args.to_string(),
             Instruction::I32Store(loc, value) => format!("(i32.store {} {})", *loc, *value),
             Instruction::I32Load(_) => todo!(),
+            Instruction::I64Const(value) => format!("(i64.const {})", value),
+            Instruction::I64Sub(lhs, rhs) => format!("(i64.sub {} {})", *lhs, *rhs),
+            Instruction::I64Ne(lhs, rhs) => format!("(i64.ne {} {})", *lhs, *rhs),
+            Instruction::F32Const(value) => format!("(f32.const {})", value),
+            Instruction::F32Neg(value) => format!("(f32.neg {})", *value),
+            Instruction::F64Const(value) => format!("(f64.const {})", value),
+            Instruction::F64Neg(value) => format!("(f64.neg {})", *value),
+            Instruction::I32TruncF32S(value) => format!("(i32.trunc_f32_s {})", *value),
+            Instruction::I32TruncF32U(value) => format!("(i32.trunc_f32_u {})", *value),
+            Instruction::I32TruncF64S(value) => format!("(i32.trunc_f64_s {})", *value),
+            Instruction::I32TruncF64U(value) => format!("(i32.trunc_f64_u {})", *value),
+            Instruction::I32WrapI64(value) => format!("(i32.wrap_i64 {})", *value),
+            Instruction::I64ExtendI32S(value) => format!("(i64.extend_i32_s {})", *value),
+            Instruction::I64ExtendI32U(value) => format!("(i64.extend_i32_u {})", *value),
+            Instruction::I64TruncF32S(value) => format!("(i64.trunc_f32_s {})", *value),
+            Instruction::I64TruncF64S(value) => format!("(i64.trunc_f64_s {})", *value),
+            Instruction::F32ConvertI32S(value) => format!("(f32.convert_i32_s {})", *value),
+            Instruction::F32ConvertI32U(value) => format!("(f32.convert_i32_u {})", *value),
+            Instruction::F32ConvertI64S(value) => format!("(f32.convert_i64_s {})", *value),
+            Instruction::F32DemoteF64(value) => format!("(f32.demote_f64 {})", *value),
+            Instruction::F64ConvertI32S(value) => format!("(f64.convert_i32_s {})", *value),
+            Instruction::F64ConvertI32U(value) => format!("(f64.convert_i32_u {})", *value),
+            Instruction::F64ConvertI64S(value) => format!("(f64.convert_i64_s {})", *value),
+            Instruction::F64PromoteF32(value) => format!("(f64.promote_f32 {})", *value),
+            Instruction::I32ReinterpretF32(value) => format!("(i32.reinterpret_f32 {})", *value),
+            Instruction::F32ReinterpretI32(value) => format!("(f32.reinterpret_i32 {})", *value),
         }
     }
 }
@@ -121,12 +121,7 @@ impl Module {
         wat += " (memory $0 1)";
 
         for function in self.functions.iter() {
-            wat += "(";
-            wat += &self.type_signature(function.type_idx, function.name.as_ref());
-            for isr in &function.instructions {
-                wat += &String::from(isr);
-            }
-            wat += ")";
+            wat += &self.function_wat(function);
         }
 
         // Generate Export Definitions
@@ -151,19 +146,37 @@ impl Module {
         format_wat(wat)
     }
 
+    /// Generates the definition of a single function of the module
+    pub fn function_as_wat(&self, function: &Function, minified: bool) -> String {
+        let wat = self.function_wat(function);
+        if minified {
+            return wat;
+        }
+        format_wat(wat)
+    }
+
+    fn function_wat(&self, function: &Function) -> String {
+        let mut wat = "(".to_string();
+        wat += &self.type_signature(function.type_idx, function.name.as_ref());
+        for isr in &function.instructions {
+            wat += &String::from(isr);
+        }
+        wat += ")";
+        wat
+    }
+
     fn type_signature(&self, type_idx: usize, name: &str) -> String {
         let mut wat = format!("func ${} ", name);
         let ty = &self.types[type_idx];
         // Generate Type Definition
         // function(p1: i32, p2: i32) : i32
         // (param $p1 i32) (param $p2 i32) (result i32)
-        for (name, _) in &ty.params {
-            // TODO - support more than just i32
-            wat += &format!("(param ${} i32)", name);
+        for (name, ty) in &ty.params {
+            wat += &format!("(param ${} {})", name, ty);
         }
 
-        if ty.ret.is_some() {
-            wat += "(result i32)";
+        if let Some(ret) = ty.ret {
+            wat += &format!("(result {})", ret);
         }
 
         wat
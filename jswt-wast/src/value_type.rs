@@ -1,12 +1,18 @@
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum ValueType {
     I32,
+    I64,
+    F32,
+    F64,
 }
 
 impl std::fmt::Display for ValueType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ValueType::I32 => f.write_str("i32"),
+            ValueType::I64 => f.write_str("i64"),
+            ValueType::F32 => f.write_str("f32"),
+            ValueType::F64 => f.write_str("f64"),
         }
     }
 }